@@ -0,0 +1,106 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use sportgems::{climb, fit_reader, gem_finder, synthetic, velocity};
+
+const DESIRED_DISTANCE: f64 = 1_000.;
+// synthetic 1 Hz activities of 1h, 10h and 48h
+const DURATIONS: [(&str, u32); 3] = [("1h", 3_600), ("10h", 36_000), ("48h", 172_800)];
+
+fn bench_parse_fit(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_fit");
+    group.sample_size(10);
+    for (name, duration) in DURATIONS {
+        let path = std::env::temp_dir().join(format!("sportgems_bench_{}.fit", name));
+        synthetic::write_fit(&synthetic::generate_activity(duration), &path).unwrap();
+        let path = path.to_str().unwrap().to_string();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &path, |b, path| {
+            b.iter(|| fit_reader::parse_fit(black_box(path)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_compute_vector_of_distances(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_vector_of_distances");
+    for (name, duration) in DURATIONS {
+        let activity = synthetic::generate_activity(duration);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &activity,
+            |b, activity| {
+                b.iter_batched_ref(
+                    || {
+                        gem_finder::InputData::new(
+                            DESIRED_DISTANCE,
+                            activity.coordinates.clone(),
+                            activity.times.clone(),
+                            None,
+                            None,
+                        )
+                        .unwrap()
+                    },
+                    |input_data| input_data.compute_vector_of_distances(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_find_fastest_section(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_fastest_section");
+    group.sample_size(10);
+    for (name, duration) in DURATIONS {
+        let activity = synthetic::generate_activity(duration);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &activity,
+            |b, activity| {
+                b.iter(|| {
+                    velocity::find_fastest_section(
+                        black_box(DESIRED_DISTANCE),
+                        activity.coordinates.clone(),
+                        activity.times.clone(),
+                        None,
+                    )
+                    .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn bench_find_best_climb_section(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_best_climb_section");
+    group.sample_size(10);
+    for (name, duration) in DURATIONS {
+        let activity = synthetic::generate_activity(duration);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &activity,
+            |b, activity| {
+                b.iter(|| {
+                    climb::find_best_climb_section(
+                        black_box(DESIRED_DISTANCE),
+                        activity.coordinates.clone(),
+                        activity.times.clone(),
+                        activity.altitudes.clone(),
+                        None,
+                    )
+                    .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parse_fit,
+    bench_compute_vector_of_distances,
+    bench_find_fastest_section,
+    bench_find_best_climb_section
+);
+criterion_main!(benches);
@@ -0,0 +1,128 @@
+#ifndef SPORTGEMS_H
+#define SPORTGEMS_H
+
+/* Generated by cbindgen from src/capi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define SPORTGEMS_DEFAULT_TOLERANCE 0.01
+
+/**
+ * Error codes returned by all functions, mirroring `InputDataError`.
+ */
+typedef enum SportgemsError {
+  SPORTGEMS_ERROR_OK = 0,
+  SPORTGEMS_ERROR_INCONSISTENT_LENGTH = 1,
+  SPORTGEMS_ERROR_TOO_FEW_DATA_POINTS = 2,
+  SPORTGEMS_ERROR_DISTANCE_TOO_SMALL = 3,
+  SPORTGEMS_ERROR_NO_SECTION_FOUND = 4,
+  SPORTGEMS_ERROR_INVALID_DESIRED_DISTANCE = 5,
+  SPORTGEMS_ERROR_INVALID_FIT_FILE = 6,
+  /**
+   * A required pointer argument was null.
+   */
+  SPORTGEMS_ERROR_NULL_POINTER = 7,
+  SPORTGEMS_ERROR_FILE_NOT_READABLE = 8,
+} SportgemsError;
+
+/**
+ * Opaque handle of a parsed activity.
+ */
+typedef struct SportgemsActivity SportgemsActivity;
+
+/**
+ * Resulting section of a search, `value` holds the velocity in m/s for the fastest section and
+ * the climb in m/min for the best climb section.
+ */
+typedef struct SportgemsSection {
+  uint32_t start;
+  uint32_t end;
+  double value;
+} SportgemsSection;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses the fit file given as `len` bytes at `data` into a new activity, which is written to
+ * `activity`.
+ *
+ * # Safety
+ * `data` must point to `len` readable bytes and `activity` to a writable pointer.
+ */
+enum SportgemsError sportgems_activity_from_fit(const uint8_t *data,
+                                                uintptr_t len,
+                                                struct SportgemsActivity **activity);
+
+/**
+ * Creates a new activity from `len` data points, `altitudes` may be null in case no altitude
+ * data is available. The arrays are copied.
+ *
+ * # Safety
+ * All non-null arrays must hold `len` values and `activity` must point to a writable pointer.
+ */
+enum SportgemsError sportgems_activity_from_arrays(const double *times,
+                                                   const double *latitudes,
+                                                   const double *longitudes,
+                                                   const double *altitudes,
+                                                   uintptr_t len,
+                                                   struct SportgemsActivity **activity);
+
+/**
+ * Returns the number of data points of the activity, 0 for null.
+ *
+ * # Safety
+ * `activity` must be null or a pointer returned by one of the constructors.
+ */
+uintptr_t sportgems_activity_len(const struct SportgemsActivity *activity);
+
+/**
+ * Searches the fastest section of `desired_distance` meter and writes it to `section`. Pass
+ * `SPORTGEMS_DEFAULT_TOLERANCE` as `tolerance` for the default.
+ *
+ * # Safety
+ * `activity` must be a pointer returned by one of the constructors and `section` must be
+ * writable.
+ */
+enum SportgemsError sportgems_find_fastest_section(const struct SportgemsActivity *activity,
+                                                   double desired_distance,
+                                                   double tolerance,
+                                                   struct SportgemsSection *section);
+
+/**
+ * Searches the best climb section of `desired_distance` meter and writes it to `section`. Pass
+ * `SPORTGEMS_DEFAULT_TOLERANCE` as `tolerance` for the default.
+ *
+ * # Safety
+ * `activity` must be a pointer returned by one of the constructors and `section` must be
+ * writable.
+ */
+enum SportgemsError sportgems_find_best_climb_section(const struct SportgemsActivity *activity,
+                                                      double desired_distance,
+                                                      double tolerance,
+                                                      struct SportgemsSection *section);
+
+/**
+ * Releases an activity, passing null is a no-op.
+ *
+ * # Safety
+ * `activity` must be null or a pointer returned by one of the constructors, which has not been
+ * freed yet.
+ */
+void sportgems_activity_free(struct SportgemsActivity *activity);
+
+/**
+ * Returns a static, null terminated description of the given error code, unknown codes are
+ * described as "Unknown error.".
+ */
+const char *sportgems_error_message(int error);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SPORTGEMS_H */
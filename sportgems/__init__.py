@@ -2,18 +2,37 @@ from .sportgems import (
     # velocity
     find_fastest_section,
     find_fastest_section_in_fit,
+    find_fastest_section_from_distances,
     
     # climb
     find_best_climb_section,
     find_best_climb_section_in_fit,
+    find_best_climb_section_from_distances,
+    total_ascent_descent,
+
+    # outliers
+    detect_outliers,
+    remove_outliers,
+    remove_coordinate_outliers,
+
+    # timestamps
+    check_times,
+    repair_times,
+    split_segments,
+    quality_report,
     
     # general fit parsing
     parse_fit_data,
 
+    # batch analysis
+    analyze_files,
+
     # classes
     FastestSection,
     ClimbSection,
     FitData,
+    FileAnalysis,
+    Activity,
 
     # exceptions
     DistanceTooSmallException,
@@ -21,20 +40,39 @@ from .sportgems import (
     TooFewDataPointsException,
     NoSectionFoundException,
     InvalidDesiredDistanceException,
+    InvalidFitFileException,
+    FileNotReadableException,
 )
+from . import aio
 
 __all__ = [
     'find_fastest_section',
     'find_fastest_section_in_fit',
+    'find_fastest_section_from_distances',
     'find_best_climb_section',
     'find_best_climb_section_in_fit',
+    'find_best_climb_section_from_distances',
+    'total_ascent_descent',
+    'detect_outliers',
+    'remove_outliers',
+    'remove_coordinate_outliers',
+    'check_times',
+    'repair_times',
+    'split_segments',
+    'quality_report',
     'parse_fit_data',
+    'analyze_files',
     'FastestSection',
     'ClimbSection',
     'FitData',
+    'FileAnalysis',
+    'Activity',
     'DistanceTooSmallException',
     'InconsistentLengthException',
     'TooFewDataPointsException',
     'NoSectionFoundException',
     'InvalidDesiredDistanceException',
+    'InvalidFitFileException',
+    'FileNotReadableException',
+    'aio',
 ]
@@ -1,49 +1,421 @@
-from typing import List, Tuple
+from typing import Any, Dict, List, Optional, Tuple, Union
 
 TOLERANCE = 0.01
+DISTANCE_MODEL = "spherical"
+SMOOTHING = "none"
+GAIN_METHOD = "sum"
+FILL = "forward"
+MOVING_TIME = "elapsed"
+SEGMENT_GAP = 300.0
+OUTLIER_METHOD = "hampel"
+
+# input series can be given as python lists or as float64 numpy arrays. The searches and
+# `Activity` copy arrays once into their own buffers, the helpers like `check_times` or
+# `remove_outliers` read contiguous arrays in place.
+Series = Union[List[float], "numpy.ndarray"]
+Coordinates = Union[List[Tuple[float, float]], "numpy.ndarray"]
 
 class FastestSection:
     """
-    Result of parsing activity data for fastest section.
+    Result of parsing activity data for fastest section. Sections can be compared, hashed and
+    pickled.
 
     Attributes:
         start (int): Start index of fastest section.
         end (int): End index of fastest section.
         velocity (float): Found max velocity of given section.
+        distance (float): Actual distance of the fastest section in meters.
+        duration (float): Elapsed time of the fastest section in seconds.
+        start_time (float): Timestamp of the start of the section since unix epoch.
+        end_time (float): Timestamp of the end of the section since unix epoch.
+        pace (float): Pace of the section in min/km, nan if the section has no distance.
+        elevation_gain (Optional[float]): Gained altitude in meters, `None` if no altitudes are available.
+        start_coordinate (Tuple[float, float]): Coordinate `(lat, lon)` of the start of the section.
+        end_coordinate (Tuple[float, float]): Coordinate `(lat, lon)` of the end of the section.
     """
     start: int
     end: int
     velocity: float
+    distance: float
+    duration: float
+    start_time: float
+    end_time: float
+    pace: float
+    elevation_gain: Optional[float]
+    start_coordinate: Tuple[float, float]
+    end_coordinate: Tuple[float, float]
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns:
+            Dict[str, Any]: All attributes of the section, which can be passed back to the
+                constructor as keyword arguments.
+        """
+        ...
 
 
 class ClimbSection:
     """
-    Result of parsing activity data for best climb section.
+    Result of parsing activity data for best climb section. Sections can be compared, hashed
+    and pickled.
 
     Attributes:
         start (int): Start index of best climb section.
         end (int): End index of best climb section.
         climb (float): Found max climb value of given section.
+        distance (float): Actual distance of the best climb section in meters.
+        duration (float): Elapsed time of the best climb section in seconds.
+        start_time (float): Timestamp of the start of the section since unix epoch.
+        end_time (float): Timestamp of the end of the section since unix epoch.
+        pace (float): Pace of the section in min/km, nan if the section has no distance.
+        elevation_gain (Optional[float]): Gained altitude in meters, `None` if no altitudes are available.
+        start_coordinate (Tuple[float, float]): Coordinate `(lat, lon)` of the start of the section.
+        end_coordinate (Tuple[float, float]): Coordinate `(lat, lon)` of the end of the section.
     """
     start: int
     end: int
     climb: float
+    distance: float
+    duration: float
+    start_time: float
+    end_time: float
+    pace: float
+    elevation_gain: Optional[float]
+    start_coordinate: Tuple[float, float]
+    end_coordinate: Tuple[float, float]
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns:
+            Dict[str, Any]: All attributes of the section, which can be passed back to the
+                constructor as keyword arguments.
+        """
+        ...
 
 
 class FitData:
     """
     Data container returned by e.g. `parse_fit_data` holding the parsed results as attributes.
+    Can be compared, hashed and pickled, missing values (`NaN`) are considered equal. The plain
+    attributes stay python lists for compatibility, the `*_array` attributes return the same
+    series as numpy arrays.
 
     Attributes:
         calories (int): Calories parsed from input data given in kcal.
         times (List[float]): List of timestamps since unix epoch.
         coordinates (List[Tuple[float]]): List of tuples of floats containing the parsed coordinates as `(lat, lon)`.
         altitudes (List[float]): List of floats containing the parsed altitude values in meters.
+        distances (List[float]): Cumulative distances in meters as recorded by the device, e.g. by
+            a footpod, empty if not known.
+        timer_pauses (List[Tuple[float, float]]): Periods as `(stop, start)` timestamps in
+            which the timer of the device was stopped, e.g. by auto pause.
+        record_messages (List[bool]): Whether each row was read from a record message, the other
+            rows hold e.g. events and laps. Empty if not known, all rows are records then.
+        times_array (numpy.ndarray): Timestamps as float64 numpy array of shape `(n,)`. The
+            arrays are read-only and created once on first access.
+        coordinates_array (numpy.ndarray): Coordinates as float64 numpy array of shape `(n, 2)`
+            with the columns `lat` and `lon`.
+        altitudes_array (numpy.ndarray): Altitudes as float64 numpy array of shape `(n,)`.
+        distances_array (numpy.ndarray): Recorded distances as float64 numpy array.
     """
     calories: int
     times: List[float]
     coordinates: List[Tuple[float]]
     altitudes: List[float]
+    distances: List[float]
+    timer_pauses: List[Tuple[float, float]]
+    record_messages: List[bool]
+    times_array: "numpy.ndarray"
+    coordinates_array: "numpy.ndarray"
+    altitudes_array: "numpy.ndarray"
+    distances_array: "numpy.ndarray"
+
+    def to_arrow(self) -> "pyarrow.RecordBatch":
+        """
+        Exports the parsed time series as arrow record batch via the Arrow C Data Interface,
+        without converting the values to python objects. Requires `pyarrow` to be installed.
+        The result can e.g. be passed on to `polars.from_arrow`.
+
+        Returns:
+            pyarrow.RecordBatch:
+                Record batch with the columns `timestamp` (milliseconds, UTC), `lat`, `lon`,
+                `altitude` and `distance`. Missing values are given as nulls. Sensor data like
+                heart rate, cadence, power or speed is not parsed and therefore not exported.
+        """
+        ...
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns:
+            Dict[str, Any]: `calories`, `times`, `coordinates`, `altitudes`, `distances`,
+                `timer_pauses` and `record_messages`, which can be passed back to the constructor as keyword arguments.
+        """
+        ...
+
+
+class FileAnalysis:
+    """
+    Result of analyzing a single file with `analyze_files`. Can be compared, hashed and pickled.
+
+    Attributes:
+        path (str): Path of the analyzed file.
+        fastest_sections (List[Optional[FastestSection]]): Fastest section per requested
+            distance, `None` if no section could be found for the given distance.
+        climb_sections (List[Optional[ClimbSection]]): Best climb section per requested
+            distance, `None` if no section could be found for the given distance.
+        fastest_section_errors (List[Optional[Exception]]): Exception raised while searching
+            the fastest section per requested distance, `None` if a section was found.
+        climb_section_errors (List[Optional[Exception]]): Exception raised while searching
+            the best climb section per requested distance, `None` if a section was found.
+        error (Optional[Exception]): Exception raised while reading the file, `None` if the
+            file could be read. All section and section error lists are empty in this case.
+    """
+    path: str
+    fastest_sections: List[Optional[FastestSection]]
+    climb_sections: List[Optional[ClimbSection]]
+    fastest_section_errors: List[Optional[Exception]]
+    climb_section_errors: List[Optional[Exception]]
+    error: Optional[Exception]
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns:
+            Dict[str, Any]: All attributes with the sections converted to dicts and the errors
+                converted to their messages.
+        """
+        ...
+
+
+class Activity:
+    """
+    Activity parsed once and searched many times. Derived data like the cleaned coordinates, the
+    cumulative distances and the elevation gain is computed on first use and reused for all
+    following searches, which makes searching the same activity for several distances a lot
+    cheaper than calling e.g. `find_fastest_section` repeatedly.
+
+    Args:
+        times (Union[List[float], numpy.ndarray]):
+            Timestamps given in seconds since the Unix epoch, one per coordinate. Numpy
+            arrays are copied once into the activity.
+
+        coordinates (Union[List[Tuple[float, float]], numpy.ndarray]):
+            Coordinates as `(lat, lon)` tuples or as float64 numpy array of shape `(n, 2)`.
+
+        altitudes (Optional[Union[List[float], numpy.ndarray]]):
+            Altitudes in meters, needed for `best_climb_section`.
+
+        distance_model (str):
+            Model used to compute the cumulative distances, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+        slope_distance (bool):
+            Include the altitude change in the cumulative distances, has no effect without
+            altitudes.
+
+        smoothing (str):
+            Filter applied to the coordinates before the cumulative distances are computed,
+            see [`find_fastest_section`][sportgems.find_fastest_section]. The `coordinates`
+            attribute is not smoothed.
+
+        elevation_smoothing (str):
+            Filter applied to the altitudes before the gained altitude is computed, see
+            [`find_best_climb_section`][sportgems.find_best_climb_section]. The `altitudes`
+            attribute is not smoothed.
+
+        gain_method (str):
+            How the gained altitude is summed up, see
+            [`find_best_climb_section`][sportgems.find_best_climb_section].
+
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+        moving_time (str):
+            Time the velocity of the fastest sections is computed on, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+    Attributes:
+        calories (Optional[int]): Calories in kcal, only known for fit files.
+        times (List[float]): Timestamps since unix epoch.
+        coordinates (List[Tuple[float, float]]): Coordinates with missing values filled in.
+        altitudes (Optional[List[float]]): Altitudes with missing values filled in, `None` if
+            no altitudes were given.
+        distances (List[float]): Cumulative distance in meters up to each coordinate.
+        elevation_gain (Optional[float]): Total gained altitude in meters.
+        distance_model (str): Name of the used distance model.
+        slope_distance (bool): Whether the distances include the altitude change.
+        smoothing (str): Spec of the applied smoothing including all parameters, e.g.
+            `"moving-average:5"`.
+        elevation_smoothing (str): Spec of the smoothing applied to the altitudes.
+        gain_method (str): Spec of the gain method, e.g. `"hysteresis:3"`.
+        fill (str): Spec of the fill strategy, e.g. `"linear:60"`.
+        moving_time (str): Spec of the moving time, e.g. `"stationary:0.5:10"`.
+        timer_pauses (List[Tuple[float, float]]): Periods in which the timer of the device was
+            stopped, only known for fit files.
+        segment_gap (float): Gap in seconds, which splits the activity into segments.
+
+    Raises:
+        InconsistentLengthException: If length of input lists is not equal.
+        TooFewDataPointsException: If input data has too few data points.
+        ValueError: If `distance_model` is unknown or a smoothing, `gain_method`, `fill` or
+            `moving_time` is invalid or `segment_gap` is not positive.
+    """
+    calories: Optional[int]
+    times: List[float]
+    coordinates: List[Tuple[float, float]]
+    altitudes: Optional[List[float]]
+    distances: List[float]
+    elevation_gain: Optional[float]
+    distance_model: str
+    slope_distance: bool
+    smoothing: str
+    elevation_smoothing: str
+    gain_method: str
+    fill: str
+    moving_time: str
+    timer_pauses: List[Tuple[float, float]]
+    segment_gap: float
+
+    def __init__(
+        self,
+        times: Series,
+        coordinates: Coordinates,
+        altitudes: Optional[Series] = None,
+        distance_model: str = DISTANCE_MODEL,
+        slope_distance: bool = False,
+        smoothing: str = SMOOTHING,
+        elevation_smoothing: str = SMOOTHING,
+        gain_method: str = GAIN_METHOD,
+        fill: str = FILL,
+        moving_time: str = MOVING_TIME,
+        segment_gap: float = SEGMENT_GAP,
+    ) -> None: ...
+
+    @staticmethod
+    def from_fit(
+        path_to_fit: str,
+        distance_model: str = DISTANCE_MODEL,
+        slope_distance: bool = False,
+        smoothing: str = SMOOTHING,
+        elevation_smoothing: str = SMOOTHING,
+        gain_method: str = GAIN_METHOD,
+        fill: str = FILL,
+        moving_time: str = MOVING_TIME,
+        segment_gap: float = SEGMENT_GAP,
+    ) -> "Activity":
+        """
+        Parses the fit file at `path_to_fit` into an activity.
+
+        Raises:
+            FileNotReadableException: If the file cannot be opened or read.
+            InvalidFitFileException: If the file is not a valid fit file.
+        """
+        ...
+
+    @staticmethod
+    def from_bytes(
+        data: bytes,
+        distance_model: str = DISTANCE_MODEL,
+        slope_distance: bool = False,
+        smoothing: str = SMOOTHING,
+        elevation_smoothing: str = SMOOTHING,
+        gain_method: str = GAIN_METHOD,
+        fill: str = FILL,
+        moving_time: str = MOVING_TIME,
+        segment_gap: float = SEGMENT_GAP,
+    ) -> "Activity":
+        """
+        Parses the content of a fit file, e.g. received via http, into an activity.
+
+        Raises:
+            InvalidFitFileException: If the data is not a valid fit file.
+        """
+        ...
+
+    @staticmethod
+    def from_distances(
+        times: Series,
+        distances: Series,
+        altitudes: Optional[Series] = None,
+        elevation_smoothing: str = SMOOTHING,
+        gain_method: str = GAIN_METHOD,
+        fill: str = FILL,
+        moving_time: str = MOVING_TIME,
+        segment_gap: float = SEGMENT_GAP,
+    ) -> "Activity":
+        """
+        Creates an activity without coordinates from cumulative distances in meters, e.g.
+        recorded by a footpod during an indoor run. Fit files without coordinates are read this
+        way by `from_fit` and `from_bytes` as well.
+
+        Raises:
+            InconsistentLengthException: If length of input lists is not equal.
+            TooFewDataPointsException: If input data has too few data points.
+        """
+        ...
+
+    def fastest_section(self, desired_distance: int, tolerance: float = TOLERANCE) -> FastestSection:
+        """
+        Same as [`find_fastest_section`][sportgems.find_fastest_section] on the data of the
+        activity. The elevation gain of the section is given if the activity has altitudes.
+        """
+        ...
+
+    def best_climb_section(self, desired_distance: int, tolerance: float = TOLERANCE) -> ClimbSection:
+        """
+        Same as [`find_best_climb_section`][sportgems.find_best_climb_section] on the data of
+        the activity.
+        """
+        ...
+
+    def best_efforts(
+        self, desired_distances: List[int], tolerance: float = TOLERANCE
+    ) -> Tuple[List[Optional[FastestSection]], List[Optional[Exception]]]:
+        """
+        Searches the fastest section for each of the given distances in parallel.
+
+        Returns:
+            Tuple[List[Optional[FastestSection]], List[Optional[Exception]]]:
+                Fastest section per distance, `None` if the search failed for the given
+                distance, and the exception raised by the failed searches, e.g.
+                `NoSectionFoundException` or `TooFewDataPointsException`, `None` for the found
+                sections.
+        """
+        ...
+
+    def summary(self) -> Dict[str, Any]:
+        """
+        Returns:
+            Dict[str, Any]:
+                `records`, `start_time`, `end_time`, `duration` (seconds), `distance` (meters),
+                `average_velocity` (m/s), `elevation_gain` and `elevation_loss` (meters, `None`
+                without altitudes) and `calories` (`None` if not parsed from a fit file).
+        """
+        ...
+
+    def ascent_descent(self) -> Optional[Tuple[float, float]]:
+        """
+        Total ascent and descent of the activity in meters with the `elevation_smoothing` and
+        `gain_method` of the activity, `None` without altitudes.
+        """
+        ...
+
+    def time_issues(self) -> Dict[str, List[int]]:
+        """
+        Irregular timestamps of the activity, see [`check_times`][sportgems.check_times].
+        """
+        ...
+
+    def segments(self) -> List[Tuple[int, int]]:
+        """
+        Records of the activity split at clock resets and gaps longer than `segment_gap` as
+        `(start, end)` index ranges with exclusive end. Sections never span two segments.
+        """
+        ...
 
 
 class DistanceTooSmallException(Exception):
@@ -84,8 +456,25 @@ class InvalidDesiredDistanceException(Exception):
     ...
 
 
+class InvalidFitFileException(Exception):
+    """
+    The given file or data is no valid fit file.
+    """
+    ...
+
+
+class FileNotReadableException(OSError):
+    """
+    The given file could not be opened or read, e.g. because it does not exist.
+    """
+    ...
+
+
 def find_fastest_section(
-    desired_distance: int, times: List[float], coordinates: List[Tuple[float, float]], tolerance: float = TOLERANCE,
+    desired_distance: int, times: Series, coordinates: Coordinates, tolerance: float = TOLERANCE,
+    distance_model: str = DISTANCE_MODEL, slope_distance: bool = False, altitudes: Optional[Series] = None,
+    smoothing: str = SMOOTHING, fill: str = FILL, moving_time: str = MOVING_TIME,
+    segment_gap: float = SEGMENT_GAP,
 ) -> FastestSection:
     """
     Parses the given input `coordinates` and `times` to find the fastest section of length
@@ -95,13 +484,16 @@ def find_fastest_section(
         desired_distance (int): 
             Length in meter of the desired fastest section to parse for.
 
-        times (List[float]):
+        times (Union[List[float], numpy.ndarray]):
             A list of timestamps as floats given in seconds since the Unix epoch, where each
-            timestamp corresponds to one coordinate in the coordinates list.
+            timestamp corresponds to one coordinate in the coordinates list. Can also be
+            given as float64 numpy array of shape `(n,)`, which is copied once into the search
+            instead of converting each element.
 
-        coordinates (List[Tuple[float, float]]):
+        coordinates (Union[List[Tuple[float, float]], numpy.ndarray]):
             A list of tuple of floats, where each tuple represents one coordinate.
             The first float represents the latitude and the second the longitude: (lat, lon).
+            Can also be given as float64 numpy array of shape `(n, 2)`.
 
         tolerance (float): 
             Percentage value to specify bounds in which the distance of a section is still
@@ -109,6 +501,42 @@ def find_fastest_section(
             resolution of activity data, not all sections are exactly e.g. 1000 meter long,
             but with the default of 0.01 a section with 1010 meter will still be considered
             as a 1000 meter section.
+        distance_model (str):
+            Model used to compute the distance between two coordinates, one of `"spherical"`
+            (default), `"haversine"`, `"equirectangular"` (fastest, accurate for short steps)
+            or `"geodesic"` (WGS84 ellipsoid, most accurate).
+        slope_distance (bool):
+            Include the altitude change in the distance between two coordinates, e.g. for
+            alpine hiking, where a steep section is considerably longer on foot than on the map.
+        altitudes (Optional[Union[List[float], numpy.ndarray]]):
+            Altitudes in meters, needed for `slope_distance` and the elevation gain of the
+            found section.
+        smoothing (str):
+            Filter applied to the coordinates before the distances are computed, to reduce
+            the gps noise which otherwise inflates the distances. One of `"none"` (default),
+            `"moving-average[:window]"`, `"savitzky-golay[:window[:order]]"` or
+            `"kalman[:process_noise[:measurement_noise]]"`, e.g. `"savitzky-golay:9:2"`. The
+            found section still reports the recorded coordinates.
+        fill (str):
+            How missing records (`NaN`) are filled, either `"forward"` (default), which repeats
+            the last valid record, or `"linear[:max_gap]"`, which interpolates in time between
+            the neighbouring records. Gaps longer than `max_gap` seconds (default 60) stay
+            missing and add neither distance nor altitude gain. A forward filled gps dropout
+            looks like standing still followed by a jump, which can turn into an absurd velocity
+            for short sections.
+        moving_time (str):
+            Time the velocity is computed on, one of `"elapsed"` (default), the wall-clock
+            time between start and end of a section, `"timer"`, which skips the periods in
+            which the timer of the device was stopped (only known for fit files), or
+            `"stationary[:max_speed[:min_duration]]"`, which additionally skips stops slower
+            than `max_speed` in m/s (default 0.5) lasting at least `min_duration` seconds
+            (default 10), e.g. at a red light. The `duration` of the found section is still
+            the elapsed time.
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, e.g. while the device
+            was switched off (default 300). Sections never span clock resets, where the times
+            start over earlier, either. Pass `math.inf` to search across gaps. Duplicated and
+            out of order times are skipped, see [`check_times`][sportgems.check_times].
 
     Returns:
         FastestSection:
@@ -126,12 +554,18 @@ def find_fastest_section(
             see [`NoSectionFoundException`][sportgems.NoSectionFoundException].
         InvalidDesiredDistanceException: If given `desired_distance` is invalid,
             see [`InvalidDesiredDistanceException`][sportgems.InvalidDesiredDistanceException].
+        ValueError: If `coordinates` is given as numpy array not of shape `(n, 2)` or if
+            `distance_model` is unknown or `smoothing`, `fill` or `moving_time` is invalid or
+            `segment_gap` is not positive.
     """
     ...
 
 
 def find_fastest_section_in_fit(
-    desired_distance: int, path_to_fit: str, tolerance: float = TOLERANCE,
+    desired_distance: int, path_to_fit: str, tolerance: float = TOLERANCE, distance_model: str = DISTANCE_MODEL,
+    slope_distance: bool = False, smoothing: str = SMOOTHING, fill: str = FILL,
+    moving_time: str = MOVING_TIME,
+    segment_gap: float = SEGMENT_GAP,
 ) -> FastestSection:
     """
     Takes `path_to_fit` file as argument and parses it to find the fastest section of
@@ -148,6 +582,29 @@ def find_fastest_section_in_fit(
             resolution of activity data, not all sections are exactly e.g. 1000 meter long,
             but with the default of 0.01 a section with 1010 meter will still be considered
             as a 1000 meter section.
+        distance_model (str):
+            Model used to compute the distance between two coordinates, one of `"spherical"`
+            (default), `"haversine"`, `"equirectangular"` (fastest, accurate for short steps)
+            or `"geodesic"` (WGS84 ellipsoid, most accurate).
+        slope_distance (bool):
+            Include the altitude change in the distance between two coordinates, e.g. for
+            alpine hiking, where a steep section is considerably longer on foot than on the map.
+        smoothing (str):
+            Filter applied to the coordinates before the distances are computed, to reduce
+            the gps noise which otherwise inflates the distances. One of `"none"` (default),
+            `"moving-average[:window]"`, `"savitzky-golay[:window[:order]]"` or
+            `"kalman[:process_noise[:measurement_noise]]"`, e.g. `"savitzky-golay:9:2"`. The
+            found section still reports the recorded coordinates.
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+        moving_time (str):
+            Time the velocity is computed on, see
+            [`find_fastest_section`][sportgems.find_fastest_section]. The timer pauses are
+            read from the timer events of the fit file.
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
 
     Returns:
         FastestSection:
@@ -163,12 +620,21 @@ def find_fastest_section_in_fit(
             see [`NoSectionFoundException`][sportgems.NoSectionFoundException].
         InvalidDesiredDistanceException: If given `desired_distance` is invalid,
             see [`InvalidDesiredDistanceException`][sportgems.InvalidDesiredDistanceException].
+        FileNotReadableException: If the given file could not be opened or read,
+            see [`FileNotReadableException`][sportgems.FileNotReadableException].
+        InvalidFitFileException: If the given file is not a valid fit file,
+            see [`InvalidFitFileException`][sportgems.InvalidFitFileException].
+        ValueError: If `distance_model` is unknown or `smoothing`, `fill` or `moving_time` is
+            invalid or `segment_gap` is not positive.
     """
     ...
 
 
 def find_best_climb_section(
-    desired_distance: int, times: List[float], coordinates: List[Tuple[float, float]], altitudes: List[float], tolerance: float = TOLERANCE,
+    desired_distance: int, times: Series, coordinates: Coordinates, altitudes: Series, tolerance: float = TOLERANCE,
+    distance_model: str = DISTANCE_MODEL, slope_distance: bool = False, smoothing: str = SMOOTHING,
+    elevation_smoothing: str = SMOOTHING, gain_method: str = GAIN_METHOD, fill: str = FILL,
+    segment_gap: float = SEGMENT_GAP,
 ) -> ClimbSection:
     """
     Parses the given input `coordinates`, `times` and `altitudes` values to find the section
@@ -178,20 +644,50 @@ def find_best_climb_section(
     Args:
         desired_distance (int):
             Length in meter of the desired best climb section to parse for.
-        times (List[float]):
+        times (Union[List[float], numpy.ndarray]):
             A list of timestamps as floats given in seconds since the Unix epoch, where each
-            timestamp corresponds to one coordinate in the coordinates list.
-        coordinates (List[Tuple[float, float]]):
+            timestamp corresponds to one coordinate in the coordinates list. Can also be
+            given as float64 numpy array of shape `(n,)`, which is copied once into the search
+            instead of converting each element.
+        coordinates (Union[List[Tuple[float, float]], numpy.ndarray]):
             A list of tuple of floats, where each tuple represents one coordinate. The first
-            float represents the latitude and the second the longitude: (lat, lon).
-        altitudes (List[float]):
-            A list of floats containing the altitude values.
+            float represents the latitude and the second the longitude: (lat, lon). Can also
+            be given as float64 numpy array of shape `(n, 2)`.
+        altitudes (Union[List[float], numpy.ndarray]):
+            A list of floats containing the altitude values. Can also be given as float64
+            numpy array of shape `(n,)`.
         tolerance (float):
             Percentage value to specify bounds in which the distance of a section is still
             considered to be equal to the desired distance. Because due to the finite
             resolution of activity data, not all sections are exactly e.g. 1000 meter long,
             but with the default of 0.01 a section with 1010 meter will still be considered
             as a 1000 meter section.
+        distance_model (str):
+            Model used to compute the distance between two coordinates, one of `"spherical"`
+            (default), `"haversine"`, `"equirectangular"` (fastest, accurate for short steps)
+            or `"geodesic"` (WGS84 ellipsoid, most accurate).
+        slope_distance (bool):
+            Include the altitude change in the distance between two coordinates, e.g. for
+            alpine hiking, where a steep section is considerably longer on foot than on the map.
+        smoothing (str):
+            Filter applied to the coordinates before the distances are computed, to reduce
+            the gps noise which otherwise inflates the distances. One of `"none"` (default),
+            `"moving-average[:window]"`, `"savitzky-golay[:window[:order]]"` or
+            `"kalman[:process_noise[:measurement_noise]]"`, e.g. `"savitzky-golay:9:2"`. The
+            found section still reports the recorded coordinates.
+        elevation_smoothing (str):
+            Filter applied to the altitudes before the gained altitude is computed, same
+            values as for `smoothing`.
+        gain_method (str):
+            How the gained altitude is summed up, either `"sum"` (default) of all positive
+            altitude changes or `"hysteresis[:threshold]"`, which ignores changes smaller than
+            the threshold in meters (default 3) like most watches and platforms do.
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
 
     Returns:
         ClimbSection:
@@ -209,12 +705,18 @@ def find_best_climb_section(
             see [`NoSectionFoundException`][sportgems.NoSectionFoundException].
         InvalidDesiredDistanceException: If given `desired_distance` is invalid,
             see [`InvalidDesiredDistanceException`][sportgems.InvalidDesiredDistanceException].
+        ValueError: If `coordinates` is given as numpy array not of shape `(n, 2)` or if
+            `distance_model` is unknown or a smoothing, `gain_method` or `fill` is invalid or
+            `segment_gap` is not positive.
     """
     ...
 
 
 def find_best_climb_section_in_fit(
-    desired_distance: int, path_to_fit: str, tolerance: float = TOLERANCE,
+    desired_distance: int, path_to_fit: str, tolerance: float = TOLERANCE, distance_model: str = DISTANCE_MODEL,
+    slope_distance: bool = False, smoothing: str = SMOOTHING, elevation_smoothing: str = SMOOTHING,
+    gain_method: str = GAIN_METHOD, fill: str = FILL,
+    segment_gap: float = SEGMENT_GAP,
 ) -> ClimbSection:
     """
     Takes `path_to_fit` file as argument and parses it to find the best climb section of
@@ -232,6 +734,32 @@ def find_best_climb_section_in_fit(
             resolution of activity data, not all sections are exactly e.g. 1000 meter long,
             but with the default of 0.01 a section with 1010 meter will still be considered
             as a 1000 meter section.
+        distance_model (str):
+            Model used to compute the distance between two coordinates, one of `"spherical"`
+            (default), `"haversine"`, `"equirectangular"` (fastest, accurate for short steps)
+            or `"geodesic"` (WGS84 ellipsoid, most accurate).
+        slope_distance (bool):
+            Include the altitude change in the distance between two coordinates, e.g. for
+            alpine hiking, where a steep section is considerably longer on foot than on the map.
+        smoothing (str):
+            Filter applied to the coordinates before the distances are computed, to reduce
+            the gps noise which otherwise inflates the distances. One of `"none"` (default),
+            `"moving-average[:window]"`, `"savitzky-golay[:window[:order]]"` or
+            `"kalman[:process_noise[:measurement_noise]]"`, e.g. `"savitzky-golay:9:2"`. The
+            found section still reports the recorded coordinates.
+        elevation_smoothing (str):
+            Filter applied to the altitudes before the gained altitude is computed, same
+            values as for `smoothing`.
+        gain_method (str):
+            How the gained altitude is summed up, either `"sum"` (default) of all positive
+            altitude changes or `"hysteresis[:threshold]"`, which ignores changes smaller than
+            the threshold in meters (default 3) like most watches and platforms do.
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
 
     Returns:
         ClimbSection:
@@ -247,6 +775,302 @@ def find_best_climb_section_in_fit(
             see [`NoSectionFoundException`][sportgems.NoSectionFoundException].
         InvalidDesiredDistanceException: If given `desired_distance` is invalid,
             see [`InvalidDesiredDistanceException`][sportgems.InvalidDesiredDistanceException].
+        FileNotReadableException: If the given file could not be opened or read,
+            see [`FileNotReadableException`][sportgems.FileNotReadableException].
+        InvalidFitFileException: If the given file is not a valid fit file,
+            see [`InvalidFitFileException`][sportgems.InvalidFitFileException].
+        ValueError: If `distance_model` is unknown or a smoothing, `gain_method` or `fill` is
+            invalid or `segment_gap` is not positive.
+    """
+    ...
+
+def find_fastest_section_from_distances(
+    desired_distance: int, times: Series, distances: Series, tolerance: float = TOLERANCE,
+    altitudes: Optional[Series] = None, fill: str = FILL, moving_time: str = MOVING_TIME,
+    segment_gap: float = SEGMENT_GAP,
+) -> FastestSection:
+    """
+    Finds the fastest section of length `desired_distance` on cumulative distances instead of
+    coordinates, e.g. for indoor runs on a treadmill with a footpod or rides on a trainer. The
+    `*_in_fit` functions use the distances recorded in the fit file automatically in case it
+    holds no coordinates.
+
+    Args:
+        desired_distance (int):
+            Length in meter of the desired fastest section to parse for.
+        times (Union[List[float], numpy.ndarray]):
+            Timestamps given in seconds since the Unix epoch, one per distance. Numpy arrays
+            are copied once into the search.
+        distances (Union[List[float], numpy.ndarray]):
+            Cumulative distances in meters, missing values (`NaN`) are filled according to
+            `fill`.
+        tolerance (float):
+            Percentage value to specify bounds in which the distance of a section is still
+            considered to be equal to the desired distance.
+        altitudes (Optional[Union[List[float], numpy.ndarray]]):
+            Altitudes in meters, used for the elevation gain of the found section.
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+        moving_time (str):
+            Time the velocity is computed on, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+    Returns:
+        FastestSection:
+            Returns a Python object of type [`FastestSection`][sportgems.FastestSection], the
+            start and end coordinates are `NaN`.
+
+    Raises:
+        DistanceTooSmallException: If input distance is too small.
+        InconsistentLengthException: If length of input lists is not equal.
+        TooFewDataPointsException: If input data has too few data points.
+        NoSectionFoundException: If no section with `desired_distance` was found.
+        InvalidDesiredDistanceException: If given `desired_distance` is invalid.
+        ValueError: If `fill` or `moving_time` is invalid or `segment_gap` is not positive.
+    """
+    ...
+
+
+def find_best_climb_section_from_distances(
+    desired_distance: int, times: Series, distances: Series, altitudes: Series, tolerance: float = TOLERANCE,
+    elevation_smoothing: str = SMOOTHING, gain_method: str = GAIN_METHOD, fill: str = FILL,
+    segment_gap: float = SEGMENT_GAP,
+) -> ClimbSection:
+    """
+    Finds the best climb section of length `desired_distance` on cumulative distances instead of
+    coordinates, see [`find_fastest_section_from_distances`][sportgems.find_fastest_section_from_distances].
+
+    Args:
+        desired_distance (int):
+            Length in meter of the desired best climb section to parse for.
+        times (Union[List[float], numpy.ndarray]):
+            Timestamps given in seconds since the Unix epoch, one per distance. Numpy arrays
+            are copied once into the search.
+        distances (Union[List[float], numpy.ndarray]):
+            Cumulative distances in meters, missing values (`NaN`) are filled according to
+            `fill`.
+        altitudes (Union[List[float], numpy.ndarray]):
+            Altitudes in meters.
+        tolerance (float):
+            Percentage value to specify bounds in which the distance of a section is still
+            considered to be equal to the desired distance.
+        elevation_smoothing (str):
+            Filter applied to the altitudes, see
+            [`find_best_climb_section`][sportgems.find_best_climb_section].
+        gain_method (str):
+            How the gained altitude is summed up, see
+            [`find_best_climb_section`][sportgems.find_best_climb_section].
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+        segment_gap (float):
+            Gap in seconds between two records, which no section spans, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+    Returns:
+        ClimbSection:
+            Returns a Python object of type [`ClimbSection`][sportgems.ClimbSection], the start
+            and end coordinates are `NaN`.
+
+    Raises:
+        DistanceTooSmallException: If input distance is too small.
+        InconsistentLengthException: If length of input lists is not equal.
+        TooFewDataPointsException: If input data has too few data points.
+        NoSectionFoundException: If no section with `desired_distance` was found.
+        InvalidDesiredDistanceException: If given `desired_distance` is invalid.
+        ValueError: If `elevation_smoothing`, `gain_method` or `fill` is invalid or `segment_gap` is
+            not positive.
+    """
+    ...
+
+def total_ascent_descent(
+    altitudes: Series, times: Optional[Series] = None, elevation_smoothing: str = SMOOTHING,
+    gain_method: str = GAIN_METHOD, fill: str = FILL,
+) -> Tuple[float, float]:
+    """
+    Computes the total ascent and descent of the given altitudes, missing values (`NaN`) are
+    filled according to `fill`.
+
+    Args:
+        altitudes (Union[List[float], numpy.ndarray]):
+            Altitudes in meters.
+        times (Optional[Union[List[float], numpy.ndarray]]):
+            Timestamps given in seconds since the Unix epoch, only used by the `"kalman"`
+            smoothing and the `"linear"` fill. Without times the records are assumed to be one
+            second apart.
+        elevation_smoothing (str):
+            Filter applied to the altitudes, see
+            [`find_best_climb_section`][sportgems.find_best_climb_section].
+        gain_method (str):
+            How the altitude changes are summed up, see
+            [`find_best_climb_section`][sportgems.find_best_climb_section].
+        fill (str):
+            How missing records are filled, see
+            [`find_fastest_section`][sportgems.find_fastest_section].
+
+    Returns:
+        Tuple[float, float]:
+            Total ascent and descent in meters, both positive.
+
+    Raises:
+        InconsistentLengthException: If `times` and `altitudes` differ in length.
+        ValueError: If `elevation_smoothing`, `gain_method` or `fill` is invalid.
+    """
+    ...
+
+def detect_outliers(
+    values: Series, times: Optional[Series] = None, method: str = OUTLIER_METHOD
+) -> List[bool]:
+    """
+    Detects spikes in a series like altitudes or speeds. Missing values (`NaN`) are never
+    outliers.
+
+    Args:
+        values (Union[List[float], numpy.ndarray]):
+            Values of the series, e.g. altitudes in meters or speeds in m/s.
+        times (Optional[Union[List[float], numpy.ndarray]]):
+            Timestamps given in seconds since the Unix epoch, only used by the `"rate"` method.
+            Without times the records are assumed to be one second apart.
+        method (str):
+            `"median"` compares each value to the median of the series, `"hampel"` to the median
+            of the surrounding values, both scaled by the median absolute deviation.
+            `"rate"` flags values reached and left faster than the maximum rate per second while
+            their neighbours are within it, so steps are kept. Parameters follow separated by
+            colons: `"median:threshold:min_deviation"` (default `"median:3.5:1"`),
+            `"hampel:window:threshold:min_deviation"` (default `"hampel:9:3:1"`) and
+            `"rate:max_rate"` (default `"rate:10"`). The minimum deviation in units of the values
+            keeps small changes of constant series from being flagged. `"none"` flags nothing.
+
+    Returns:
+        List[bool]:
+            `True` for every outlier.
+
+    Raises:
+        InconsistentLengthException: If `times` and `values` differ in length.
+        ValueError: If `method` is invalid.
+    """
+    ...
+
+def remove_outliers(
+    values: Series, times: Optional[Series] = None, method: str = OUTLIER_METHOD
+) -> List[float]:
+    """
+    Replaces the spikes detected by [`detect_outliers`][sportgems.detect_outliers] with `NaN`.
+    Takes the same arguments.
+
+    Returns:
+        List[float]:
+            Values with the outliers replaced by `NaN`.
+    """
+    ...
+
+def remove_coordinate_outliers(
+    coordinates: List[Tuple[float, float]], times: Optional[Series] = None,
+    method: str = OUTLIER_METHOD,
+) -> List[Tuple[float, float]]:
+    """
+    Replaces spikes in the coordinates with `(NaN, NaN)`. The coordinates are projected to meters
+    around their median, so the minimum deviation is given in meters and the maximum rate of
+    `"rate"` is a speed in m/s.
+
+    Args:
+        coordinates (List[Tuple[float, float]]):
+            Latitude and longitude pairs.
+        times (Optional[Union[List[float], numpy.ndarray]]):
+            Timestamps given in seconds since the Unix epoch, see
+            [`detect_outliers`][sportgems.detect_outliers].
+        method (str):
+            Outlier filter, see [`detect_outliers`][sportgems.detect_outliers].
+
+    Returns:
+        List[Tuple[float, float]]:
+            Coordinates with the outliers replaced by `(NaN, NaN)`.
+
+    Raises:
+        InconsistentLengthException: If `times` and `coordinates` differ in length.
+        ValueError: If `method` is invalid.
+    """
+    ...
+
+def check_times(times: Series, max_gap: float = SEGMENT_GAP) -> Dict[str, List[int]]:
+    """
+    Finds irregular timestamps, each compared with the last regular timestamp before it. Missing
+    times (`NaN`) are skipped.
+
+    Args:
+        times (Union[List[float], numpy.ndarray]):
+            Timestamps given in seconds since the Unix epoch.
+        max_gap (float):
+            Gap in seconds between two timestamps, which is reported as gap.
+
+    Returns:
+        Dict[str, List[int]]:
+            Indexes of the `duplicates` (same time as before), the `out_of_order` times (single
+            earlier times, while the following times continue after the time before), the
+            `clock_resets` (the times start over earlier from here on, e.g. in merged files)
+            and the `gaps` (more than `max_gap` after the time before).
+
+    Raises:
+        ValueError: If `max_gap` is not positive.
+    """
+    ...
+
+def repair_times(times: Series) -> List[float]:
+    """
+    Replaces the duplicated and out of order timestamps found by
+    [`check_times`][sportgems.check_times] with `NaN`, which the search skips. Clock resets and
+    gaps are kept, sections never span them.
+
+    Returns:
+        List[float]:
+            Timestamps with the irregular ones replaced by `NaN`.
+    """
+    ...
+
+def split_segments(times: Series, max_gap: float = SEGMENT_GAP) -> List[Tuple[int, int]]:
+    """
+    Splits the timestamps at clock resets and gaps longer than `max_gap` seconds.
+
+    Returns:
+        List[Tuple[int, int]]:
+            Segments as `(start, end)` index ranges with exclusive end, covering all timestamps.
+
+    Raises:
+        ValueError: If `max_gap` is not positive.
+    """
+    ...
+
+def quality_report(activity: Activity, desired_distance: int = 1_000) -> Dict[str, Any]:
+    """
+    Reports the problems in the data of an activity, e.g. to find out why a search raised
+    [`NoSectionFoundException`][sportgems.NoSectionFoundException].
+
+    Args:
+        activity (Activity):
+            Activity to check, the gaps are detected with its `segment_gap`.
+        desired_distance (int):
+            Distance in meters the suggested tolerance is computed for.
+
+    Returns:
+        Dict[str, Any]:
+            `records` (number of rows read from record messages, fit files also hold rows of
+            e.g. events and laps), `missing` (number of `NaN` values of the records in the
+            `times`, `coordinates`, `altitudes` and device `distances`, `None` for series the
+            activity does not have), `sampling_intervals` (`min`, `median`, `mean` and `max`
+            seconds between two records), `time_issues` (see
+            [`check_times`][sportgems.check_times]), `speed_spikes` and `altitude_spikes`
+            (indexes of the records, including the altitude spikes removed from fit files),
+            `step_distance` (meters between two records, which 95% of the
+            records stay below) and `suggested_tolerance` (smallest tolerance at which the
+            search for `desired_distance` cannot step over the allowed distances, at least the
+            default tolerance).
+
+    Raises:
+        InvalidDesiredDistanceException: If `desired_distance` is not positive.
     """
     ...
 
@@ -263,6 +1087,38 @@ def parse_fit_data(path_to_fit: str) -> FitData:
         FitData:
             Returns a Python object of type [`FitData`][sportgems.FitData], with the results
             given as attributes.
+
+    Raises:
+        FileNotReadableException: If the given file could not be opened or read,
+            see [`FileNotReadableException`][sportgems.FileNotReadableException].
+        InvalidFitFileException: If the given file is not a valid fit file,
+            see [`InvalidFitFileException`][sportgems.InvalidFitFileException].
+    """
+    ...
+
+
+def analyze_files(
+    paths: List[str], distances: List[float], tolerance: float = TOLERANCE,
+) -> List[FileAnalysis]:
+    """
+    Parses all given fit files in parallel and searches each of them for the fastest and
+    best climb section of every distance in `distances`. The GIL is released during the
+    analysis. A file which cannot be read does not abort the batch, instead the exception
+    is stored in the `error` attribute of its result.
+
+    Args:
+        paths (List[str]):
+            Paths to the fit files, which should be analyzed.
+        distances (List[float]):
+            Lengths in meter of the desired sections to parse for.
+        tolerance (float):
+            Percentage value to specify bounds in which the distance of a section is still
+            considered to be equal to the desired distance.
+
+    Returns:
+        List[FileAnalysis]:
+            Returns one [`FileAnalysis`][sportgems.FileAnalysis] per given path, in the
+            order of `paths`.
     """
     ...
     
\ No newline at end of file
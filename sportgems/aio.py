@@ -0,0 +1,164 @@
+"""
+Awaitable variants of the sportgems functions. Parsing and searching run on a rust thread pool
+and the result is handed back to the running asyncio event loop, so the event loop is never
+blocked, e.g.
+
+    result = await sportgems.aio.find_fastest_section_in_fit(1_000, path_to_fit)
+"""
+from typing import List, Optional, Tuple
+
+from .sportgems import aio as _aio
+from .sportgems import ClimbSection, FastestSection, FileAnalysis, FitData
+
+
+async def find_fastest_section(
+    desired_distance: int,
+    times: List[float],
+    coordinates: List[Tuple[float, float]],
+    tolerance: Optional[float] = None,
+    distance_model: Optional[str] = None,
+    slope_distance: bool = False,
+    altitudes: Optional[List[float]] = None,
+    smoothing: Optional[str] = None,
+    fill: Optional[str] = None,
+    moving_time: Optional[str] = None,
+    segment_gap: Optional[float] = None,
+) -> FastestSection:
+    """
+    Awaitable variant of [`find_fastest_section`][sportgems.find_fastest_section].
+    """
+    return await _aio.find_fastest_section(
+        desired_distance, times, coordinates, tolerance, distance_model, slope_distance, altitudes, smoothing,
+        fill, moving_time, segment_gap,
+    )
+
+
+async def find_fastest_section_in_fit(
+    desired_distance: int,
+    path_to_fit: str,
+    tolerance: Optional[float] = None,
+    distance_model: Optional[str] = None,
+    slope_distance: bool = False,
+    smoothing: Optional[str] = None,
+    fill: Optional[str] = None,
+    moving_time: Optional[str] = None,
+    segment_gap: Optional[float] = None,
+) -> FastestSection:
+    """
+    Awaitable variant of [`find_fastest_section_in_fit`][sportgems.find_fastest_section_in_fit].
+    """
+    return await _aio.find_fastest_section_in_fit(
+        desired_distance, path_to_fit, tolerance, distance_model, slope_distance, smoothing, fill,
+        moving_time, segment_gap,
+    )
+
+
+async def find_best_climb_section(
+    desired_distance: int,
+    times: List[float],
+    coordinates: List[Tuple[float, float]],
+    altitudes: List[float],
+    tolerance: Optional[float] = None,
+    distance_model: Optional[str] = None,
+    slope_distance: bool = False,
+    smoothing: Optional[str] = None,
+    elevation_smoothing: Optional[str] = None,
+    gain_method: Optional[str] = None,
+    fill: Optional[str] = None,
+    segment_gap: Optional[float] = None,
+) -> ClimbSection:
+    """
+    Awaitable variant of [`find_best_climb_section`][sportgems.find_best_climb_section].
+    """
+    return await _aio.find_best_climb_section(
+        desired_distance, times, coordinates, altitudes, tolerance, distance_model, slope_distance, smoothing,
+        elevation_smoothing, gain_method, fill, segment_gap,
+    )
+
+
+async def find_best_climb_section_in_fit(
+    desired_distance: int,
+    path_to_fit: str,
+    tolerance: Optional[float] = None,
+    distance_model: Optional[str] = None,
+    slope_distance: bool = False,
+    smoothing: Optional[str] = None,
+    elevation_smoothing: Optional[str] = None,
+    gain_method: Optional[str] = None,
+    fill: Optional[str] = None,
+    segment_gap: Optional[float] = None,
+) -> ClimbSection:
+    """
+    Awaitable variant of [`find_best_climb_section_in_fit`][sportgems.find_best_climb_section_in_fit].
+    """
+    return await _aio.find_best_climb_section_in_fit(
+        desired_distance, path_to_fit, tolerance, distance_model, slope_distance, smoothing,
+        elevation_smoothing, gain_method, fill, segment_gap,
+    )
+
+
+async def find_fastest_section_from_distances(
+    desired_distance: int,
+    times: List[float],
+    distances: List[float],
+    tolerance: Optional[float] = None,
+    altitudes: Optional[List[float]] = None,
+    fill: Optional[str] = None,
+    moving_time: Optional[str] = None,
+    segment_gap: Optional[float] = None,
+) -> FastestSection:
+    """
+    Awaitable variant of
+    [`find_fastest_section_from_distances`][sportgems.find_fastest_section_from_distances].
+    """
+    return await _aio.find_fastest_section_from_distances(
+        desired_distance, times, distances, tolerance, altitudes, fill, moving_time,
+        segment_gap,
+    )
+
+
+async def find_best_climb_section_from_distances(
+    desired_distance: int,
+    times: List[float],
+    distances: List[float],
+    altitudes: List[float],
+    tolerance: Optional[float] = None,
+    elevation_smoothing: Optional[str] = None,
+    gain_method: Optional[str] = None,
+    fill: Optional[str] = None,
+    segment_gap: Optional[float] = None,
+) -> ClimbSection:
+    """
+    Awaitable variant of
+    [`find_best_climb_section_from_distances`][sportgems.find_best_climb_section_from_distances].
+    """
+    return await _aio.find_best_climb_section_from_distances(
+        desired_distance, times, distances, altitudes, tolerance, elevation_smoothing, gain_method, fill,
+        segment_gap,
+    )
+
+
+async def parse_fit_data(path_to_fit: str) -> FitData:
+    """
+    Awaitable variant of [`parse_fit_data`][sportgems.parse_fit_data].
+    """
+    return await _aio.parse_fit_data(path_to_fit)
+
+
+async def analyze_files(
+    paths: List[str], distances: List[float], tolerance: Optional[float] = None,
+) -> List[FileAnalysis]:
+    """
+    Awaitable variant of [`analyze_files`][sportgems.analyze_files].
+    """
+    return await _aio.analyze_files(paths, distances, tolerance)
+
+
+__all__ = [
+    'find_fastest_section',
+    'find_fastest_section_in_fit',
+    'find_best_climb_section',
+    'find_best_climb_section_in_fit',
+    'parse_fit_data',
+    'analyze_files',
+]
@@ -0,0 +1,97 @@
+use crate::climb;
+use crate::dtypes;
+use crate::errors;
+use crate::fit_reader;
+use crate::velocity;
+use rayon::prelude::*;
+
+// results of analyzing a single file, holding one result per requested distance
+#[derive(Debug, PartialEq)]
+pub struct FileAnalysis {
+    pub path: String,
+    pub fastest_sections: Vec<Result<dtypes::TargetSection, errors::InputDataError>>,
+    pub climb_sections: Vec<Result<dtypes::TargetSection, errors::InputDataError>>,
+}
+
+pub fn analyze_file(
+    path: &str,
+    distances: &[f64],
+    tolerance: Option<f64>,
+) -> Result<FileAnalysis, errors::InputDataError> {
+    let fit_data = fit_reader::parse_fit(path)?;
+    Ok(FileAnalysis {
+        path: path.to_string(),
+        fastest_sections: distances
+            .iter()
+            .map(|d| velocity::find_fastest_section_in_fit_data(*d, &fit_data, tolerance))
+            .collect(),
+        climb_sections: distances
+            .iter()
+            .map(|d| climb::find_best_climb_section_in_fit_data(*d, &fit_data, tolerance))
+            .collect(),
+    })
+}
+
+// parse and analyze all files in parallel, a file which cannot be parsed does not abort the batch
+// but results in an error at the corresponding position of the returned vector
+pub fn analyze_files(
+    paths: &[String],
+    distances: &[f64],
+    tolerance: Option<f64>,
+) -> Vec<Result<FileAnalysis, errors::InputDataError>> {
+    paths
+        .par_iter()
+        .map(|path| analyze_file(path, distances, tolerance))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_batch {
+    use super::*;
+    use crate::synthetic;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_analyze_file() {
+        let result = analyze_file(FIT_FILE, &[1_000., 10_000.], Some(0.01)).unwrap();
+        assert_eq!(result.path, FIT_FILE);
+        assert_eq!(
+            result.fastest_sections[0],
+            velocity::find_fastest_section_in_fit(1_000., FIT_FILE, Some(0.01))
+        );
+        assert_eq!(
+            result.climb_sections[0],
+            climb::find_best_climb_section_in_fit(1_000., FIT_FILE, Some(0.01))
+        );
+        // too large distances do not fail the whole file
+        assert_eq!(
+            result.fastest_sections[1],
+            Err(errors::InputDataError::DistanceTooSmall)
+        );
+        assert_eq!(
+            result.climb_sections[1],
+            Err(errors::InputDataError::DistanceTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_analyze_files() {
+        let synthetic_file = std::env::temp_dir().join("sportgems_test_analyze_files.fit");
+        synthetic::write_fit(&synthetic::generate_activity(3_600), &synthetic_file).unwrap();
+        let paths = vec![
+            FIT_FILE.to_string(),
+            "tests/data/does_not_exist.fit".to_string(),
+            synthetic_file.to_str().unwrap().to_string(),
+        ];
+        let results = analyze_files(&paths, &[1_000., 2_000.], None);
+        assert_eq!(results.len(), 3);
+        // results are returned in the order of the given paths
+        assert_eq!(results[0], analyze_file(FIT_FILE, &[1_000., 2_000.], None));
+        assert_eq!(results[1], Err(errors::InputDataError::FileNotReadable));
+        let synthetic_result = results[2].as_ref().unwrap();
+        assert_eq!(synthetic_result.path, paths[2]);
+        assert!(synthetic_result.fastest_sections.iter().all(|r| r.is_ok()));
+        assert!(synthetic_result.climb_sections.iter().all(|r| r.is_ok()));
+    }
+}
@@ -0,0 +1,253 @@
+use clap::{Args, ValueEnum};
+use rayon::prelude::*;
+use sportgems::export;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+// file extensions of all activity formats which can be parsed
+const SUPPORTED_EXTENSIONS: [&str; 1] = ["fit"];
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Format {
+    Csv,
+    Parquet,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// Activity files or directories, which are searched recursively for activity files
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// Format of the converted files
+    #[arg(short, long, value_enum, default_value_t = Format::Parquet)]
+    format: Format,
+    /// Directory to write the converted files to, defaults to the directory of each input file.
+    /// The directory structure of given input directories is kept, inputs which would be
+    /// converted to the same file are rejected.
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+}
+
+fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if is_supported(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// returns pairs of input file and the path of the converted file relative to the output directory
+fn collect_inputs(inputs: &[PathBuf]) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut pairs = vec![];
+    for input in inputs {
+        if input.is_dir() {
+            let mut files = vec![];
+            collect_files(input, &mut files)?;
+            files.sort();
+            for file in files {
+                let relative = file.strip_prefix(input).unwrap().to_path_buf();
+                pairs.push((file, relative));
+            }
+        } else {
+            let name = input.file_name().map(PathBuf::from).unwrap_or_default();
+            pairs.push((input.clone(), name));
+        }
+    }
+    Ok(pairs)
+}
+
+fn output_path(
+    input: &Path,
+    relative: &Path,
+    output_dir: Option<&Path>,
+    format: Format,
+) -> PathBuf {
+    match output_dir {
+        Some(dir) => dir.join(relative),
+        None => input.to_path_buf(),
+    }
+    .with_extension(format.extension())
+}
+
+// pairs of input file and converted file, several inputs must not be converted to the same file,
+// e.g. files with equal relative paths in two input directories
+fn output_paths(
+    pairs: &[(PathBuf, PathBuf)],
+    output_dir: Option<&Path>,
+    format: Format,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut inputs: HashMap<PathBuf, &Path> = HashMap::new();
+    let mut outputs = vec![];
+    for (input, relative) in pairs {
+        let output = output_path(input, relative, output_dir, format);
+        if let Some(other) = inputs.insert(output.clone(), input) {
+            return Err(format!(
+                "{} and {} would both be converted to {}",
+                other.display(),
+                input.display(),
+                output.display()
+            ));
+        }
+        outputs.push((input.clone(), output));
+    }
+    Ok(outputs)
+}
+
+fn convert_file(input: &Path, output: &Path, format: Format) -> Result<(), String> {
+    let fit_data = crate::quiet::parse_fit(input.to_str().ok_or("invalid path")?)
+        .map_err(|e| e.to_string())?;
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    match format {
+        Format::Csv => export::write_csv_file(&fit_data, output),
+        Format::Parquet => export::write_parquet_file(&fit_data, output),
+    }
+    .map_err(|e| e.to_string())
+}
+
+pub fn run(args: ConvertArgs) -> ExitCode {
+    let pairs = match collect_inputs(&args.inputs) {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let pairs = match output_paths(&pairs, args.output_dir.as_deref(), args.format) {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    // stdout is silenced while any file is parsed, the progress is therefore printed afterwards
+    let results = pairs
+        .par_iter()
+        .map(|(input, output)| convert_file(input, output, args.format))
+        .collect::<Vec<_>>();
+    let mut failures = 0;
+    for ((input, output), result) in pairs.iter().zip(results) {
+        match result {
+            Ok(_) => println!("{} -> {}", input.display(), output.display()),
+            Err(e) => {
+                eprintln!("error: could not convert {}: {}", input.display(), e);
+                failures += 1;
+            }
+        }
+    }
+    eprintln!(
+        "converted {} of {} files",
+        pairs.len() - failures,
+        pairs.len()
+    );
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod test_convert {
+    use super::*;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_is_supported() {
+        assert!(is_supported(Path::new("a/b.fit")));
+        assert!(is_supported(Path::new("a/b.FIT")));
+        assert!(!is_supported(Path::new("a/b.gpx")));
+        assert!(!is_supported(Path::new("a/fit")));
+    }
+
+    #[test]
+    fn test_collect_inputs() {
+        let dir = std::env::temp_dir().join("sportgems_test_collect_inputs");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for file in ["a.fit", "b.txt", "nested/c.FIT"] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+        let pairs = collect_inputs(&[dir.clone(), PathBuf::from("x/y.fit")]).unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                (dir.join("a.fit"), PathBuf::from("a.fit")),
+                (dir.join("nested/c.FIT"), PathBuf::from("nested/c.FIT")),
+                (PathBuf::from("x/y.fit"), PathBuf::from("y.fit")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_paths() {
+        let pairs = vec![
+            (PathBuf::from("2019/a.fit"), PathBuf::from("a.fit")),
+            (PathBuf::from("2020/a.fit"), PathBuf::from("a.fit")),
+        ];
+        assert_eq!(
+            output_paths(&pairs, None, Format::Csv).unwrap(),
+            vec![
+                (PathBuf::from("2019/a.fit"), PathBuf::from("2019/a.csv")),
+                (PathBuf::from("2020/a.fit"), PathBuf::from("2020/a.csv")),
+            ]
+        );
+        // equal relative paths in two input directories clobber each other in the output dir
+        assert_eq!(
+            output_paths(&pairs, Some(Path::new("out")), Format::Csv),
+            Err("2019/a.fit and 2020/a.fit would both be converted to out/a.csv".to_string())
+        );
+    }
+
+    #[test]
+    fn test_output_path() {
+        let input = Path::new("tests/data/a.fit");
+        let relative = Path::new("data/a.fit");
+        assert_eq!(
+            output_path(input, relative, None, Format::Csv),
+            PathBuf::from("tests/data/a.csv")
+        );
+        assert_eq!(
+            output_path(input, relative, Some(Path::new("out")), Format::Parquet),
+            PathBuf::from("out/data/a.parquet")
+        );
+    }
+
+    #[test]
+    fn test_convert_file() {
+        let output = std::env::temp_dir().join("sportgems_test_convert/nested/activity.csv");
+        convert_file(Path::new(FIT_FILE), &output, Format::Csv).unwrap();
+        let csv = fs::read_to_string(&output).unwrap();
+        assert!(csv.starts_with("timestamp,lat,lon,altitude,distance\n"));
+        assert_eq!(csv.lines().count(), 1232);
+        assert_eq!(
+            convert_file(Path::new("does_not_exist.fit"), &output, Format::Csv),
+            Err(sportgems::errors::FILE_NOT_READABLE_MSG.to_string())
+        );
+    }
+}
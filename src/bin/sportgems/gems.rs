@@ -0,0 +1,239 @@
+use clap::Args;
+use serde_json::{json, Value};
+use sportgems::climb::GainMethod;
+use sportgems::dtypes::TargetSection;
+use sportgems::errors::InputDataError;
+use sportgems::gem_finder::FinderOptions;
+use sportgems::math::{DistanceModel, FillStrategy};
+use sportgems::pauses::MovingTime;
+use sportgems::smoothing::Smoothing;
+use sportgems::{climb, fit_reader, timestamps, velocity};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Args)]
+pub struct GemArgs {
+    /// Fit files to search
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Length in meter of the desired section, can be given multiple times
+    #[arg(short, long = "distance", required = true)]
+    distances: Vec<f64>,
+    /// Percentage value in which the length of a section is still considered to be equal to
+    /// the desired distance
+    #[arg(short, long)]
+    tolerance: Option<f64>,
+    /// Model used to compute distances between coordinates, one of: spherical, haversine,
+    /// equirectangular, geodesic
+    #[arg(long, default_value = "spherical")]
+    distance_model: DistanceModel,
+    /// Include the altitude change in the distance between two records
+    #[arg(long)]
+    slope_distance: bool,
+    /// Filter applied to the coordinates before computing distances, one of: none,
+    /// moving-average[:window], savitzky-golay[:window[:order]],
+    /// kalman[:process_noise[:measurement_noise]]
+    #[arg(long, default_value = "none")]
+    smoothing: Smoothing,
+    /// Filter applied to the altitudes before computing the gained altitude, same values as
+    /// for `--smoothing`
+    #[arg(long, default_value = "none")]
+    elevation_smoothing: Smoothing,
+    /// Method to sum up the gained altitude, one of: sum, hysteresis[:threshold]. The
+    /// hysteresis ignores altitude changes below the threshold in meter (default 3)
+    #[arg(long, default_value = "sum")]
+    gain_method: GainMethod,
+    /// Strategy to fill missing records, one of: forward, linear[:max_gap]. The linear
+    /// interpolation fills gaps up to max_gap seconds (default 60), longer gaps stay missing
+    /// and add no distance
+    #[arg(long, default_value = "forward")]
+    fill: FillStrategy,
+    /// Time the velocity of the fastest sections is computed on, one of: elapsed, timer,
+    /// stationary[:max_speed[:min_duration]]. The timer skips the pauses of the device timer,
+    /// stationary additionally skips stops slower than max_speed in m/s (default 0.5) lasting
+    /// at least min_duration seconds (default 10)
+    #[arg(long, default_value = "elapsed")]
+    moving_time: MovingTime,
+    /// Gap in seconds between two records, which no section spans, `inf` searches across gaps
+    #[arg(long, default_value_t = timestamps::DEFAULT_SEGMENT_GAP)]
+    segment_gap: f64,
+    /// Print the results as json
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gem {
+    Fastest,
+    Climb,
+}
+
+impl Gem {
+    fn find(
+        self,
+        distance: f64,
+        fit_data: &fit_reader::FitData,
+        options: &FinderOptions,
+    ) -> Result<TargetSection, InputDataError> {
+        match self {
+            Gem::Fastest => {
+                velocity::find_fastest_section_in_fit_data_with_options(distance, fit_data, options)
+            }
+            Gem::Climb => {
+                climb::find_best_climb_section_in_fit_data_with_options(distance, fit_data, options)
+            }
+        }
+    }
+
+    fn value_name(self) -> &'static str {
+        match self {
+            Gem::Fastest => "velocity",
+            Gem::Climb => "climb",
+        }
+    }
+
+    fn describe(self, section: &TargetSection) -> String {
+        match self {
+            Gem::Fastest => format!(
+                "{:.2} m/s ({:.2} km/h)",
+                section.target_value,
+                section.target_value * 3.6
+            ),
+            Gem::Climb => format!("{:.2} m/min", section.target_value),
+        }
+    }
+}
+
+fn section_to_json(
+    gem: Gem,
+    distance: f64,
+    result: &Result<TargetSection, InputDataError>,
+) -> Value {
+    match result {
+        Ok(section) => json!({
+            "desired_distance": distance,
+            "start": section.start,
+            "end": section.end,
+            gem.value_name(): section.target_value,
+            "distance": section.distance,
+            "duration": section.duration,
+            "start_time": section.start_time,
+            "end_time": section.end_time,
+            "start_coordinate": section.start_coordinate,
+            "end_coordinate": section.end_coordinate,
+            "elevation_gain": section.elevation_gain,
+        }),
+        Err(e) => json!({ "desired_distance": distance, "error": e.to_string() }),
+    }
+}
+
+fn print_human(gem: Gem, file: &Path, results: &[(f64, Result<TargetSection, InputDataError>)]) {
+    println!("{}", file.display());
+    for (distance, result) in results {
+        let name = match gem {
+            Gem::Fastest => "fastest",
+            Gem::Climb => "best climb",
+        };
+        match result {
+            Ok(section) => println!(
+                "  {} {} m: records {} to {}, {}",
+                name,
+                distance,
+                section.start,
+                section.end,
+                gem.describe(section)
+            ),
+            Err(e) => println!("  {} {} m: {}", name, distance, e),
+        }
+    }
+}
+
+pub fn run(gem: Gem, args: GemArgs) -> ExitCode {
+    let options = FinderOptions {
+        distance_model: args.distance_model,
+        slope_distance: args.slope_distance,
+        smoothing: args.smoothing,
+        elevation_smoothing: args.elevation_smoothing,
+        gain_method: args.gain_method,
+        fill: args.fill,
+        moving_time: args.moving_time,
+        segment_gap: args.segment_gap,
+        ..FinderOptions::with_tolerance(args.tolerance)
+    };
+    let mut success = true;
+    let mut json_output = vec![];
+    for file in &args.files {
+        let fit_data = match crate::quiet::parse_fit(&file.to_string_lossy()) {
+            Ok(fit_data) => fit_data,
+            Err(e) => {
+                success = false;
+                if args.json {
+                    json_output.push(json!({ "path": file, "error": e.to_string() }));
+                } else {
+                    eprintln!("error: {}: {}", file.display(), e);
+                }
+                continue;
+            }
+        };
+        let results: Vec<_> = args
+            .distances
+            .iter()
+            .map(|d| (*d, gem.find(*d, &fit_data, &options)))
+            .collect();
+        success &= results.iter().all(|(_, r)| r.is_ok());
+        if args.json {
+            json_output.push(json!({
+                "path": file,
+                "sections": results
+                    .iter()
+                    .map(|(d, r)| section_to_json(gem, *d, r))
+                    .collect::<Vec<_>>(),
+            }));
+        } else {
+            print_human(gem, file, &results);
+        }
+    }
+    if args.json {
+        println!("{}", Value::Array(json_output));
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod test_gems {
+    use super::*;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_section_to_json() {
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        let result = Gem::Fastest.find(1_000., &fit_data, &FinderOptions::default());
+        let value = section_to_json(Gem::Fastest, 1_000., &result);
+        assert_eq!(value["start"], 628);
+        assert_eq!(value["end"], 719);
+        let section = result.unwrap();
+        assert_eq!(value["velocity"], section.target_value);
+        assert_eq!(value["desired_distance"], 1_000.);
+        assert_eq!(value["distance"], section.distance);
+        assert_eq!(value["duration"], section.duration);
+        assert_eq!(value["start_time"], section.start_time);
+        assert_eq!(value["end_time"], section.end_time);
+        assert_eq!(value["start_coordinate"], json!(section.start_coordinate));
+        assert_eq!(value["end_coordinate"], json!(section.end_coordinate));
+        assert_eq!(value["elevation_gain"], json!(section.elevation_gain));
+
+        let result = Gem::Climb.find(100_000., &fit_data, &FinderOptions::default());
+        assert_eq!(
+            section_to_json(Gem::Climb, 100_000., &result),
+            json!({
+                "desired_distance": 100_000.,
+                "error": sportgems::errors::DISTANCE_TOO_SMALL_MSG,
+            })
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use chrono::DateTime;
+use clap::Args;
+use serde_json::{json, Value};
+use sportgems::climb::GainMethod;
+use sportgems::gem_finder::{self, FinderOptions};
+use sportgems::smoothing::Smoothing;
+use sportgems::{climb, fit_reader, math, timestamps};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Fit files to summarize
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Model used to compute the distance, see the `--distance-model` option of the gem commands
+    #[arg(long, default_value = "spherical")]
+    distance_model: math::DistanceModel,
+    /// Filter applied to the altitudes before computing the ascent and descent, see the
+    /// `--smoothing` option of the gem commands
+    #[arg(long, default_value = "none")]
+    elevation_smoothing: Smoothing,
+    /// Method to sum up the ascent and descent, one of: sum, hysteresis[:threshold]
+    #[arg(long, default_value = "sum")]
+    gain_method: GainMethod,
+    /// Strategy to fill missing altitudes, one of: forward, linear[:max_gap]
+    #[arg(long, default_value = "forward")]
+    fill: math::FillStrategy,
+    /// Gap in seconds between two records, which splits the activity into segments
+    #[arg(long, default_value_t = timestamps::DEFAULT_SEGMENT_GAP)]
+    segment_gap: f64,
+    /// Print the results as json
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, PartialEq)]
+struct Info {
+    records: usize,
+    calories: u16,
+    start_time: Option<f64>,
+    end_time: Option<f64>,
+    distance: f64,
+    min_altitude: Option<f64>,
+    max_altitude: Option<f64>,
+    ascent: Option<f64>,
+    descent: Option<f64>,
+    // parts between gaps and clock resets
+    segments: usize,
+}
+
+fn total_distance(coordinates: &[(f64, f64)], options: &FinderOptions) -> f64 {
+    if coordinates.is_empty() {
+        return 0.;
+    }
+    let distances = gem_finder::cumulative_distances(coordinates, options.distance_model);
+    distances[distances.len() - 1]
+}
+
+fn min_max(values: &[f64]) -> (Option<f64>, Option<f64>) {
+    let valid = values.iter().copied().filter(|v| !v.is_nan());
+    (valid.clone().reduce(f64::min), valid.reduce(f64::max))
+}
+
+// only records with a valid position are taken into account, fit files also contain other messages
+// like device infos or laps, which would otherwise add bogus timestamps and altitudes. Indoor
+// activities have no positions, there the records with a recorded distance are used instead.
+fn summarize(fit_data: &fit_reader::FitData, options: &FinderOptions) -> Info {
+    let device_distances = fit_data.use_device_distances();
+    let valid: Vec<usize> = (0..fit_data.coordinates.len())
+        .filter(|&i| {
+            if device_distances {
+                !fit_data.distances[i].is_nan()
+            } else {
+                fit_data.coordinates[i].0.is_normal() && fit_data.coordinates[i].1.is_normal()
+            }
+        })
+        .collect();
+    let select = |values: &[f64]| valid.iter().map(|&i| values[i]).collect::<Vec<f64>>();
+    let coordinates: Vec<(f64, f64)> = valid.iter().map(|&i| fit_data.coordinates[i]).collect();
+    let times = select(&fit_data.times);
+    let (start_time, end_time) = min_max(&times);
+    let (min_altitude, max_altitude) = min_max(&select(&fit_data.altitudes));
+    let (ascent, descent) = match min_altitude {
+        Some(_) => {
+            let (ascent, descent) =
+                climb::total_ascent_descent(&select(&fit_data.altitudes), &times, options);
+            (Some(ascent), Some(descent))
+        }
+        None => (None, None),
+    };
+    let distance = if device_distances {
+        match min_max(&select(&fit_data.distances)) {
+            (Some(min), Some(max)) => max - min,
+            _ => 0.,
+        }
+    } else {
+        total_distance(&coordinates, options)
+    };
+    Info {
+        records: valid.len(),
+        calories: fit_data.calories,
+        start_time,
+        end_time,
+        distance,
+        min_altitude,
+        max_altitude,
+        ascent,
+        descent,
+        segments: timestamps::split_segments(&times, options.segment_gap).len(),
+    }
+}
+
+fn format_time(time: Option<f64>) -> String {
+    time.and_then(|t| DateTime::from_timestamp(t as i64, 0))
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_optional(value: Option<f64>, unit: &str) -> String {
+    value
+        .map(|v| format!("{:.1} {}", v, unit))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_human(file: &Path, info: &Info) {
+    let duration = match (info.start_time, info.end_time) {
+        (Some(start), Some(end)) => end - start,
+        _ => 0.,
+    };
+    println!("{}", file.display());
+    println!("  records:    {}", info.records);
+    println!("  start time: {}", format_time(info.start_time));
+    println!("  end time:   {}", format_time(info.end_time));
+    println!(
+        "  duration:   {}:{:02}:{:02}",
+        duration as u64 / 3600,
+        duration as u64 % 3600 / 60,
+        duration as u64 % 60
+    );
+    println!("  distance:   {:.2} km", info.distance / 1_000.);
+    println!(
+        "  altitude:   {} to {}",
+        format_optional(info.min_altitude, "m"),
+        format_optional(info.max_altitude, "m")
+    );
+    println!("  ascent:     {}", format_optional(info.ascent, "m"));
+    println!("  descent:    {}", format_optional(info.descent, "m"));
+    println!("  segments:   {}", info.segments);
+    println!("  calories:   {} kcal", info.calories);
+}
+
+fn info_to_json(file: &PathBuf, info: &Info) -> Value {
+    json!({
+        "path": file,
+        "records": info.records,
+        "start_time": info.start_time,
+        "end_time": info.end_time,
+        "distance": info.distance,
+        "min_altitude": info.min_altitude,
+        "max_altitude": info.max_altitude,
+        "ascent": info.ascent,
+        "descent": info.descent,
+        "segments": info.segments,
+        "calories": info.calories,
+    })
+}
+
+pub fn run(args: InfoArgs) -> ExitCode {
+    let options = FinderOptions {
+        distance_model: args.distance_model,
+        elevation_smoothing: args.elevation_smoothing,
+        gain_method: args.gain_method,
+        fill: args.fill,
+        segment_gap: args.segment_gap,
+        ..Default::default()
+    };
+    let mut success = true;
+    let mut json_output = vec![];
+    for file in &args.files {
+        match crate::quiet::parse_fit(&file.to_string_lossy()) {
+            Ok(fit_data) => {
+                let info = summarize(&fit_data, &options);
+                if args.json {
+                    json_output.push(info_to_json(file, &info));
+                } else {
+                    print_human(file, &info);
+                }
+            }
+            Err(e) => {
+                success = false;
+                if args.json {
+                    json_output.push(json!({ "path": file, "error": e.to_string() }));
+                } else {
+                    eprintln!("error: {}: {}", file.display(), e);
+                }
+            }
+        }
+    }
+    if args.json {
+        println!("{}", Value::Array(json_output));
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod test_info {
+    use super::*;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_summarize() {
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        let info = summarize(&fit_data, &FinderOptions::default());
+        assert_eq!(info.records, 1157);
+        assert_eq!(info.calories, 432);
+        assert_eq!(info.segments, 1);
+        assert!(info.start_time.unwrap() < info.end_time.unwrap());
+        assert!(info.distance > 5_000. && info.distance < 5_500.);
+        assert_eq!(format_optional(info.min_altitude, "m"), "238.2 m");
+        assert!(info.ascent.unwrap() > 0. && info.descent.unwrap() > 0.);
+        let options = FinderOptions {
+            gain_method: "hysteresis".parse().unwrap(),
+            ..Default::default()
+        };
+        let hysteresis = summarize(&fit_data, &options);
+        assert!(hysteresis.ascent.unwrap() < info.ascent.unwrap());
+    }
+
+    #[test]
+    fn test_summarize_skips_nans() {
+        let fit_data = fit_reader::FitData {
+            calories: 0,
+            times: vec![f64::NAN, 10., 20.],
+            coordinates: vec![(f64::NAN, f64::NAN), (48.0, 8.0), (48.0, 8.1)],
+            altitudes: vec![f64::NAN, f64::NAN, f64::NAN],
+            distances: vec![],
+            timer_pauses: vec![],
+            record_messages: vec![],
+        };
+        let info = summarize(&fit_data, &FinderOptions::default());
+        assert_eq!(info.start_time, Some(10.));
+        assert_eq!(info.end_time, Some(20.));
+        assert_eq!(info.distance, 7448.684105664539);
+        let options = FinderOptions {
+            distance_model: math::DistanceModel::Geodesic,
+            ..Default::default()
+        };
+        let geodesic = summarize(&fit_data, &options);
+        assert!((geodesic.distance - info.distance).abs() > 1.);
+        assert!((geodesic.distance - info.distance).abs() < 50.);
+        assert_eq!(info.min_altitude, None);
+        assert_eq!(info.ascent, None);
+        assert_eq!(format_time(Some(1568474841.)), "2019-09-14T15:27:21+00:00");
+    }
+
+    #[test]
+    fn test_summarize_indoor_activity() {
+        let fit_data = fit_reader::FitData {
+            calories: 0,
+            times: vec![f64::NAN, 10., 20., 30.],
+            coordinates: vec![(f64::NAN, f64::NAN); 4],
+            altitudes: vec![f64::NAN; 4],
+            distances: vec![f64::NAN, 0., 40., 85.],
+            timer_pauses: vec![],
+            record_messages: vec![],
+        };
+        let info = summarize(&fit_data, &FinderOptions::default());
+        assert_eq!(info.records, 3);
+        assert_eq!(info.start_time, Some(10.));
+        assert_eq!(info.distance, 85.);
+        let options = FinderOptions {
+            segment_gap: 5.,
+            ..Default::default()
+        };
+        assert_eq!(summarize(&fit_data, &options).segments, 3);
+    }
+}
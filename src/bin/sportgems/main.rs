@@ -0,0 +1,36 @@
+use clap::{Parser, Subcommand};
+use std::process::ExitCode;
+
+mod convert;
+mod gems;
+mod info;
+mod quiet;
+
+/// Find valuable gems in your tracked sport activities.
+#[derive(Parser)]
+#[command(name = "sportgems", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert fit files into csv or parquet files with the columns timestamp, lat, lon and altitude
+    Convert(convert::ConvertArgs),
+    /// Find the fastest sections of the given distances
+    Fastest(gems::GemArgs),
+    /// Find the best climb sections of the given distances
+    Climb(gems::GemArgs),
+    /// Print a summary of fit files
+    Info(info::InfoArgs),
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Command::Convert(args) => convert::run(args),
+        Command::Fastest(args) => gems::run(gems::Gem::Fastest, args),
+        Command::Climb(args) => gems::run(gems::Gem::Climb, args),
+        Command::Info(args) => info::run(args),
+    }
+}
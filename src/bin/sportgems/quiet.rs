@@ -0,0 +1,81 @@
+use sportgems::{errors, fit_reader};
+
+// the fit crate prints debug output to stdout while parsing, which would end up in the csv or json
+// output of the command-line tool. Stdout is therefore redirected to /dev/null while parsing. The
+// redirect is shared by all threads parsing at the same time and restored by the last of them.
+// Only the command-line tool is silenced this way, the library prints the debug output to the
+// stdout of the host process.
+#[cfg(unix)]
+mod redirect {
+    use std::io::{self, Write};
+    use std::sync::Mutex;
+
+    // number of threads currently parsing and the saved stdout file descriptor
+    static STDOUT: Mutex<(usize, libc::c_int)> = Mutex::new((0, -1));
+
+    pub fn silence() {
+        let mut state = STDOUT.lock().unwrap_or_else(|e| e.into_inner());
+        if state.0 == 0 {
+            let _ = io::stdout().flush();
+            unsafe {
+                let devnull = libc::open(
+                    b"/dev/null\0".as_ptr() as *const libc::c_char,
+                    libc::O_WRONLY,
+                );
+                if devnull < 0 {
+                    return;
+                }
+                state.1 = libc::dup(libc::STDOUT_FILENO);
+                libc::dup2(devnull, libc::STDOUT_FILENO);
+                libc::close(devnull);
+            }
+        }
+        state.0 += 1;
+    }
+
+    pub fn restore() {
+        let mut state = STDOUT.lock().unwrap_or_else(|e| e.into_inner());
+        if state.0 == 0 {
+            return;
+        }
+        state.0 -= 1;
+        if state.0 == 0 {
+            let _ = io::stdout().flush();
+            unsafe {
+                libc::dup2(state.1, libc::STDOUT_FILENO);
+                libc::close(state.1);
+            }
+            state.1 = -1;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod redirect {
+    pub fn silence() {}
+    pub fn restore() {}
+}
+
+// restores stdout when dropped, also if parsing panics
+struct Silenced;
+
+impl Silenced {
+    fn new() -> Silenced {
+        redirect::silence();
+        Silenced
+    }
+}
+
+impl Drop for Silenced {
+    fn drop(&mut self) {
+        redirect::restore();
+    }
+}
+
+// the redirect applies to the whole process, anything another thread prints to stdout while a file
+// is parsed is lost as well. It must therefore not run alongside other stdout writers, e.g. the
+// results are printed only after all files of a command are parsed.
+pub fn parse_fit(path: &str) -> Result<fit_reader::FitData, errors::InputDataError> {
+    let _silenced = Silenced::new();
+    fit_reader::parse_fit(path)
+}
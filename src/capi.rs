@@ -0,0 +1,432 @@
+use crate::{climb, dtypes, errors, fit_reader, velocity};
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::slice;
+use std::sync::OnceLock;
+
+// c api for embedding sportgems into non-python applications, see `include/sportgems.h`. Activities
+// are handed out as opaque pointers, which need to be released with `sportgems_activity_free`.
+
+// cbindgen can only export literals, kept in sync with `gem_finder::DEFAULT_TOLERANCE` by a test
+pub const SPORTGEMS_DEFAULT_TOLERANCE: f64 = 0.01;
+
+/// Error codes returned by all functions, mirroring `InputDataError`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SportgemsError {
+    Ok = 0,
+    InconsistentLength = 1,
+    TooFewDataPoints = 2,
+    DistanceTooSmall = 3,
+    NoSectionFound = 4,
+    InvalidDesiredDistance = 5,
+    InvalidFitFile = 6,
+    /// A required pointer argument was null.
+    NullPointer = 7,
+    FileNotReadable = 8,
+}
+
+const OK_MSG: &str = "Ok.";
+const NULL_POINTER_MSG: &str = "A required pointer argument was null.";
+const UNKNOWN_ERROR_MSG: &str = "Unknown error.";
+
+// all error codes, ordered by their value
+const ERRORS: [SportgemsError; 9] = [
+    SportgemsError::Ok,
+    SportgemsError::InconsistentLength,
+    SportgemsError::TooFewDataPoints,
+    SportgemsError::DistanceTooSmall,
+    SportgemsError::NoSectionFound,
+    SportgemsError::InvalidDesiredDistance,
+    SportgemsError::InvalidFitFile,
+    SportgemsError::NullPointer,
+    SportgemsError::FileNotReadable,
+];
+
+impl SportgemsError {
+    fn message(self) -> &'static str {
+        match self {
+            SportgemsError::Ok => OK_MSG,
+            SportgemsError::InconsistentLength => errors::INCONSISTENT_LENGTH_MSG,
+            SportgemsError::TooFewDataPoints => errors::TOO_FEW_DATA_POINTS_MSG,
+            SportgemsError::DistanceTooSmall => errors::DISTANCE_TOO_SMALL_MSG,
+            SportgemsError::NoSectionFound => errors::NO_SECTION_FOUND_MSG,
+            SportgemsError::InvalidDesiredDistance => errors::INVALID_DESIRED_DISTANCE_MSG,
+            SportgemsError::InvalidFitFile => errors::INVALID_FIT_FILE_MSG,
+            SportgemsError::NullPointer => NULL_POINTER_MSG,
+            SportgemsError::FileNotReadable => errors::FILE_NOT_READABLE_MSG,
+        }
+    }
+}
+
+impl From<errors::InputDataError> for SportgemsError {
+    fn from(err: errors::InputDataError) -> SportgemsError {
+        match err {
+            errors::InputDataError::InconsistentLength => SportgemsError::InconsistentLength,
+            errors::InputDataError::TooFewDataPoints => SportgemsError::TooFewDataPoints,
+            errors::InputDataError::DistanceTooSmall => SportgemsError::DistanceTooSmall,
+            errors::InputDataError::NoSectionFound => SportgemsError::NoSectionFound,
+            errors::InputDataError::InvalidDesiredDistance => {
+                SportgemsError::InvalidDesiredDistance
+            }
+            errors::InputDataError::InvalidFitFile => SportgemsError::InvalidFitFile,
+            errors::InputDataError::FileNotReadable => SportgemsError::FileNotReadable,
+        }
+    }
+}
+
+/// Opaque handle of a parsed activity.
+pub struct SportgemsActivity {
+    fit_data: fit_reader::FitData,
+}
+
+/// Resulting section of a search, `value` holds the velocity in m/s for the fastest section and
+/// the climb in m/min for the best climb section.
+#[repr(C)]
+#[derive(Debug, Default, PartialEq)]
+pub struct SportgemsSection {
+    pub start: u32,
+    pub end: u32,
+    pub value: f64,
+}
+
+impl From<dtypes::TargetSection> for SportgemsSection {
+    fn from(section: dtypes::TargetSection) -> SportgemsSection {
+        SportgemsSection {
+            start: section.start,
+            end: section.end,
+            value: section.target_value,
+        }
+    }
+}
+
+fn into_handle(
+    fit_data: fit_reader::FitData,
+    activity: *mut *mut SportgemsActivity,
+) -> SportgemsError {
+    unsafe { *activity = Box::into_raw(Box::new(SportgemsActivity { fit_data })) };
+    SportgemsError::Ok
+}
+
+fn write_section(
+    result: Result<dtypes::TargetSection, errors::InputDataError>,
+    section: *mut SportgemsSection,
+) -> SportgemsError {
+    match result {
+        Ok(result) => {
+            unsafe { *section = result.into() };
+            SportgemsError::Ok
+        }
+        Err(e) => e.into(),
+    }
+}
+
+/// Parses the fit file given as `len` bytes at `data` into a new activity, which is written to
+/// `activity`.
+///
+/// # Safety
+/// `data` must point to `len` readable bytes and `activity` to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sportgems_activity_from_fit(
+    data: *const u8,
+    len: usize,
+    activity: *mut *mut SportgemsActivity,
+) -> SportgemsError {
+    if data.is_null() || activity.is_null() {
+        return SportgemsError::NullPointer;
+    }
+    match fit_reader::parse_fit_bytes(slice::from_raw_parts(data, len)) {
+        Ok(fit_data) => into_handle(fit_data, activity),
+        Err(e) => e.into(),
+    }
+}
+
+/// Creates a new activity from `len` data points, `altitudes` may be null in case no altitude
+/// data is available. The arrays are copied.
+///
+/// # Safety
+/// All non-null arrays must hold `len` values and `activity` must point to a writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn sportgems_activity_from_arrays(
+    times: *const f64,
+    latitudes: *const f64,
+    longitudes: *const f64,
+    altitudes: *const f64,
+    len: usize,
+    activity: *mut *mut SportgemsActivity,
+) -> SportgemsError {
+    if times.is_null() || latitudes.is_null() || longitudes.is_null() || activity.is_null() {
+        return SportgemsError::NullPointer;
+    }
+    let latitudes = slice::from_raw_parts(latitudes, len);
+    let longitudes = slice::from_raw_parts(longitudes, len);
+    let fit_data = fit_reader::FitData {
+        calories: 0,
+        times: slice::from_raw_parts(times, len).to_vec(),
+        coordinates: latitudes
+            .iter()
+            .copied()
+            .zip(longitudes.iter().copied())
+            .collect(),
+        altitudes: if altitudes.is_null() {
+            vec![f64::NAN; len]
+        } else {
+            slice::from_raw_parts(altitudes, len).to_vec()
+        },
+        distances: vec![],
+        timer_pauses: vec![],
+        record_messages: vec![],
+    };
+    into_handle(fit_data, activity)
+}
+
+/// Returns the number of data points of the activity, 0 for null.
+///
+/// # Safety
+/// `activity` must be null or a pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn sportgems_activity_len(activity: *const SportgemsActivity) -> usize {
+    activity.as_ref().map_or(0, |a| a.fit_data.times.len())
+}
+
+/// Searches the fastest section of `desired_distance` meter and writes it to `section`. Pass
+/// `SPORTGEMS_DEFAULT_TOLERANCE` as `tolerance` for the default.
+///
+/// # Safety
+/// `activity` must be a pointer returned by one of the constructors and `section` must be
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn sportgems_find_fastest_section(
+    activity: *const SportgemsActivity,
+    desired_distance: f64,
+    tolerance: f64,
+    section: *mut SportgemsSection,
+) -> SportgemsError {
+    match (activity.as_ref(), section.is_null()) {
+        (Some(activity), false) => write_section(
+            velocity::find_fastest_section_in_fit_data(
+                desired_distance,
+                &activity.fit_data,
+                Some(tolerance),
+            ),
+            section,
+        ),
+        _ => SportgemsError::NullPointer,
+    }
+}
+
+/// Searches the best climb section of `desired_distance` meter and writes it to `section`. Pass
+/// `SPORTGEMS_DEFAULT_TOLERANCE` as `tolerance` for the default.
+///
+/// # Safety
+/// `activity` must be a pointer returned by one of the constructors and `section` must be
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn sportgems_find_best_climb_section(
+    activity: *const SportgemsActivity,
+    desired_distance: f64,
+    tolerance: f64,
+    section: *mut SportgemsSection,
+) -> SportgemsError {
+    match (activity.as_ref(), section.is_null()) {
+        (Some(activity), false) => write_section(
+            climb::find_best_climb_section_in_fit_data(
+                desired_distance,
+                &activity.fit_data,
+                Some(tolerance),
+            ),
+            section,
+        ),
+        _ => SportgemsError::NullPointer,
+    }
+}
+
+/// Releases an activity, passing null is a no-op.
+///
+/// # Safety
+/// `activity` must be null or a pointer returned by one of the constructors, which has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn sportgems_activity_free(activity: *mut SportgemsActivity) {
+    if !activity.is_null() {
+        drop(Box::from_raw(activity));
+    }
+}
+
+// null terminated copies of the messages, indexed by the error code and followed by the message of
+// unknown codes. Created on first use and never freed.
+fn c_messages() -> &'static [CString] {
+    static MESSAGES: OnceLock<Vec<CString>> = OnceLock::new();
+    MESSAGES.get_or_init(|| {
+        ERRORS
+            .iter()
+            .map(|e| e.message())
+            .chain([UNKNOWN_ERROR_MSG])
+            .map(|message| CString::new(message).unwrap())
+            .collect()
+    })
+}
+
+/// Returns a static, null terminated description of the given error code, unknown codes are
+/// described as "Unknown error.".
+#[no_mangle]
+pub extern "C" fn sportgems_error_message(error: c_int) -> *const c_char {
+    let index = usize::try_from(error)
+        .ok()
+        .filter(|&i| i < ERRORS.len())
+        .unwrap_or(ERRORS.len());
+    c_messages()[index].as_ptr()
+}
+
+#[cfg(test)]
+mod test_capi {
+    use super::*;
+    use std::ffi::CStr;
+    use std::ptr;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_activity_from_fit() {
+        let bytes = std::fs::read(FIT_FILE).unwrap();
+        let mut activity = ptr::null_mut();
+        let mut section = SportgemsSection::default();
+        unsafe {
+            assert_eq!(
+                sportgems_activity_from_fit(bytes.as_ptr(), bytes.len(), &mut activity),
+                SportgemsError::Ok
+            );
+            assert_eq!(sportgems_activity_len(activity), 1231);
+            assert_eq!(
+                sportgems_find_fastest_section(activity, 1_000., 0.01, &mut section),
+                SportgemsError::Ok
+            );
+            assert_eq!((section.start, section.end), (628, 719));
+            assert_eq!(
+                sportgems_find_best_climb_section(activity, 1_000., 0.01, &mut section),
+                SportgemsError::Ok
+            );
+            assert_eq!((section.start, section.end), (346, 586));
+            assert_eq!(
+                sportgems_find_fastest_section(activity, 100_000., 0.01, &mut section),
+                SportgemsError::DistanceTooSmall
+            );
+            sportgems_activity_free(activity);
+        }
+    }
+
+    #[test]
+    fn test_activity_from_arrays() {
+        let times = [1608228953.8, 1608228954.8, 1608228955.8, 1608228956.8];
+        let latitudes = [48.123; 4];
+        let longitudes = [9.35, 9.36, 9.37, 9.38];
+        let mut activity = ptr::null_mut();
+        let mut section = SportgemsSection::default();
+        unsafe {
+            assert_eq!(
+                sportgems_activity_from_arrays(
+                    times.as_ptr(),
+                    latitudes.as_ptr(),
+                    longitudes.as_ptr(),
+                    ptr::null(),
+                    4,
+                    &mut activity
+                ),
+                SportgemsError::Ok
+            );
+            assert_eq!(
+                sportgems_find_fastest_section(activity, 1_000., 0.5, &mut section),
+                SportgemsError::Ok
+            );
+            assert_eq!((section.start, section.end), (0, 3));
+            assert_eq!(section.value.round(), 495.0);
+            // no altitudes given
+            assert_eq!(
+                sportgems_find_best_climb_section(activity, 1_000., 0.5, &mut section),
+                SportgemsError::TooFewDataPoints
+            );
+            sportgems_activity_free(activity);
+        }
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut activity = ptr::null_mut();
+        let mut section = SportgemsSection::default();
+        unsafe {
+            assert_eq!(
+                sportgems_activity_from_fit(b"no fit file".as_ptr(), 11, &mut activity),
+                SportgemsError::InvalidFitFile
+            );
+            assert!(activity.is_null());
+            assert_eq!(
+                sportgems_activity_from_fit(ptr::null(), 0, &mut activity),
+                SportgemsError::NullPointer
+            );
+            assert_eq!(
+                sportgems_find_fastest_section(ptr::null(), 1_000., 0.01, &mut section),
+                SportgemsError::NullPointer
+            );
+            assert_eq!(sportgems_activity_len(ptr::null()), 0);
+            sportgems_activity_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_default_tolerance() {
+        assert_eq!(
+            SPORTGEMS_DEFAULT_TOLERANCE,
+            crate::gem_finder::DEFAULT_TOLERANCE
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        let message = |e: SportgemsError| {
+            unsafe { CStr::from_ptr(sportgems_error_message(e as c_int)) }
+                .to_str()
+                .unwrap()
+        };
+        for (code, error) in ERRORS.iter().enumerate() {
+            assert_eq!(*error as usize, code);
+        }
+        assert_eq!(message(SportgemsError::Ok), "Ok.");
+        assert_eq!(
+            message(SportgemsError::InconsistentLength),
+            errors::INCONSISTENT_LENGTH_MSG
+        );
+        assert_eq!(
+            message(SportgemsError::TooFewDataPoints),
+            errors::TOO_FEW_DATA_POINTS_MSG
+        );
+        assert_eq!(
+            message(SportgemsError::DistanceTooSmall),
+            errors::DISTANCE_TOO_SMALL_MSG
+        );
+        assert_eq!(
+            message(SportgemsError::NoSectionFound),
+            errors::NO_SECTION_FOUND_MSG
+        );
+        assert_eq!(
+            message(SportgemsError::InvalidDesiredDistance),
+            errors::INVALID_DESIRED_DISTANCE_MSG
+        );
+        assert_eq!(
+            message(SportgemsError::InvalidFitFile),
+            errors::INVALID_FIT_FILE_MSG
+        );
+        assert_eq!(
+            message(SportgemsError::NullPointer),
+            "A required pointer argument was null."
+        );
+        assert_eq!(
+            message(SportgemsError::FileNotReadable),
+            errors::FILE_NOT_READABLE_MSG
+        );
+        for code in [-1, 9, c_int::MAX] {
+            let message = unsafe { CStr::from_ptr(sportgems_error_message(code)) };
+            assert_eq!(message.to_str().unwrap(), "Unknown error.");
+        }
+    }
+}
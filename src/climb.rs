@@ -3,13 +3,161 @@ use crate::errors;
 use crate::fit_reader;
 use crate::gem_finder;
 use crate::math;
+use crate::outliers::{self, OutlierFilter};
+use crate::smoothing;
+
+// spikes in the altitudes of fit files are treated as missing, barometric altimeters and gps
+// report spikes of several hundred meters at times
+pub const FIT_ALTITUDE_OUTLIER_FILTER: OutlierFilter = OutlierFilter::Hampel {
+    window: outliers::DEFAULT_HAMPEL_WINDOW,
+    threshold: outliers::DEFAULT_HAMPEL_THRESHOLD,
+    min_deviation: 5.0,
+};
+// changes of the altitude below this threshold in meter are treated as noise by the hysteresis
+pub const DEFAULT_HYSTERESIS_THRESHOLD: f64 = 3.0;
+
+// how the gained altitude is summed up from the altitudes
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum GainMethod {
+    // every positive increment counts, noisy altitudes add up to too much gain
+    #[default]
+    Sum,
+    // the altitude has to change by at least `threshold` meter against the last turning point
+    // before a change of direction is accepted, like most watches and platforms do
+    Hysteresis {
+        threshold: f64,
+    },
+}
+
+impl GainMethod {
+    pub const NAMES: [&'static str; 2] = ["sum", "hysteresis"];
+
+    // gained altitude in the section, the end index is exclusive like in
+    // `get_gained_altitude_in_section`
+    pub fn gained_altitude(&self, altitudes: &[f64], start: usize, end: usize) -> f64 {
+        match self {
+            GainMethod::Sum => get_gained_altitude_in_section(altitudes, start, end),
+            GainMethod::Hysteresis { threshold } => {
+                hysteresis_ascent_descent(&altitudes[start..end], *threshold).0
+            }
+        }
+    }
+
+    pub fn ascent_descent(&self, altitudes: &[f64]) -> (f64, f64) {
+        match self {
+            GainMethod::Sum => altitudes
+                .windows(2)
+                .fold((0.0, 0.0), |(ascent, descent), pair| {
+                    let increment = pair[1] - pair[0];
+                    if increment > 0. {
+                        (ascent + increment, descent)
+                    } else if increment < 0. {
+                        (ascent, descent - increment)
+                    } else {
+                        (ascent, descent)
+                    }
+                }),
+            GainMethod::Hysteresis { threshold } => {
+                hysteresis_ascent_descent(altitudes, *threshold)
+            }
+        }
+    }
+}
+
+// methods are given by name, the hysteresis optionally with its threshold, e.g. `hysteresis:5`
+impl std::str::FromStr for GainMethod {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<GainMethod, String> {
+        let (name, threshold) = match spec.split_once(':') {
+            Some((name, threshold)) => (name, Some(threshold)),
+            None => (spec, None),
+        };
+        match (name, threshold) {
+            ("sum", None) => Ok(GainMethod::Sum),
+            ("hysteresis", threshold) => {
+                let threshold = match threshold {
+                    Some(threshold) => threshold.parse::<f64>().ok().filter(|t| *t >= 0.0),
+                    None => Some(DEFAULT_HYSTERESIS_THRESHOLD),
+                };
+                threshold
+                    .map(|threshold| GainMethod::Hysteresis { threshold })
+                    .ok_or_else(|| format!("Invalid threshold for gain method '{}'.", spec))
+            }
+            _ => Err(format!(
+                "Unknown gain method '{}', expected one of: {}.",
+                spec,
+                GainMethod::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for GainMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GainMethod::Sum => write!(f, "sum"),
+            GainMethod::Hysteresis { threshold } => write!(f, "hysteresis:{}", threshold),
+        }
+    }
+}
+
+// the altitude of the last turning point is the reference, while climbing every new maximum is
+// added to the ascent, a descent is only started once the altitude drops by the threshold below
+// the reference and vice versa
+pub fn hysteresis_ascent_descent(altitudes: &[f64], threshold: f64) -> (f64, f64) {
+    let (mut ascent, mut descent) = (0.0, 0.0);
+    // missing altitudes are skipped
+    let mut altitudes = altitudes
+        .iter()
+        .copied()
+        .filter(|altitude| !altitude.is_nan());
+    let mut reference = match altitudes.next() {
+        Some(altitude) => altitude,
+        None => return (ascent, descent),
+    };
+    // None until the first change larger than the threshold
+    let mut climbing: Option<bool> = None;
+    for altitude in altitudes {
+        let change = altitude - reference;
+        let (up, down) = match climbing {
+            Some(true) => (change > 0., change <= -threshold),
+            Some(false) => (change >= threshold, change < 0.),
+            None => (change >= threshold, change <= -threshold),
+        };
+        if up {
+            ascent += change;
+            reference = altitude;
+            climbing = Some(true);
+        } else if down {
+            descent -= change;
+            reference = altitude;
+            climbing = Some(false);
+        }
+    }
+    (ascent, descent)
+}
+
+// total ascent and descent of the altitudes with the fill strategy, the elevation smoothing and
+// the gain method of the options. Changes from or to missing altitudes are not counted.
+pub fn total_ascent_descent(
+    altitudes: &[f64],
+    times: &[f64],
+    options: &gem_finder::FinderOptions,
+) -> (f64, f64) {
+    let mut altitudes = altitudes.to_vec();
+    options.fill.fill(&mut altitudes, times);
+    let altitudes = smoothing::smooth_values(&altitudes, times, options.elevation_smoothing);
+    options.gain_method.ascent_descent(&altitudes)
+}
 
 fn get_climb(
     section: &dtypes::WindowSection,
     altitudes: &dtypes::Altitudes,
     times: &dtypes::Times,
+    gain_method: GainMethod,
 ) -> f64 {
-    let gained_altitude_in_section: f64 = get_gained_altitude_in_section(
+    let gained_altitude_in_section: f64 = gain_method.gained_altitude(
         &altitudes.values,
         section.start as usize,
         section.end as usize,
@@ -18,7 +166,7 @@ fn get_climb(
     math::climb_equation(&gained_altitude_in_section, &(duration / 60.))
 }
 
-fn get_gained_altitude_in_section(altitudes: &Vec<f64>, start: usize, end: usize) -> f64 {
+pub fn get_gained_altitude_in_section(altitudes: &[f64], start: usize, end: usize) -> f64 {
     let section = altitudes[start..end].to_vec();
     if section.len() <= 1 {
         0.0
@@ -43,7 +191,12 @@ pub fn update_sections_max_climb(
         window_sec.start as usize,
         window_sec.end as usize,
     );
-    window_sec.climb = get_climb(&window_sec, &input_data.altitudes, &input_data.times);
+    window_sec.climb = get_climb(
+        window_sec,
+        &input_data.altitudes,
+        &input_data.times,
+        input_data.gain_method,
+    );
     // update fastest_sec only in case the current distance is equal to the desired distance +- 1% and velocity is larger
     if gem_finder::distance_in_bounds(
         window_sec.distance,
@@ -79,49 +232,132 @@ pub fn find_best_climb_section(
     altitudes: Vec<f64>,
     tolerance: Option<f64>,
 ) -> Result<dtypes::TargetSection, errors::InputDataError> {
-    match gem_finder::InputData::new(
+    find_best_climb_section_with_options(
+        desired_distance,
+        coordinates,
+        times,
+        altitudes,
+        &gem_finder::FinderOptions::with_tolerance(tolerance),
+    )
+}
+
+pub fn find_best_climb_section_with_options(
+    desired_distance: f64,
+    coordinates: Vec<(f64, f64)>,
+    times: Vec<f64>,
+    altitudes: Vec<f64>,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    match gem_finder::InputData::with_options(
         desired_distance,
         coordinates,
         times,
         Some(altitudes),
-        tolerance,
+        options,
     ) {
         Err(e) => Err(e),
         Ok(mut finder) => {
-            math::fill_nans(&mut finder.coordinates);
+            finder.fill_coordinates();
             finder.compute_vector_of_distances();
             match finder.check_if_total_distance_suffice() {
                 Ok(_) => match specific_data_check(&finder) {
                     Ok(_) => {
-                        return {
-                            math::fill_nans(&mut finder.altitudes.values);
-                            finder.search_section(update_sections_max_climb)
-                        }
+                        finder.fill_altitudes();
+                        finder.smooth_altitudes();
+                        finder.split_segments();
+                        finder.search_section(update_sections_max_climb)
                     }
-                    Err(e) => return Err(e),
+                    Err(e) => Err(e),
                 },
-                Err(e) => return Err(e),
+                Err(e) => Err(e),
             }
         }
     }
 }
 
+pub fn find_best_climb_section_with_distances(
+    desired_distance: f64,
+    distances: Vec<f64>,
+    times: Vec<f64>,
+    altitudes: Vec<f64>,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let mut finder = gem_finder::InputData::with_distances(
+        desired_distance,
+        distances,
+        times,
+        Some(altitudes),
+        options,
+    )?;
+    finder.check_if_total_distance_suffice()?;
+    specific_data_check(&finder)?;
+    finder.fill_altitudes();
+    finder.smooth_altitudes();
+    finder.split_segments();
+    finder.search_section(update_sections_max_climb)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 pub fn find_best_climb_section_in_fit(
     desired_distance: f64,
     path_to_fit: &str,
     tolerance: Option<f64>,
 ) -> Result<dtypes::TargetSection, errors::InputDataError> {
-    let fit_data: fit_reader::FitData = fit_reader::parse_fit(path_to_fit);
-    let filtered_altitudes = math::remove_outliers(&fit_data.altitudes, 10.0); // = 1000 %
-    find_best_climb_section(
+    find_best_climb_section_in_fit_with_options(
+        desired_distance,
+        path_to_fit,
+        &gem_finder::FinderOptions::with_tolerance(tolerance),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn find_best_climb_section_in_fit_with_options(
+    desired_distance: f64,
+    path_to_fit: &str,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let fit_data: fit_reader::FitData = fit_reader::parse_fit(path_to_fit)?;
+    find_best_climb_section_in_fit_data_with_options(desired_distance, &fit_data, options)
+}
+
+pub fn find_best_climb_section_in_fit_data(
+    desired_distance: f64,
+    fit_data: &fit_reader::FitData,
+    tolerance: Option<f64>,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    find_best_climb_section_in_fit_data_with_options(
         desired_distance,
-        fit_data.coordinates,
-        fit_data.times,
-        filtered_altitudes,
-        tolerance,
+        fit_data,
+        &gem_finder::FinderOptions::with_tolerance(tolerance),
     )
 }
 
+pub fn find_best_climb_section_in_fit_data_with_options(
+    desired_distance: f64,
+    fit_data: &fit_reader::FitData,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let filtered_altitudes =
+        FIT_ALTITUDE_OUTLIER_FILTER.apply(&fit_data.altitudes, &fit_data.times);
+    if fit_data.use_device_distances() {
+        find_best_climb_section_with_distances(
+            desired_distance,
+            fit_data.distances.clone(),
+            fit_data.times.clone(),
+            filtered_altitudes,
+            options,
+        )
+    } else {
+        find_best_climb_section_with_options(
+            desired_distance,
+            fit_data.coordinates.clone(),
+            fit_data.times.clone(),
+            filtered_altitudes,
+            options,
+        )
+    }
+}
+
 #[cfg(test)]
 mod test_climb {
     use super::*;
@@ -197,6 +433,17 @@ mod test_climb {
         assert_eq!(result.target_value.round(), 6.0);
     }
 
+    #[test]
+    fn test_find_best_climb_section_in_fit_details() {
+        let result = find_best_climb_section_in_fit(1_000., FIT_FILE, Some(0.01)).unwrap();
+        assert!((result.distance - 1_000.).abs() <= 10.);
+        let climb = result.elevation_gain.unwrap() / (result.duration / 60.);
+        assert!((climb - result.target_value).abs() < 1e-9);
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        assert_eq!(result.start_time, fit_data.times[346]);
+        assert_eq!(result.start_coordinate, fit_data.coordinates[346]);
+    }
+
     #[test]
     fn test_find_best_climb_section_in_fit_larger_section() {
         let result = find_best_climb_section_in_fit(3_000., FIT_FILE, Some(0.01)).unwrap();
@@ -204,6 +451,114 @@ mod test_climb {
         assert_eq!(result.end, 706);
         assert_eq!(result.target_value.round(), 4.0);
     }
+
+    #[test]
+    fn test_find_best_climb_section_in_fit_hysteresis() {
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        let sum = find_best_climb_section_in_fit_data(1_000., &fit_data, None).unwrap();
+        let mut options = gem_finder::FinderOptions {
+            gain_method: "hysteresis".parse().unwrap(),
+            ..Default::default()
+        };
+        let hysteresis =
+            find_best_climb_section_in_fit_data_with_options(1_000., &fit_data, &options).unwrap();
+        assert!(hysteresis.target_value > 0.);
+        // the hysteresis never counts more than the sum of all increments
+        assert!(hysteresis.target_value <= sum.target_value);
+        options.elevation_smoothing = "moving-average".parse().unwrap();
+        let (ascent, descent) =
+            total_ascent_descent(&fit_data.altitudes, &fit_data.times, &options);
+        let (raw_ascent, raw_descent) = total_ascent_descent(
+            &fit_data.altitudes,
+            &fit_data.times,
+            &gem_finder::FinderOptions::default(),
+        );
+        assert!(ascent > 0. && ascent < raw_ascent);
+        assert!(descent > 0. && descent < raw_descent);
+    }
+}
+
+#[cfg(test)]
+mod test_gain_method {
+    use super::*;
+
+    #[test]
+    fn test_hysteresis_ignores_noise() {
+        // climb of 10 m with noise of 1 m on top
+        let altitudes = [0., 1., 0., 1., 3., 2., 5., 4., 8., 7., 10., 9., 10., 9.];
+        assert_eq!(hysteresis_ascent_descent(&altitudes, 3.), (10., 0.));
+        assert_eq!(GainMethod::Sum.ascent_descent(&altitudes), (15., 6.));
+        // a change of direction is counted once it exceeds the threshold
+        let altitudes = [0., 10., 8., 6., 7., 12.];
+        assert_eq!(hysteresis_ascent_descent(&altitudes, 3.), (16., 4.));
+        assert_eq!(hysteresis_ascent_descent(&altitudes, 0.), (16., 4.));
+        assert_eq!(hysteresis_ascent_descent(&[2., 1.], 3.), (0., 0.));
+        assert_eq!(hysteresis_ascent_descent(&[], 3.), (0., 0.));
+        // missing altitudes are skipped
+        let altitudes = [f64::NAN, 0., 10., f64::NAN, 5.];
+        assert_eq!(hysteresis_ascent_descent(&altitudes, 3.), (10., 5.));
+    }
+
+    #[test]
+    fn test_gained_altitude() {
+        let altitudes = [0., 1., 0., 1., 5., 2.];
+        assert_eq!(GainMethod::Sum.gained_altitude(&altitudes, 0, 5), 6.);
+        let hysteresis = GainMethod::Hysteresis { threshold: 3. };
+        assert_eq!(hysteresis.gained_altitude(&altitudes, 0, 5), 5.);
+        assert_eq!(hysteresis.gained_altitude(&altitudes, 0, 4), 0.);
+    }
+
+    #[test]
+    fn test_gain_method_from_str() {
+        assert_eq!("sum".parse(), Ok(GainMethod::Sum));
+        assert_eq!(
+            "hysteresis".parse(),
+            Ok(GainMethod::Hysteresis {
+                threshold: DEFAULT_HYSTERESIS_THRESHOLD
+            })
+        );
+        assert_eq!(
+            "hysteresis:5".parse(),
+            Ok(GainMethod::Hysteresis { threshold: 5. })
+        );
+        assert_eq!(
+            GainMethod::Hysteresis { threshold: 5. }.to_string(),
+            "hysteresis:5"
+        );
+        assert!("hysteresis:-1".parse::<GainMethod>().is_err());
+        assert!("sum:1".parse::<GainMethod>().is_err());
+        assert_eq!(
+            "barometric".parse::<GainMethod>(),
+            Err("Unknown gain method 'barometric', expected one of: sum, hysteresis.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_total_ascent_descent() {
+        let options = gem_finder::FinderOptions::default();
+        assert_eq!(
+            total_ascent_descent(&[f64::NAN, 1., 3., f64::NAN, 2.], &[0.; 5], &options),
+            (2., 1.)
+        );
+        assert_eq!(
+            total_ascent_descent(&[f64::NAN; 3], &[0.; 3], &options),
+            (0., 0.)
+        );
+        // the linear fill leaves the gap of 9 seconds missing, the changes from and to it are
+        // not counted
+        let options = gem_finder::FinderOptions {
+            fill: "linear:5".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            total_ascent_descent(
+                &[f64::NAN, 1., 3., f64::NAN, 2.],
+                &[0., 1., 2., 3., 11.],
+                &options
+            ),
+            (2., 0.)
+        );
+    }
 }
 
 #[cfg(test)]
@@ -244,3 +599,50 @@ mod test_checks {
         )
     }
 }
+
+#[cfg(test)]
+mod test_climb_synthetic {
+    use super::*;
+    use crate::synthetic;
+
+    #[test]
+    fn test_find_best_climb_section_synthetic_activity() {
+        let activity = synthetic::generate_activity(3_600);
+        let result = find_best_climb_section(
+            1_000.,
+            activity.coordinates,
+            activity.times,
+            activity.altitudes,
+            Some(0.01),
+        )
+        .unwrap();
+        // synthetic altitude rises steepest every 30 minutes (t = 0s, 1800s, ...) with
+        // 50 m * 2 pi / 30 min = 10.47 m/min, the best climb section must be centered around it
+        let center = (result.start + result.end) / 2;
+        assert!((center as i32 - 1800).abs() <= 30);
+        assert!(result.target_value > 9.0 && result.target_value < 10.5);
+    }
+
+    #[test]
+    fn test_find_best_climb_section_indoor_activity() {
+        let mut activity = synthetic::generate_activity(3_600);
+        activity.coordinates = vec![(f64::NAN, f64::NAN); activity.times.len()];
+        let fit_data = fit_reader::parse_fit_bytes(&synthetic::to_fit_bytes(&activity)).unwrap();
+        let result = find_best_climb_section_in_fit_data(1_000., &fit_data, Some(0.01)).unwrap();
+        let center = (result.start + result.end) / 2;
+        assert!((center as i32 - 1801).abs() <= 30);
+        assert!(result.target_value > 9.0 && result.target_value < 10.5);
+
+        // altitudes are required for the climb
+        assert_eq!(
+            find_best_climb_section_with_distances(
+                1_000.,
+                activity.distances,
+                activity.times,
+                vec![f64::NAN; 3_600],
+                &gem_finder::FinderOptions::default(),
+            ),
+            Err(errors::InputDataError::TooFewDataPoints)
+        );
+    }
+}
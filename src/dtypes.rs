@@ -1,3 +1,5 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::default::Default;
 
 pub struct Coordinate {
@@ -6,25 +8,57 @@ pub struct Coordinate {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Times {
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null"))]
     pub values: Vec<f64>,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Distances {
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null"))]
     pub values: Vec<f64>,
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Altitudes {
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null"))]
     pub values: Vec<f64>,
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TargetSection {
     pub start: u32,
     pub end: u32,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
     pub target_value: f64, // to be maximized
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub distance: f64, // in meter
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub duration: f64, // elapsed time in seconds
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub start_time: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub end_time: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::coordinate"))]
+    pub start_coordinate: (f64, f64),
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::coordinate"))]
+    pub end_coordinate: (f64, f64),
+    pub elevation_gain: Option<f64>, // in meter, only known if altitudes are given
+}
+
+impl TargetSection {
+    // pace in minutes per kilometer, NaN if the section has no distance
+    pub fn pace(&self) -> f64 {
+        if self.distance > 0. {
+            (self.duration / 60.) / (self.distance / 1_000.)
+        } else {
+            f64::NAN
+        }
+    }
 }
 
 impl Default for TargetSection {
@@ -33,17 +67,28 @@ impl Default for TargetSection {
             start: 0,
             end: 0,
             target_value: 0.0,
+            distance: 0.0,
+            duration: 0.0,
+            start_time: 0.0,
+            end_time: 0.0,
+            start_coordinate: (0.0, 0.0),
+            end_coordinate: (0.0, 0.0),
+            elevation_gain: None,
         }
     }
 }
 
 // window section used to scan through the activity
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WindowSection {
     pub start: u32,
     pub end: u32,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
     pub distance: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
     pub velocity: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
     pub climb: f64,
 }
 
@@ -58,3 +103,244 @@ impl Default for WindowSection {
         }
     }
 }
+
+// json has no representation for NaN, missing values are therefore written as null and read back
+// as NaN to keep the data lossless
+#[cfg(feature = "serde")]
+pub(crate) mod nan_as_null {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    fn to_option(value: f64) -> Option<f64> {
+        if value.is_nan() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    pub fn serialize<S: Serializer>(values: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|value| to_option(*value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f64>, D::Error> {
+        let values = Vec::<Option<f64>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect())
+    }
+
+    // single values like the fields of the sections
+    pub mod value {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+            to_option(*value).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+            Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+        }
+    }
+
+    pub mod coordinate {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            value: &(f64, f64),
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            (to_option(value.0), to_option(value.1)).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<(f64, f64), D::Error> {
+            let (lat, lon) = <(Option<f64>, Option<f64>)>::deserialize(deserializer)?;
+            Ok((lat.unwrap_or(f64::NAN), lon.unwrap_or(f64::NAN)))
+        }
+    }
+
+    pub mod coordinates {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            values: &[(f64, f64)],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(
+                values
+                    .iter()
+                    .map(|(lat, lon)| (to_option(*lat), to_option(*lon))),
+            )
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<(f64, f64)>, D::Error> {
+            let values = Vec::<(Option<f64>, Option<f64>)>::deserialize(deserializer)?;
+            Ok(values
+                .into_iter()
+                .map(|(lat, lon)| (lat.unwrap_or(f64::NAN), lon.unwrap_or(f64::NAN)))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_target_section {
+    use super::*;
+
+    #[test]
+    fn test_pace() {
+        let section = TargetSection {
+            distance: 2_000.,
+            duration: 600.,
+            ..Default::default()
+        };
+        assert_eq!(section.pace(), 5.0);
+        let section = TargetSection {
+            distance: 0.,
+            duration: 600.,
+            ..Default::default()
+        };
+        assert!(section.pace().is_nan());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_dtypes {
+    use super::*;
+
+    #[test]
+    fn test_serde_times() {
+        let times = Times {
+            values: vec![1.0, f64::NAN, 3.5],
+        };
+        let json = serde_json::to_string(&times).unwrap();
+        assert_eq!(json, r#"{"values":[1.0,null,3.5]}"#);
+        let parsed: Times = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.values[0], 1.0);
+        assert!(parsed.values[1].is_nan());
+        assert_eq!(parsed.values[2], 3.5);
+    }
+
+    #[test]
+    fn test_serde_distances_and_altitudes() {
+        let distances = Distances {
+            values: vec![0.0, 10.5],
+        };
+        let json = serde_json::to_string(&distances).unwrap();
+        assert_eq!(json, r#"{"values":[0.0,10.5]}"#);
+        assert_eq!(serde_json::from_str::<Distances>(&json).unwrap(), distances);
+        let altitudes = Altitudes {
+            values: vec![250.0, 251.2],
+        };
+        let json = serde_json::to_string(&altitudes).unwrap();
+        assert_eq!(json, r#"{"values":[250.0,251.2]}"#);
+        assert_eq!(serde_json::from_str::<Altitudes>(&json).unwrap(), altitudes);
+    }
+
+    #[test]
+    fn test_serde_target_section() {
+        let section = TargetSection {
+            start: 628,
+            end: 719,
+            target_value: 2.5,
+            distance: 1000.0,
+            duration: 400.0,
+            start_time: 1568475000.0,
+            end_time: 1568475400.0,
+            start_coordinate: (49.4, 8.6),
+            end_coordinate: (49.41, 8.61),
+            elevation_gain: None,
+        };
+        let json = serde_json::to_string(&section).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"start":628,"end":719,"target_value":2.5,"distance":1000.0,"duration":400.0,"#,
+                r#""start_time":1568475000.0,"end_time":1568475400.0,"start_coordinate":[49.4,8.6],"#,
+                r#""end_coordinate":[49.41,8.61],"elevation_gain":null}"#
+            )
+        );
+        assert_eq!(
+            serde_json::from_str::<TargetSection>(&json).unwrap(),
+            section
+        );
+    }
+
+    #[test]
+    fn test_serde_target_section_with_nans() {
+        let section = TargetSection {
+            start: 3,
+            end: 9,
+            target_value: f64::NAN,
+            distance: 1000.0,
+            duration: f64::NAN,
+            start_time: f64::NAN,
+            end_time: 1568475400.0,
+            start_coordinate: (f64::NAN, f64::NAN),
+            end_coordinate: (49.41, f64::NAN),
+            elevation_gain: None,
+        };
+        let json = serde_json::to_string(&section).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"start":3,"end":9,"target_value":null,"distance":1000.0,"duration":null,"#,
+                r#""start_time":null,"end_time":1568475400.0,"start_coordinate":[null,null],"#,
+                r#""end_coordinate":[49.41,null],"elevation_gain":null}"#
+            )
+        );
+        let parsed: TargetSection = serde_json::from_str(&json).unwrap();
+        assert_eq!((parsed.start, parsed.end), (3, 9));
+        assert!(parsed.target_value.is_nan());
+        assert_eq!(parsed.distance, 1000.0);
+        assert!(parsed.duration.is_nan());
+        assert!(parsed.start_time.is_nan());
+        assert_eq!(parsed.end_time, 1568475400.0);
+        assert!(parsed.start_coordinate.0.is_nan() && parsed.start_coordinate.1.is_nan());
+        assert_eq!(parsed.end_coordinate.0, 49.41);
+        assert!(parsed.end_coordinate.1.is_nan());
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_serde_window_section() {
+        let section = WindowSection {
+            start: 1,
+            end: 5,
+            distance: 1000.0,
+            velocity: 3.0,
+            climb: 12.5,
+        };
+        let json = serde_json::to_string(&section).unwrap();
+        assert_eq!(
+            json,
+            r#"{"start":1,"end":5,"distance":1000.0,"velocity":3.0,"climb":12.5}"#
+        );
+        let parsed: WindowSection = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            (
+                parsed.start,
+                parsed.end,
+                parsed.distance,
+                parsed.velocity,
+                parsed.climb
+            ),
+            (1, 5, 1000.0, 3.0, 12.5)
+        );
+        let section = WindowSection {
+            velocity: f64::NAN,
+            ..section
+        };
+        let json = serde_json::to_string(&section).unwrap();
+        assert_eq!(
+            json,
+            r#"{"start":1,"end":5,"distance":1000.0,"velocity":null,"climb":12.5}"#
+        );
+        let parsed: WindowSection = serde_json::from_str(&json).unwrap();
+        assert!(parsed.velocity.is_nan());
+    }
+}
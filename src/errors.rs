@@ -1,5 +1,6 @@
-use pyo3::create_exception;
-use pyo3::exceptions;
+#[cfg(feature = "python")]
+use pyo3::{create_exception, exceptions, PyErr};
+use std::fmt;
 
 pub const TOO_FEW_DATA_POINTS_MSG: &str =
     "Input data must consist of at least 2 not null data points.";
@@ -9,6 +10,10 @@ pub const INCONSISTENT_LENGTH_MSG: &str = "Input data lists must be of equal len
 pub const NO_SECTION_FOUND_MSG: &str =
     "Could not find proper section, check quality of input data or increase tolerance.";
 pub const INVALID_DESIRED_DISTANCE_MSG: &str = "desired_distance must be greater than 0.";
+pub const INVALID_FIT_FILE_MSG: &str =
+    "Could not read fit file, check that the given path points to a valid fit file.";
+pub const FILE_NOT_READABLE_MSG: &str =
+    "Could not open file, check that the given path exists and is readable.";
 
 #[derive(Debug, PartialEq)]
 pub enum InputDataError {
@@ -17,26 +22,93 @@ pub enum InputDataError {
     DistanceTooSmall,
     NoSectionFound,
     InvalidDesiredDistance,
+    InvalidFitFile,
+    // the file could not be opened or read, e.g. because it does not exist
+    FileNotReadable,
 }
 
+impl InputDataError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            InputDataError::InconsistentLength => INCONSISTENT_LENGTH_MSG,
+            InputDataError::TooFewDataPoints => TOO_FEW_DATA_POINTS_MSG,
+            InputDataError::DistanceTooSmall => DISTANCE_TOO_SMALL_MSG,
+            InputDataError::NoSectionFound => NO_SECTION_FOUND_MSG,
+            InputDataError::InvalidDesiredDistance => INVALID_DESIRED_DISTANCE_MSG,
+            InputDataError::InvalidFitFile => INVALID_FIT_FILE_MSG,
+            InputDataError::FileNotReadable => FILE_NOT_READABLE_MSG,
+        }
+    }
+}
+
+impl fmt::Display for InputDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for InputDataError {}
+
+#[cfg(feature = "python")]
 create_exception!(
     sportgems,
     InconsistentLengthException,
     exceptions::PyException
 );
+#[cfg(feature = "python")]
 create_exception!(
     sportgems,
     DistanceTooSmallException,
     exceptions::PyException
 );
+#[cfg(feature = "python")]
 create_exception!(
     sportgems,
     TooFewDataPointsException,
     exceptions::PyException
 );
+#[cfg(feature = "python")]
 create_exception!(
     sportgems,
     InvalidDesiredDistanceException,
     exceptions::PyException
 );
+#[cfg(feature = "python")]
 create_exception!(sportgems, NoSectionFoundException, exceptions::PyException);
+#[cfg(feature = "python")]
+create_exception!(sportgems, InvalidFitFileException, exceptions::PyException);
+#[cfg(feature = "python")]
+create_exception!(sportgems, FileNotReadableException, exceptions::PyOSError);
+
+#[cfg(feature = "python")]
+impl From<InputDataError> for PyErr {
+    fn from(err: InputDataError) -> PyErr {
+        let message = err.message();
+        match err {
+            InputDataError::TooFewDataPoints => TooFewDataPointsException::new_err(message),
+            InputDataError::DistanceTooSmall => DistanceTooSmallException::new_err(message),
+            InputDataError::InconsistentLength => InconsistentLengthException::new_err(message),
+            InputDataError::NoSectionFound => NoSectionFoundException::new_err(message),
+            InputDataError::InvalidDesiredDistance => {
+                InvalidDesiredDistanceException::new_err(message)
+            }
+            InputDataError::InvalidFitFile => InvalidFitFileException::new_err(message),
+            InputDataError::FileNotReadable => FileNotReadableException::new_err(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_errors {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            InputDataError::NoSectionFound.to_string(),
+            NO_SECTION_FOUND_MSG
+        );
+        let error: Box<dyn std::error::Error> = Box::new(InputDataError::InvalidFitFile);
+        assert_eq!(error.to_string(), INVALID_FIT_FILE_MSG);
+    }
+}
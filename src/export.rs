@@ -0,0 +1,304 @@
+use crate::errors;
+use crate::fit_reader;
+use arrow::array::{Array, ArrayRef, Float64Array, StructArray, TimestampMillisecondArray};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::error::ArrowError;
+use arrow::ffi::{FFI_ArrowArray, FFI_ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use parquet::errors::ParquetError;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const TIMESTAMP: &str = "timestamp";
+pub const LATITUDE: &str = "lat";
+pub const LONGITUDE: &str = "lon";
+pub const ALTITUDE: &str = "altitude";
+pub const DISTANCE: &str = "distance";
+
+// offset based notation, named timezones would require the tz database when writing csv
+const UTC: &str = "+00:00";
+
+// one column per time series of the parsed fit data, timestamps are given in UTC. Sensor data
+// like heart rate, cadence, power or speed is not parsed into FitData and therefore not exported.
+pub fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new(
+            TIMESTAMP,
+            DataType::Timestamp(TimeUnit::Millisecond, Some(UTC.into())),
+            true,
+        ),
+        Field::new(LATITUDE, DataType::Float64, true),
+        Field::new(LONGITUDE, DataType::Float64, true),
+        Field::new(ALTITUDE, DataType::Float64, true),
+        Field::new(DISTANCE, DataType::Float64, true),
+    ])
+}
+
+// missing values are stored as NaN in FitData, but arrow has a proper notion of null values
+fn to_float_column(values: impl Iterator<Item = f64>) -> ArrayRef {
+    Arc::new(
+        values
+            .map(|v| if v.is_nan() { None } else { Some(v) })
+            .collect::<Float64Array>(),
+    )
+}
+
+fn to_timestamp_column(times: &[f64]) -> ArrayRef {
+    Arc::new(
+        times
+            .iter()
+            .map(|t| {
+                if t.is_nan() {
+                    None
+                } else {
+                    Some((t * 1_000.).round() as i64)
+                }
+            })
+            .collect::<TimestampMillisecondArray>()
+            .with_timezone(UTC),
+    )
+}
+
+pub fn to_record_batch(fit_data: &fit_reader::FitData) -> Result<RecordBatch, ExportError> {
+    let records = fit_data.times.len();
+    // distances are empty if the device did not record them
+    let distances = if fit_data.distances.is_empty() {
+        vec![f64::NAN; records]
+    } else {
+        fit_data.distances.clone()
+    };
+    if [
+        fit_data.coordinates.len(),
+        fit_data.altitudes.len(),
+        distances.len(),
+    ]
+    .iter()
+    .any(|len| *len != records)
+    {
+        return Err(errors::InputDataError::InconsistentLength.into());
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(schema()),
+        vec![
+            to_timestamp_column(&fit_data.times),
+            to_float_column(fit_data.coordinates.iter().map(|c| c.0)),
+            to_float_column(fit_data.coordinates.iter().map(|c| c.1)),
+            to_float_column(fit_data.altitudes.iter().copied()),
+            to_float_column(distances.into_iter()),
+        ],
+    )?)
+}
+
+// export the record batch as struct array via the arrow c data interface, which can be imported
+// by e.g. pyarrow without copying the data
+pub fn to_ffi(
+    fit_data: &fit_reader::FitData,
+) -> Result<(FFI_ArrowArray, FFI_ArrowSchema), ExportError> {
+    let batch = StructArray::from(to_record_batch(fit_data)?);
+    Ok(arrow::ffi::to_ffi(&batch.to_data())?)
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    InputData(errors::InputDataError),
+    Io(std::io::Error),
+    Arrow(ArrowError),
+    Parquet(ParquetError),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportError::InputData(e) => write!(f, "{}", e),
+            ExportError::Io(e) => write!(f, "{}", e),
+            ExportError::Arrow(e) => write!(f, "{}", e),
+            ExportError::Parquet(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<errors::InputDataError> for ExportError {
+    fn from(e: errors::InputDataError) -> Self {
+        ExportError::InputData(e)
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<ArrowError> for ExportError {
+    fn from(e: ArrowError) -> Self {
+        ExportError::Arrow(e)
+    }
+}
+
+impl From<ParquetError> for ExportError {
+    fn from(e: ParquetError) -> Self {
+        ExportError::Parquet(e)
+    }
+}
+
+#[cfg(feature = "python")]
+impl From<ExportError> for pyo3::PyErr {
+    fn from(e: ExportError) -> pyo3::PyErr {
+        match e {
+            ExportError::InputData(e) => e.into(),
+            e => pyo3::exceptions::PyRuntimeError::new_err(e.to_string()),
+        }
+    }
+}
+
+// csv files start with a header line holding the column names of `schema`, timestamps are
+// written as RFC 3339 strings and missing values as empty fields
+pub fn write_csv<W: Write>(fit_data: &fit_reader::FitData, writer: W) -> Result<(), ExportError> {
+    let mut writer = arrow::csv::Writer::new(writer);
+    writer.write(&to_record_batch(fit_data)?)?;
+    Ok(())
+}
+
+pub fn write_parquet<W: Write + Send>(
+    fit_data: &fit_reader::FitData,
+    writer: W,
+) -> Result<(), ExportError> {
+    let mut writer = ArrowWriter::try_new(writer, Arc::new(schema()), None)?;
+    writer.write(&to_record_batch(fit_data)?)?;
+    writer.close()?;
+    Ok(())
+}
+
+pub fn write_csv_file(fit_data: &fit_reader::FitData, path: &Path) -> Result<(), ExportError> {
+    write_csv(fit_data, File::create(path)?)
+}
+
+pub fn write_parquet_file(fit_data: &fit_reader::FitData, path: &Path) -> Result<(), ExportError> {
+    write_parquet(fit_data, File::create(path)?)
+}
+
+#[cfg(test)]
+mod test_export {
+    use super::*;
+    use crate::synthetic;
+    use arrow::array::AsArray;
+    use arrow::datatypes::TimestampMillisecondType;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_to_record_batch() {
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        let batch = to_record_batch(&fit_data).unwrap();
+        assert_eq!(batch.num_rows(), 1231);
+        assert_eq!(batch.schema().as_ref(), &schema());
+        let timestamps = batch.column(0).as_primitive::<TimestampMillisecondType>();
+        assert_eq!(timestamps.value(100), 1568474841000);
+        let latitudes = batch
+            .column(1)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(latitudes.value(100), fit_data.coordinates[100].0);
+        let longitudes = batch
+            .column(2)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(longitudes.value(100), fit_data.coordinates[100].1);
+        let altitudes = batch
+            .column(3)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(altitudes.value(100), fit_data.altitudes[100]);
+        let distances = batch
+            .column(4)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(distances.value(100), fit_data.distances[100]);
+    }
+
+    #[test]
+    fn test_to_record_batch_nans_become_nulls() {
+        let fit_data = fit_reader::FitData {
+            calories: 0,
+            times: vec![1., f64::NAN, 3.],
+            coordinates: vec![(48.0, 8.0), (f64::NAN, f64::NAN), (48.1, 8.1)],
+            altitudes: vec![f64::NAN, 250., 251.],
+            distances: vec![],
+            timer_pauses: vec![],
+            record_messages: vec![],
+        };
+        let batch = to_record_batch(&fit_data).unwrap();
+        assert_eq!(batch.column(0).null_count(), 1);
+        assert!(batch.column(0).is_null(1));
+        assert!(batch.column(1).is_null(1));
+        assert!(batch.column(2).is_null(1));
+        assert!(batch.column(3).is_null(0));
+        assert_eq!(batch.column(4).len(), 3);
+    }
+
+    #[test]
+    fn test_to_record_batch_inconsistent_length() {
+        let fit_data = fit_reader::FitData {
+            calories: 0,
+            times: vec![1., 2.],
+            coordinates: vec![(48.0, 8.0)],
+            altitudes: vec![250., 251.],
+            distances: vec![],
+            timer_pauses: vec![],
+            record_messages: vec![],
+        };
+        assert!(matches!(
+            to_record_batch(&fit_data),
+            Err(ExportError::InputData(
+                errors::InputDataError::InconsistentLength
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_to_ffi_round_trip() {
+        let fit_data = synthetic::generate_activity(600);
+        let (array, schema) = to_ffi(&fit_data).unwrap();
+        let imported = unsafe { arrow::ffi::from_ffi(array, &schema) }.unwrap();
+        let batch = RecordBatch::from(StructArray::from(imported));
+        assert_eq!(batch, to_record_batch(&fit_data).unwrap());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let fit_data = fit_reader::FitData {
+            calories: 0,
+            times: vec![1568474841., 1568474842.],
+            coordinates: vec![(49.5, 8.5), (f64::NAN, f64::NAN)],
+            altitudes: vec![250.5, 251.],
+            distances: vec![10., 12.5],
+            timer_pauses: vec![],
+            record_messages: vec![],
+        };
+        let mut buffer = vec![];
+        write_csv(&fit_data, &mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "timestamp,lat,lon,altitude,distance\n\
+             2019-09-14T15:27:21Z,49.5,8.5,250.5,10.0\n\
+             2019-09-14T15:27:22Z,,,251.0,12.5\n"
+        );
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReader;
+
+        let fit_data = synthetic::generate_activity(600);
+        let path = std::env::temp_dir().join("sportgems_test_write_parquet.parquet");
+        write_parquet_file(&fit_data, &path).unwrap();
+        let batches: Vec<RecordBatch> =
+            ParquetRecordBatchReader::try_new(File::open(&path).unwrap(), 1024)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(batches, vec![to_record_batch(&fit_data).unwrap()]);
+    }
+}
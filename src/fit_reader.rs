@@ -1,111 +1,613 @@
+#[cfg(not(target_arch = "wasm32"))]
 extern crate fit;
 
+#[cfg(feature = "serde")]
+use crate::dtypes::nan_as_null;
+use crate::errors;
+#[cfg(not(target_arch = "wasm32"))]
 use fit::Fit;
+use fitsdk::{
+    match_message_field, match_message_timestamp_field, match_messagetype,
+    match_predefined_field_value, FieldType, MessageType,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::borrow::Cow;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::hash_map::RandomState;
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs::OpenOptions;
+#[cfg(not(target_arch = "wasm32"))]
+use std::hash::{BuildHasher, Hasher};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+const FIT_HEADER_SIZE: usize = 14;
+const FIT_CRC_SIZE: usize = 2;
+const FIELD_DESCRIPTION_MESSAGE: u16 = 206;
+
+const COMPRESSED_HEADER_MASK: u8 = 0x80;
+const DEFINITION_HEADER_MASK: u8 = 0x40;
+const DEVELOPER_FIELDS_MASK: u8 = 0x20;
+const LOCAL_MESSAGE_NUMBER_MASK: u8 = 0x0F;
+const BASE_TYPE_MASK: u8 = 0x1F;
+
+// fit timestamps are seconds since 1989-12-31, coordinates are given in semicircles
+const PSEUDO_EPOCH: u32 = 631_065_600;
+const SEMICIRCLES_TO_DEGREES: f32 = (180. / 2_147_483_648f64) as f32;
+
+// event and event types of the event message which start and stop the timer
+const TIMER_EVENT: &str = "timer";
+const TIMER_START: &str = "start";
+const TIMER_STOPS: [&str; 4] = ["stop", "stop_all", "stop_disable", "stop_disable_all"];
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FitData {
     pub calories: u16,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null"))]
     pub times: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::coordinates"))]
     pub coordinates: Vec<(f64, f64)>,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null"))]
     pub altitudes: Vec<f64>,
+    // cumulative distance in meter as recorded by the device, e.g. by a footpod on a treadmill.
+    // Empty if the distances are not known at all.
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null", default))]
+    pub distances: Vec<f64>,
+    // periods as (stop, start) in seconds, in which the timer of the device was stopped, e.g. by
+    // auto pause
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timer_pauses: Vec<(f64, f64)>,
+    // whether each row was read from a record message, the other rows hold e.g. events, laps
+    // and the session. Empty if not known, all rows are records then.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub record_messages: Vec<bool>,
 }
 
-fn match_one_time_values(record: &fit::DataField, fit_data: &mut FitData) {
-    // get calories
-    if record.field_num == 11 {
-        match record.value {
-            fit::Value::U16(val) => {
-                fit_data.calories = val;
-            }
-            _ => {}
+impl FitData {
+    pub fn has_coordinates(&self) -> bool {
+        self.coordinates
+            .iter()
+            .filter(|(lat, lon)| lat.is_normal() && lon.is_normal())
+            .nth(1)
+            .is_some()
+    }
+
+    pub fn has_distances(&self) -> bool {
+        self.distances
+            .iter()
+            .filter(|distance| !distance.is_nan())
+            .nth(1)
+            .is_some()
+    }
+
+    // indoor activities have no coordinates, their sections are searched on the recorded distances
+    pub fn use_device_distances(&self) -> bool {
+        !self.has_coordinates() && self.has_distances()
+    }
+}
+
+// layout of the data messages of a local message number, read from its definition message
+struct MessageDefinition {
+    global_number: u16,
+    big_endian: bool,
+    // field number, size and base type of each field
+    fields: Vec<(u8, u8, u8)>,
+    // total size of the developer fields, which follow the fields
+    developer_size: usize,
+}
+
+impl MessageDefinition {
+    fn size(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| field.1 as usize)
+            .sum::<usize>()
+            + self.developer_size
+    }
+}
+
+// a definition or data message, `fields` holds the bytes of the fields and `developer_fields`
+// those of the developer fields. For definition messages these are the field definitions, the
+// fixed part of the definition is part of `fields`.
+struct FitMessage<'a> {
+    header: u8,
+    definition: &'a MessageDefinition,
+    fields: &'a [u8],
+    // only read to strip the developer fields for the fit crate
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    developer_fields: &'a [u8],
+}
+
+impl FitMessage<'_> {
+    fn is_definition(&self) -> bool {
+        self.header & COMPRESSED_HEADER_MASK == 0 && self.header & DEFINITION_HEADER_MASK != 0
+    }
+}
+
+// the fit crate panics on unexpected bytes and loops forever on truncated files, therefore the
+// header and the framing of all messages are checked while walking over them, before the file is
+// handed over. Each message is passed to `visit` together with the definition it is read with.
+fn walk_fit_messages(
+    bytes: &[u8],
+    mut visit: impl FnMut(FitMessage),
+) -> Result<(), errors::InputDataError> {
+    let header = bytes
+        .get(..FIT_HEADER_SIZE)
+        .ok_or(errors::InputDataError::InvalidFitFile)?;
+    let data_size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let end = FIT_HEADER_SIZE + data_size;
+    if header[0] as usize != FIT_HEADER_SIZE
+        || &header[8..12] != b".FIT"
+        || end + FIT_CRC_SIZE > bytes.len()
+    {
+        return Err(errors::InputDataError::InvalidFitFile);
+    }
+    let take = |position: &mut usize, size: usize| -> Result<&[u8], errors::InputDataError> {
+        let taken = bytes
+            .get(*position..*position + size)
+            .filter(|_| *position + size <= end)
+            .ok_or(errors::InputDataError::InvalidFitFile)?;
+        *position += size;
+        Ok(taken)
+    };
+    let mut definitions: [Option<MessageDefinition>; 16] = Default::default();
+    let mut position = FIT_HEADER_SIZE;
+    while position < end {
+        let header = take(&mut position, 1)?[0];
+        let local_number = if header & COMPRESSED_HEADER_MASK != 0 {
+            (header >> 5) & 0x03
+        } else {
+            header & LOCAL_MESSAGE_NUMBER_MASK
+        };
+        if header & COMPRESSED_HEADER_MASK == 0 && header & DEFINITION_HEADER_MASK != 0 {
+            // reserved byte, architecture, global message number, number of fields and fields
+            let fixed = take(&mut position, 5)?;
+            let big_endian = match fixed[1] {
+                0 => false,
+                1 => true,
+                _ => return Err(errors::InputDataError::InvalidFitFile),
+            };
+            let global_number = if big_endian {
+                u16::from_be_bytes([fixed[2], fixed[3]])
+            } else {
+                u16::from_le_bytes([fixed[2], fixed[3]])
+            };
+            let field_count = fixed[4] as usize;
+            take(&mut position, 3 * field_count)?;
+            let fields = &bytes[position - 5 - 3 * field_count..position];
+            let developer_fields = if header & DEVELOPER_FIELDS_MASK != 0 {
+                let count = take(&mut position, 1)?[0] as usize;
+                take(&mut position, 3 * count)?;
+                &bytes[position - 1 - 3 * count..position]
+            } else {
+                &[]
+            };
+            let definition = MessageDefinition {
+                global_number,
+                big_endian,
+                fields: fields[5..]
+                    .chunks(3)
+                    .map(|field| (field[0], field[1], field[2]))
+                    .collect(),
+                developer_size: developer_fields
+                    .get(1..)
+                    .unwrap_or_default()
+                    .chunks(3)
+                    .map(|field| field[1] as usize)
+                    .sum(),
+            };
+            let definition = definitions[local_number as usize].insert(definition);
+            visit(FitMessage {
+                header,
+                definition,
+                fields,
+                developer_fields,
+            });
+        } else {
+            let definition = definitions[local_number as usize]
+                .as_ref()
+                .ok_or(errors::InputDataError::InvalidFitFile)?;
+            let message = take(&mut position, definition.size())?;
+            let (fields, developer_fields) =
+                message.split_at(message.len() - definition.developer_size);
+            visit(FitMessage {
+                header,
+                definition,
+                fields,
+                developer_fields,
+            });
         }
     }
+    Ok(())
 }
 
-fn match_time_series_values(
-    record: &fit::DataField,
-    latitude: &mut f64,
-    longitude: &mut f64,
-    timestamp: &mut f64,
-    altitude: &mut f64,
-) {
-    // get latitude
-    if record.field_num == 0 {
-        match record.value {
-            fit::Value::F32(val) => {
-                *latitude = val as f64;
-            }
-            _ => {}
+// the fit crate reads developer fields only for one developer data index and panics on field
+// descriptions it does not expect, the developer fields and field descriptions are therefore
+// removed before the file is handed over. Files without developer fields are passed as they are.
+#[cfg(not(target_arch = "wasm32"))]
+fn strip_developer_fields(bytes: &[u8]) -> Result<Cow<'_, [u8]>, errors::InputDataError> {
+    let mut has_developer_fields = false;
+    let mut data = vec![];
+    walk_fit_messages(bytes, |message| {
+        if message.is_definition() {
+            has_developer_fields |= !message.developer_fields.is_empty();
+            data.push(message.header & !DEVELOPER_FIELDS_MASK);
+            data.extend_from_slice(message.fields);
+        } else if message.definition.global_number != FIELD_DESCRIPTION_MESSAGE {
+            data.push(message.header);
+            data.extend_from_slice(message.fields);
+        } else {
+            has_developer_fields = true;
         }
+    })?;
+    if !has_developer_fields {
+        return Ok(Cow::Borrowed(bytes));
     }
-    // get longitude
-    if record.field_num == 1 {
-        match record.value {
-            fit::Value::F32(val) => {
-                *longitude = val as f64;
-            }
-            _ => {}
+    let data_size = u32::to_le_bytes(data.len() as u32);
+    let mut stripped = bytes[..FIT_HEADER_SIZE].to_vec();
+    stripped[4..8].copy_from_slice(&data_size);
+    stripped.extend_from_slice(&data);
+    // the fit crate does not check the crc, it is kept as it is
+    let end =
+        FIT_HEADER_SIZE + u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+    stripped.extend_from_slice(&bytes[end..end + FIT_CRC_SIZE]);
+    Ok(Cow::Owned(stripped))
+}
+
+// decoded field values, only the types which are needed to fill FitData are kept apart. Values of
+// enum fields are mapped to their names like in the fit crate.
+#[derive(Debug, PartialEq)]
+enum Value {
+    U8(u8),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    F32(f32),
+    Time(u32),
+    Enum(&'static str),
+    Other,
+}
+
+fn to_u16(bytes: &[u8], big_endian: bool) -> u16 {
+    let bytes = [bytes[0], bytes[1]];
+    if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+fn to_u32(bytes: &[u8], big_endian: bool) -> u32 {
+    let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+fn all_equal(bytes: &[u8], value: u8) -> bool {
+    bytes.iter().all(|b| *b == value)
+}
+
+// decode a single field, returns None for invalid values, which are not part of the message
+fn decode_value(base_type: u8, bytes: &[u8], big_endian: bool) -> Option<Value> {
+    let size = bytes.len();
+    match base_type & BASE_TYPE_MASK {
+        // enum, byte, sint8 and uint8z are only supported as single values
+        0 if size == 1 && bytes[0] != 0xFF => Some(Value::U8(bytes[0])),
+        13 if size == 1 && bytes[0] != 0xFF => Some(Value::Other),
+        1 if size == 1 && bytes[0] != 0x7F => Some(Value::Other),
+        10 if size == 1 && bytes[0] != 0x00 => Some(Value::Other),
+        // uint8, uint16 and uint32 arrays are valid as long as one element is valid
+        2 if !all_equal(bytes, 0xFF) => Some(Value::Other),
+        4 if size == 2 => match to_u16(bytes, big_endian) {
+            0xFFFF => None,
+            v => Some(Value::U16(v)),
+        },
+        4 if size > 2 && !all_equal(bytes, 0xFF) => Some(Value::Other),
+        6 if size > 4 && !all_equal(bytes, 0xFF) => Some(Value::Other),
+        3 if size == 2 && to_u16(bytes, big_endian) != 0x7FFF => Some(Value::Other),
+        5 if size == 4 => match to_u32(bytes, big_endian) as i32 {
+            0x7FFF_FFFF => None,
+            v => Some(Value::I32(v)),
+        },
+        6 if size == 4 => match to_u32(bytes, big_endian) {
+            0xFFFF_FFFF => None,
+            v => Some(Value::U32(v)),
+        },
+        7 => {
+            let string: Vec<u8> = bytes.iter().copied().filter(|b| *b != 0x00).collect();
+            String::from_utf8(string).ok().map(|_| Value::Other)
         }
+        8 if size == 4 => match to_u32(bytes, big_endian) {
+            0xFFFF_FFFF => None,
+            v => Some(Value::F32(f32::from_bits(v))),
+        },
+        11 if size == 2 => match to_u16(bytes, big_endian) {
+            0x0000 => None,
+            v => Some(Value::U16(v)),
+        },
+        12 if size == 4 && !all_equal(bytes, 0x00) => Some(Value::Other),
+        9 | 15 if size == 8 && !all_equal(bytes, 0xFF) => Some(Value::Other),
+        14 if size == 8 && bytes != [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F] => {
+            Some(Value::Other)
+        }
+        16 if size == 8 && !all_equal(bytes, 0x00) => Some(Value::Other),
+        _ => None,
     }
-    // get timestamp
-    if record.field_num == 253 {
-        match record.value {
-            fit::Value::Time(val) => {
-                *timestamp = val as f64;
-            }
-            _ => {}
+}
+
+// convert raw values according to the field type given by the fit profile
+fn process_value(value: Value, field_type: FieldType) -> Value {
+    match (field_type, value) {
+        (FieldType::Coordinates, Value::I32(v)) => Value::F32(v as f32 * SEMICIRCLES_TO_DEGREES),
+        (FieldType::Timestamp, Value::U32(v)) | (FieldType::DateTime, Value::U32(v)) => {
+            Value::Time(v.wrapping_add(PSEUDO_EPOCH))
         }
+        (FieldType::LocalDateTime, Value::U32(v)) => {
+            Value::Time(v.wrapping_add(PSEUDO_EPOCH - 3600))
+        }
+        (
+            FieldType::None
+            | FieldType::String
+            | FieldType::LocaltimeIntoDay
+            | FieldType::Uint8
+            | FieldType::Uint8Z
+            | FieldType::Uint16
+            | FieldType::Uint16Z
+            | FieldType::Uint32
+            | FieldType::Uint32Z
+            | FieldType::Sint8,
+            value,
+        ) => value,
+        (field_type, Value::U8(v)) => match match_predefined_field_value(field_type, v as usize) {
+            Some(name) => Value::Enum(name),
+            None => Value::U8(v),
+        },
+        (field_type, Value::U16(v)) => match match_predefined_field_value(field_type, v as usize) {
+            Some(name) => Value::Enum(name),
+            None => Value::U16(v),
+        },
+        (_, value) => value,
     }
-    // get altitude
-    if record.field_num == 2 {
-        match record.value {
-            fit::Value::U16(val) => {
-                *altitude = val as f64 / 10.0; // turn cm into meter
+}
+
+// pure rust decoder of fit files, used where the fit crate is not available like on wasm32. Like
+// `parse_fit`, each message with at least one valid field becomes a row, but without the row the
+// fit crate reads from the crc at the end of the file. Developer fields are skipped.
+pub fn decode_fit_bytes(bytes: &[u8]) -> Result<FitData, errors::InputDataError> {
+    let mut fit_data = FitData {
+        times: vec![],
+        coordinates: vec![],
+        altitudes: vec![],
+        distances: vec![],
+        calories: 0,
+        timer_pauses: vec![],
+        record_messages: vec![],
+    };
+    let mut timer_stop: Option<f64> = None;
+    let mut last_timestamp: u32 = 0;
+    walk_fit_messages(bytes, |message| {
+        let definition = message.definition;
+        let message_type = match_messagetype(definition.global_number);
+        if message.is_definition()
+            || message_type == MessageType::None
+            || definition.global_number == FIELD_DESCRIPTION_MESSAGE
+        {
+            return;
+        }
+        let field_types = match_message_field(message_type);
+        let mut values = vec![];
+        let mut position = 0;
+        for (field_num, size, base_type) in &definition.fields {
+            let field = &message.fields[position..position + *size as usize];
+            position += *size as usize;
+            if let Some(value) = decode_value(*base_type, field, definition.big_endian) {
+                values.push((
+                    *field_num,
+                    process_value(value, field_types(*field_num as usize)),
+                ));
             }
-            _ => {}
         }
-    }
+        if values.is_empty() {
+            return;
+        }
+        if let Some((_, Value::Time(t))) = values.iter().find(|(num, _)| *num == 253) {
+            last_timestamp = t.wrapping_sub(PSEUDO_EPOCH);
+        }
+        // compressed timestamp headers only hold the lower 5 bits of the time since the last
+        // full timestamp
+        if message.header & COMPRESSED_HEADER_MASK != 0 {
+            let offset = (message.header & 0x1F) as u32;
+            let mut timestamp = (last_timestamp & 0xFFFF_FFE0) + offset;
+            if offset < last_timestamp & 0x1F {
+                timestamp += 0x20;
+            }
+            last_timestamp = timestamp;
+            if let Some(field_num) = match_message_timestamp_field(message_type) {
+                values.push((
+                    field_num as u8,
+                    Value::Time(timestamp.wrapping_add(PSEUDO_EPOCH)),
+                ));
+            }
+        }
+        let mut latitude = f64::NAN;
+        let mut longitude = f64::NAN;
+        let mut timestamp = f64::NAN;
+        let mut altitude = f64::NAN;
+        let mut distance = f64::NAN;
+        let mut event = None;
+        let mut event_type = None;
+        for (field_num, value) in &values {
+            match (field_num, value) {
+                (0, Value::F32(val)) => latitude = *val as f64,
+                (1, Value::F32(val)) => longitude = *val as f64,
+                (253, Value::Time(val)) => timestamp = *val as f64,
+                (2, Value::U16(val)) => altitude = *val as f64 / 10.0, // turn cm into meter
+                (11, Value::U16(val)) => fit_data.calories = *val,
+                // field 5 holds other values in other messages
+                (5, Value::U32(val)) if message_type == MessageType::Record => {
+                    distance = *val as f64 / 100.0 // turn cm into meter
+                }
+                (0, Value::Enum(val)) if message_type == MessageType::Event => event = Some(*val),
+                (1, Value::Enum(val)) if message_type == MessageType::Event => {
+                    event_type = Some(*val)
+                }
+                _ => (),
+            }
+        }
+        if !timestamp.is_nan() {
+            match (event, event_type) {
+                (Some(TIMER_EVENT), Some(TIMER_START)) => {
+                    if let Some(stop) = timer_stop.take() {
+                        fit_data.timer_pauses.push((stop, timestamp));
+                    }
+                }
+                (Some(TIMER_EVENT), Some(event_type)) if TIMER_STOPS.contains(&event_type) => {
+                    timer_stop.get_or_insert(timestamp);
+                }
+                _ => (),
+            }
+        }
+        fit_data.times.push(timestamp);
+        fit_data.altitudes.push(altitude);
+        fit_data.coordinates.push((latitude, longitude));
+        fit_data.distances.push(distance);
+        fit_data
+            .record_messages
+            .push(message_type == MessageType::Record);
+    })?;
+    Ok(fit_data)
 }
 
-pub fn parse_fit(path_to_fit: &str) -> FitData {
-    let filepath = PathBuf::from(path_to_fit);
-    let fit_file = Fit::new(&filepath);
+#[cfg(not(target_arch = "wasm32"))]
+fn read_fit(filepath: &PathBuf) -> FitData {
+    let fit_file = Fit::new(filepath);
     let mut fit_data = FitData {
         times: vec![],
         coordinates: vec![],
         altitudes: vec![],
+        distances: vec![],
         calories: 0,
+        timer_pauses: vec![],
+        record_messages: vec![],
     };
-    let mut timestamp: f64;
-    let mut latitude: f64;
-    let mut longitude: f64;
-    let mut altitude: f64;
+    let mut timer_stop: Option<f64> = None;
     for message in fit_file {
-        // println!("------------------");
-        // println!("{:?}", message.values);
-        latitude = f64::NAN;
-        longitude = f64::NAN;
-        timestamp = f64::NAN;
-        altitude = f64::NAN;
-        for record in message.values {
-            // get time series values, e.g. coordinates, timestamps and altitude
-            match_time_series_values(
-                &record,
-                &mut latitude,
-                &mut longitude,
-                &mut timestamp,
-                &mut altitude,
-            );
-            // get one time values, e.g. overall calories, ...
-            match_one_time_values(&record, &mut fit_data);
+        let mut latitude = f64::NAN;
+        let mut longitude = f64::NAN;
+        let mut timestamp = f64::NAN;
+        let mut altitude = f64::NAN;
+        let mut distance = f64::NAN;
+        let mut event = None;
+        let mut event_type = None;
+        for record in &message.values {
+            match (record.field_num, &record.value) {
+                (0, fit::Value::F32(val)) => latitude = *val as f64,
+                (1, fit::Value::F32(val)) => longitude = *val as f64,
+                (253, fit::Value::Time(val)) => timestamp = *val as f64,
+                (2, fit::Value::U16(val)) => altitude = *val as f64 / 10.0, // turn cm into meter
+                (11, fit::Value::U16(val)) => fit_data.calories = *val,
+                // field 5 holds other values in other messages
+                (5, fit::Value::U32(val)) if message.kind == MessageType::Record => {
+                    distance = *val as f64 / 100.0 // turn cm into meter
+                }
+                // the fit crate maps the values of enum fields to their names
+                (0, fit::Value::Enum(val)) if message.kind == MessageType::Event => {
+                    event = Some(*val)
+                }
+                (1, fit::Value::Enum(val)) if message.kind == MessageType::Event => {
+                    event_type = Some(*val)
+                }
+                _ => (),
+            }
+        }
+        if !timestamp.is_nan() {
+            match (event, event_type) {
+                (Some(TIMER_EVENT), Some(TIMER_START)) => {
+                    if let Some(stop) = timer_stop.take() {
+                        fit_data.timer_pauses.push((stop, timestamp));
+                    }
+                }
+                (Some(TIMER_EVENT), Some(event_type)) if TIMER_STOPS.contains(&event_type) => {
+                    timer_stop.get_or_insert(timestamp);
+                }
+                _ => (),
+            }
         }
         fit_data.times.push(timestamp);
         fit_data.altitudes.push(altitude);
         fit_data.coordinates.push((latitude, longitude));
+        fit_data.distances.push(distance);
+        fit_data
+            .record_messages
+            .push(message.kind == MessageType::Record);
+    }
+    fit_data
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_fit(path_to_fit: &str) -> Result<FitData, errors::InputDataError> {
+    let filepath = PathBuf::from(path_to_fit);
+    let bytes = std::fs::read(&filepath).map_err(|_| errors::InputDataError::FileNotReadable)?;
+    match strip_developer_fields(&bytes)? {
+        Cow::Borrowed(_) => Ok(read_fit(&filepath)),
+        Cow::Owned(stripped) => parse_fit_bytes(&stripped),
     }
-    assert_eq!(fit_data.times.len(), fit_data.coordinates.len());
-    assert_eq!(fit_data.times.len(), fit_data.altitudes.len());
-    return fit_data;
+}
+
+// temporary copy of fit bytes, which is removed when dropped, also if parsing panics. The name is
+// randomized and the file is created exclusively, so that no existing file or symlink in the
+// shared temp dir is written to.
+#[cfg(not(target_arch = "wasm32"))]
+struct TempFile {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TempFile {
+    fn new(bytes: &[u8]) -> std::io::Result<TempFile> {
+        static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+        loop {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_usize(TEMP_FILES.fetch_add(1, Ordering::Relaxed));
+            let path = std::env::temp_dir().join(format!(
+                "sportgems_{}_{:016x}.fit",
+                std::process::id(),
+                hasher.finish()
+            ));
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let temp_file = TempFile { path };
+                    file.write_all(bytes)?;
+                    return Ok(temp_file);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// the fit crate can only read files, therefore the bytes are written to a temporary file first
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_fit_bytes(bytes: &[u8]) -> Result<FitData, errors::InputDataError> {
+    let bytes = strip_developer_fields(bytes)?;
+    let temp_file = TempFile::new(&bytes).map_err(|_| errors::InputDataError::InvalidFitFile)?;
+    Ok(read_fit(&temp_file.path))
 }
 
 #[cfg(test)]
@@ -116,12 +618,260 @@ mod test_fit_reader {
 
     #[test]
     fn test_parse_fit() {
-        let fit = parse_fit(FIT_FILE);
+        let fit = parse_fit(FIT_FILE).unwrap();
         assert_eq!(fit.times.len(), fit.coordinates.len());
         assert!(fit.times[0].is_nan());
         assert_eq!(fit.coordinates[100], (49.40629959106445, 8.695788383483887));
         assert_eq!(fit.times[100], (1568474841.0));
         assert_eq!(fit.altitudes[100], (254.9));
+        assert_eq!(fit.distances.len(), fit.times.len());
+        assert_eq!(fit.distances[100], 766.55);
         assert_eq!(fit.calories, 432);
+        assert!(fit.has_coordinates());
+        assert!(fit.has_distances());
+        assert!(!fit.use_device_distances());
+        // the timer is started once and stopped at the end
+        assert!(fit.timer_pauses.is_empty());
+        assert_eq!(fit.record_messages.len(), fit.times.len());
+        assert!(!fit.record_messages[0] && fit.record_messages[100]);
+    }
+
+    #[test]
+    fn test_parse_fit_missing_file() {
+        assert_eq!(
+            parse_fit("tests/data/does_not_exist.fit").err(),
+            Some(errors::InputDataError::FileNotReadable)
+        );
+    }
+
+    #[test]
+    fn test_parse_fit_no_fit_file() {
+        assert_eq!(
+            parse_fit("Cargo.toml").err(),
+            Some(errors::InputDataError::InvalidFitFile)
+        );
+    }
+
+    #[test]
+    fn test_parse_fit_truncated_file() {
+        let bytes = std::fs::read(FIT_FILE).unwrap();
+        let path = std::env::temp_dir().join("sportgems_test_parse_fit_truncated_file.fit");
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        assert_eq!(
+            parse_fit(path.to_str().unwrap()).err(),
+            Some(errors::InputDataError::InvalidFitFile)
+        );
+    }
+
+    #[test]
+    fn test_parse_fit_bytes() {
+        let bytes = std::fs::read(FIT_FILE).unwrap();
+        let fit = parse_fit_bytes(&bytes).unwrap();
+        assert_eq!(fit.times.len(), 1231);
+        assert_eq!(fit.coordinates[100], (49.40629959106445, 8.695788383483887));
+        assert_eq!(
+            parse_fit_bytes(&bytes[..1_000]).err(),
+            Some(errors::InputDataError::InvalidFitFile)
+        );
+        assert_eq!(
+            parse_fit_bytes(&[]).err(),
+            Some(errors::InputDataError::InvalidFitFile)
+        );
+    }
+
+    #[test]
+    fn test_temp_file() {
+        let first = TempFile::new(b"first").unwrap();
+        let second = TempFile::new(b"second").unwrap();
+        assert_ne!(first.path, second.path);
+        assert_eq!(std::fs::read(&first.path).unwrap(), b"first");
+        let path = first.path.clone();
+        drop(first);
+        assert!(!path.exists());
+        assert!(second.path.exists());
+    }
+
+    #[test]
+    fn test_walk_fit_messages() {
+        let bytes = std::fs::read(FIT_FILE).unwrap();
+        assert_eq!(walk_fit_messages(&bytes, |_| ()), Ok(()));
+        // the crc at the end of the file is required, otherwise the fit crate does not stop
+        assert_eq!(
+            walk_fit_messages(&bytes[..bytes.len() - 2], |_| ()),
+            Err(errors::InputDataError::InvalidFitFile)
+        );
+        // the first definition message has an invalid architecture byte
+        let mut invalid = bytes.clone();
+        invalid[16] = 2;
+        assert_eq!(
+            walk_fit_messages(&invalid, |_| ()),
+            Err(errors::InputDataError::InvalidFitFile)
+        );
+        // a data message of a local message number which was never defined
+        let mut invalid = bytes;
+        invalid[14] = 0x0F;
+        assert_eq!(
+            walk_fit_messages(&invalid, |_| ()),
+            Err(errors::InputDataError::InvalidFitFile)
+        );
+    }
+
+    // fit file with a record every second, with a developer field e.g. of a power meter app
+    // after the fields of each record if `developer_fields` is set
+    fn fit_file_with_records(records: u32, developer_fields: bool) -> Vec<u8> {
+        let mut data: Vec<u8> = vec![];
+        if developer_fields {
+            // developer data id and the description of the developer field
+            data.extend_from_slice(&[0x44, 0, 0, 207, 0, 1, 3, 1, 0x02]);
+            data.extend_from_slice(&[0x04, 0]);
+            data.extend_from_slice(&[0x45, 0, 0, 206, 0, 4, 0, 1, 0x02, 1, 1, 0x02, 2, 1, 0x02]);
+            data.extend_from_slice(&[3, 8, 0x07]);
+            data.extend_from_slice(&[0x05, 0, 0, 0x84]);
+            data.extend_from_slice(b"power\0\0\0");
+        }
+        let header = if developer_fields { 0x61 } else { 0x41 };
+        data.extend_from_slice(&[header, 0, 0, 20, 0, 4, 253, 4, 0x86, 0, 4, 0x85, 1, 4, 0x85]);
+        data.extend_from_slice(&[2, 2, 0x84]);
+        if developer_fields {
+            data.extend_from_slice(&[1, 0, 2, 0]);
+        }
+        let semicircles = |degree: f64| (degree * 2f64.powi(31) / 180.0).round() as i32;
+        for i in 0..records {
+            data.push(0x01);
+            data.extend_from_slice(&(1_000_000_000 + i).to_le_bytes());
+            data.extend_from_slice(&semicircles(48.0).to_le_bytes());
+            data.extend_from_slice(&semicircles(8.0 + i as f64 * 1e-4).to_le_bytes());
+            data.extend_from_slice(&(2_500 + i as u16).to_le_bytes());
+            if developer_fields {
+                data.extend_from_slice(&(200 + i as u16).to_le_bytes());
+            }
+        }
+        // header without crc, the crc is not checked
+        let mut bytes = vec![14, 0x10];
+        bytes.extend_from_slice(&2093u16.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b".FIT");
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(&data);
+        bytes.extend_from_slice(&[0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_fit_developer_fields() {
+        let bytes = fit_file_with_records(20, true);
+        let plain = fit_file_with_records(20, false);
+        assert!(matches!(
+            strip_developer_fields(&plain),
+            Ok(Cow::Borrowed(_))
+        ));
+        // the definitions of the developer data id and the field description are kept
+        assert!(strip_developer_fields(&bytes)
+            .unwrap()
+            .ends_with(&plain[14..]));
+        let fit = parse_fit_bytes(&bytes).unwrap();
+        let expected = parse_fit_bytes(&plain).unwrap();
+        // the developer data id is read as a row without values in front of the records
+        assert!(!fit.record_messages[0] && fit.times[0].is_nan());
+        assert_eq!(fit.times[1..], expected.times);
+        assert_eq!(fit.altitudes[1..], expected.altitudes);
+        assert_eq!(fit.coordinates[20], expected.coordinates[19]);
+        assert_eq!(fit.times[20], 1_000_000_019. + 631_065_600.);
+        assert_eq!(fit.altitudes[20], 251.9);
+        // files holding developer fields are also read from a path
+        let path = std::env::temp_dir().join("sportgems_test_parse_fit_developer_fields.fit");
+        std::fs::write(&path, &bytes).unwrap();
+        let from_path = parse_fit(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(from_path.times[1..], fit.times[1..]);
+    }
+
+    // compare floats with NaN being equal to NaN
+    fn same(a: f64, b: f64) -> bool {
+        a == b || (a.is_nan() && b.is_nan())
+    }
+
+    #[test]
+    fn test_decode_fit_bytes() {
+        let bytes = std::fs::read(FIT_FILE).unwrap();
+        let fit = decode_fit_bytes(&bytes).unwrap();
+        let expected = parse_fit(FIT_FILE).unwrap();
+        // the fit crate reads one more row from the crc at the end of the file
+        assert_eq!(fit.times.len(), 1230);
+        assert_eq!(expected.times.len(), 1231);
+        for i in 0..fit.times.len() {
+            assert!(same(fit.times[i], expected.times[i]), "time {}", i);
+            assert!(same(fit.coordinates[i].0, expected.coordinates[i].0));
+            assert!(same(fit.coordinates[i].1, expected.coordinates[i].1));
+            assert!(
+                same(fit.altitudes[i], expected.altitudes[i]),
+                "altitude {}",
+                i
+            );
+            assert!(
+                same(fit.distances[i], expected.distances[i]),
+                "distance {}",
+                i
+            );
+        }
+        assert_eq!(fit.record_messages[..], expected.record_messages[..1230]);
+        assert_eq!(fit.calories, expected.calories);
+        assert_eq!(fit.timer_pauses, expected.timer_pauses);
+        let fastest =
+            crate::velocity::find_fastest_section_in_fit_data(1_000., &fit, Some(0.01)).unwrap();
+        assert_eq!((fastest.start, fastest.end), (628, 719));
+        assert!(decode_fit_bytes(&bytes[..100]).is_err());
+        assert!(matches!(
+            decode_fit_bytes(b"no fit file"),
+            Err(errors::InputDataError::InvalidFitFile)
+        ));
+        // developer fields are skipped
+        let fit = decode_fit_bytes(&fit_file_with_records(20, true)).unwrap();
+        let expected = decode_fit_bytes(&fit_file_with_records(20, false)).unwrap();
+        assert_eq!(fit.times[fit.times.len() - 20..], expected.times[..]);
+        assert_eq!(
+            fit.altitudes[fit.times.len() - 20..],
+            expected.altitudes[..]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let fit = parse_fit(FIT_FILE).unwrap();
+        // the test file has records without coordinates, which are written as null
+        assert!(fit.coordinates.iter().any(|(lat, _)| lat.is_nan()));
+        let json = serde_json::to_string(&fit).unwrap();
+        assert!(json.starts_with(r#"{"calories":432,"times":["#));
+        assert!(json.contains("[null,null]"));
+        let parsed: FitData = serde_json::from_str(&json).unwrap();
+        let same = |a: f64, b: f64| a == b || a.is_nan() && b.is_nan();
+        assert_eq!(parsed.calories, fit.calories);
+        assert_eq!(parsed.times.len(), fit.times.len());
+        assert!(fit
+            .times
+            .iter()
+            .zip(&parsed.times)
+            .all(|(a, b)| same(*a, *b)));
+        assert_eq!(parsed.coordinates.len(), fit.coordinates.len());
+        assert!(fit
+            .coordinates
+            .iter()
+            .zip(&parsed.coordinates)
+            .all(|(a, b)| same(a.0, b.0) && same(a.1, b.1)));
+        assert_eq!(parsed.altitudes.len(), fit.altitudes.len());
+        assert!(fit
+            .altitudes
+            .iter()
+            .zip(&parsed.altitudes)
+            .all(|(a, b)| same(*a, *b)));
+        assert_eq!(parsed.distances.len(), fit.distances.len());
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+        // json written before the distances were parsed can still be read
+        let parsed: FitData = serde_json::from_str(
+            r#"{"calories":1,"times":[1.0],"coordinates":[[48.0,8.0]],"altitudes":[null]}"#,
+        )
+        .unwrap();
+        assert!(parsed.distances.is_empty());
     }
 }
@@ -1,9 +1,70 @@
+use crate::climb;
+#[cfg(feature = "serde")]
+use crate::dtypes::nan_as_null;
 use crate::errors;
-use crate::math;
+use crate::fit_reader;
+use crate::math::{self, IsNaN};
+use crate::pauses;
+use crate::smoothing;
+use crate::timestamps;
+use crate::velocity;
 use crate::{dtypes, errors::InputDataError};
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::OnceLock;
 
 pub const DEFAULT_TOLERANCE: f64 = 0.01;
 
+// options of the section search, the `_with_options` variants of the finders take these instead
+// of only the tolerance
+#[derive(Clone, Debug, PartialEq)]
+pub struct FinderOptions {
+    pub tolerance: f64,
+    pub distance_model: math::DistanceModel,
+    // include the altitude change in the distance between two coordinates, only applied if
+    // altitudes are given
+    pub slope_distance: bool,
+    // filter of the gps noise before the distances are computed
+    pub smoothing: smoothing::Smoothing,
+    // filter of the altitude noise before the gained altitude is computed
+    pub elevation_smoothing: smoothing::Smoothing,
+    pub gain_method: climb::GainMethod,
+    // how missing coordinates, altitudes and device distances are filled
+    pub fill: math::FillStrategy,
+    // time the velocity of the fastest section is computed on, the duration of the section is
+    // still the elapsed time
+    pub moving_time: pauses::MovingTime,
+    // gap in seconds between two records, which no section spans. Infinite to search across gaps.
+    pub segment_gap: f64,
+}
+
+impl FinderOptions {
+    pub fn with_tolerance(tolerance: Option<f64>) -> FinderOptions {
+        FinderOptions {
+            tolerance: tolerance.unwrap_or(DEFAULT_TOLERANCE),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for FinderOptions {
+    fn default() -> FinderOptions {
+        FinderOptions {
+            tolerance: DEFAULT_TOLERANCE,
+            distance_model: math::DistanceModel::default(),
+            slope_distance: false,
+            smoothing: smoothing::Smoothing::default(),
+            elevation_smoothing: smoothing::Smoothing::default(),
+            gain_method: climb::GainMethod::default(),
+            fill: math::FillStrategy::default(),
+            moving_time: pauses::MovingTime::default(),
+            segment_gap: timestamps::DEFAULT_SEGMENT_GAP,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct InputData {
     pub desired_distance: f64,
@@ -12,6 +73,20 @@ pub struct InputData {
     pub distances: dtypes::Distances,
     pub altitudes: dtypes::Altitudes,
     pub tolerance: f64,
+    pub distance_model: math::DistanceModel,
+    pub slope_distance: bool,
+    pub smoothing: smoothing::Smoothing,
+    pub elevation_smoothing: smoothing::Smoothing,
+    pub gain_method: climb::GainMethod,
+    pub fill: math::FillStrategy,
+    pub moving_time: pauses::MovingTime,
+    // periods in which the timer of the device was stopped, only known for fit files
+    pub timer_pauses: Vec<(f64, f64)>,
+    // cumulative moving time up to each record, empty if the velocity is computed on the times
+    pub moving_times: dtypes::Times,
+    pub segment_gap: f64,
+    // records after a gap or a clock reset, the first record of a new segment
+    pub segment_breaks: Vec<usize>,
 }
 
 impl InputData {
@@ -35,12 +110,87 @@ impl InputData {
                         values: altitudes.unwrap_or(vec![]),
                     },
                     tolerance: tolerance.unwrap_or(DEFAULT_TOLERANCE),
+                    distance_model: math::DistanceModel::default(),
+                    slope_distance: false,
+                    smoothing: smoothing::Smoothing::default(),
+                    elevation_smoothing: smoothing::Smoothing::default(),
+                    gain_method: climb::GainMethod::default(),
+                    fill: math::FillStrategy::default(),
+                    moving_time: pauses::MovingTime::default(),
+                    timer_pauses: vec![],
+                    moving_times: dtypes::Times { values: vec![] },
+                    segment_gap: timestamps::DEFAULT_SEGMENT_GAP,
+                    segment_breaks: vec![],
                 }),
                 Err(e) => Err(e),
             }
         }
     }
 
+    pub fn with_options(
+        desired_distance: f64,
+        coordinates: Vec<(f64, f64)>,
+        times: Vec<f64>,
+        altitudes: Option<Vec<f64>>,
+        options: &FinderOptions,
+    ) -> Result<InputData, errors::InputDataError> {
+        let mut input_data = InputData::new(
+            desired_distance,
+            coordinates,
+            times,
+            altitudes,
+            Some(options.tolerance),
+        )?;
+        input_data.distance_model = options.distance_model;
+        input_data.slope_distance = options.slope_distance;
+        input_data.smoothing = options.smoothing;
+        input_data.elevation_smoothing = options.elevation_smoothing;
+        input_data.gain_method = options.gain_method;
+        input_data.fill = options.fill;
+        input_data.moving_time = options.moving_time;
+        input_data.segment_gap = options.segment_gap;
+        Ok(input_data)
+    }
+
+    // search on cumulative distances recorded by the device instead of coordinates, e.g. for
+    // indoor activities. The distances are not recomputed, so the distance model, the slope
+    // distance and the smoothing do not apply.
+    pub fn with_distances(
+        desired_distance: f64,
+        distances: Vec<f64>,
+        times: Vec<f64>,
+        altitudes: Option<Vec<f64>>,
+        options: &FinderOptions,
+    ) -> Result<InputData, errors::InputDataError> {
+        if desired_distance <= 0.0 {
+            return Err(InputDataError::InvalidDesiredDistance);
+        }
+        distance_data_checks(&distances, &times)?;
+        let mut distances = distances;
+        fill_device_distances(&mut distances, &times, options.fill);
+        Ok(InputData {
+            desired_distance,
+            coordinates: vec![(f64::NAN, f64::NAN); times.len()],
+            times: dtypes::Times { values: times },
+            distances: dtypes::Distances { values: distances },
+            altitudes: dtypes::Altitudes {
+                values: altitudes.unwrap_or_default(),
+            },
+            tolerance: options.tolerance,
+            distance_model: options.distance_model,
+            slope_distance: false,
+            smoothing: smoothing::Smoothing::None,
+            elevation_smoothing: options.elevation_smoothing,
+            gain_method: options.gain_method,
+            fill: options.fill,
+            moving_time: options.moving_time,
+            timer_pauses: vec![],
+            moving_times: dtypes::Times { values: vec![] },
+            segment_gap: options.segment_gap,
+            segment_breaks: vec![],
+        })
+    }
+
     pub fn check_if_total_distance_suffice(&self) -> Result<(), errors::InputDataError> {
         let total_distance = self.distances.values.last().unwrap().clone();
         if self.desired_distance > total_distance {
@@ -50,24 +200,78 @@ impl InputData {
         }
     }
 
+    // the smoothed coordinates are only used for the distances, the sections still report the
+    // recorded coordinates
     pub fn compute_vector_of_distances(&mut self) {
-        let mut distance: f64 = 0.0;
-        self.distances.values.push(distance);
-
-        // loop through coordinates and calculate the distance from one coordinate to the next one
-        for i in 0..self.coordinates.len() - 1 {
-            let coordinate = dtypes::Coordinate {
-                lat: self.coordinates[i].0,
-                lon: self.coordinates[i].1,
-            };
-            let next_coordinate = dtypes::Coordinate {
-                lat: self.coordinates[i + 1].0,
-                lon: self.coordinates[i + 1].1,
+        let coordinates =
+            smoothing::smooth_coordinates(&self.coordinates, &self.times.values, self.smoothing);
+        self.distances.values =
+            if self.slope_distance && self.altitudes.values.len() == self.coordinates.len() {
+                // altitudes might not be filled yet, the climb finder checks them first
+                let mut altitudes = self.altitudes.values.clone();
+                self.fill.fill(&mut altitudes, &self.times.values);
+                cumulative_slope_distances(&coordinates, &altitudes, self.distance_model)
+            } else {
+                cumulative_distances(&coordinates, self.distance_model)
             };
-            distance += math::calculate_distance(coordinate, next_coordinate);
-            self.distances.values.push(distance);
+    }
+
+    pub fn fill_coordinates(&mut self) {
+        self.fill.fill(&mut self.coordinates, &self.times.values);
+    }
+
+    // missing altitudes are only filled if an altitude is given for every record
+    pub fn fill_altitudes(&mut self) {
+        if self.altitudes.values.len() == self.times.values.len() {
+            self.fill
+                .fill(&mut self.altitudes.values, &self.times.values);
         }
     }
+
+    // the elevation smoothing only applies to the gained altitude, the slope distances are
+    // computed from the recorded altitudes. Expects the altitudes to be filled already.
+    pub fn smooth_altitudes(&mut self) {
+        if self.altitudes.values.len() != self.times.values.len() {
+            return;
+        }
+        if let Cow::Owned(altitudes) = smoothing::smooth_values(
+            &self.altitudes.values,
+            &self.times.values,
+            self.elevation_smoothing,
+        ) {
+            self.altitudes.values = altitudes;
+        }
+    }
+
+    // duplicated and out of order times are dropped, the segments are split at the remaining
+    // clock resets and gaps. Needs to run before the moving times are computed.
+    pub fn split_segments(&mut self) {
+        self.times.values = timestamps::repair_times(&self.times.values);
+        self.segment_breaks =
+            timestamps::check_times(&self.times.values, self.segment_gap).segment_breaks();
+    }
+
+    // the stationary periods are detected on the distances, so these need to be computed first
+    pub fn compute_moving_times(&mut self) {
+        self.moving_times.values = self
+            .moving_time
+            .cumulative_times(
+                &self.times.values,
+                &self.distances.values,
+                &self.timer_pauses,
+            )
+            .unwrap_or_default();
+    }
+
+    // times the velocity of a section is computed on
+    pub fn velocity_times(&self) -> &dtypes::Times {
+        if self.moving_times.values.is_empty() {
+            &self.times
+        } else {
+            &self.moving_times
+        }
+    }
+
     // implementation of the search algorithm, takes an update func (which depends on the use case) as input argument
     pub fn search_section(
         &mut self,
@@ -75,6 +279,7 @@ impl InputData {
     ) -> Result<dtypes::TargetSection, errors::InputDataError> {
         let mut window_sec = dtypes::WindowSection::default();
         let mut target_sec = dtypes::TargetSection::default();
+        let mut segment_breaks = self.segment_breaks.iter().peekable();
         while window_sec.end < self.distances.values.len() as u32 - 1 {
             // println!("{:?}", window_sec);
 
@@ -89,15 +294,65 @@ impl InputData {
                     window_sec.end += 1;
                 }
             }
+            // sections never span a gap, the window starts over at the first record after it
+            if segment_breaks
+                .next_if(|b| **b as u32 <= window_sec.end)
+                .is_some()
+            {
+                window_sec.start = window_sec.end;
+            }
+            // an empty window has no distance yet, e.g. after a gap at the last record
+            if window_sec.start == window_sec.end {
+                continue;
+            }
             update_func(&self, &mut window_sec, &mut target_sec);
         }
         // after the while loop is finished, check that found fastest_section is valid and return
         if target_sec.target_value == 0.0 || target_sec.start == target_sec.end {
             Err(errors::InputDataError::NoSectionFound)
         } else {
+            self.describe_section(&mut target_sec);
             Ok(target_sec)
         }
     }
+
+    // time of a section boundary, the times of duplicated and out of order records are missing
+    // after `split_segments`, these take the nearest valid time before them, which is the time
+    // the duplicated record was written at
+    fn boundary_time(&self, index: usize) -> f64 {
+        let times = &self.times.values;
+        times[..=index]
+            .iter()
+            .rev()
+            .chain(&times[index + 1..])
+            .copied()
+            .find(|time| !time.is_nan())
+            .unwrap_or(f64::NAN)
+    }
+
+    // fill in the details of the found section, which are not needed while searching
+    fn describe_section(&self, section: &mut dtypes::TargetSection) {
+        let (start, end) = (section.start as usize, section.end as usize);
+        section.distance = get_distance(&self.distances.values, start, end);
+        section.start_time = self.boundary_time(start);
+        section.end_time = self.boundary_time(end);
+        section.duration = section.end_time - section.start_time;
+        section.start_coordinate = self.coordinates[start];
+        section.end_coordinate = self.coordinates[end];
+        if self.altitudes.values.len() == self.coordinates.len()
+            && self
+                .altitudes
+                .values
+                .iter()
+                .any(|altitude| !altitude.is_nan())
+        {
+            section.elevation_gain = Some(self.gain_method.gained_altitude(
+                &self.altitudes.values,
+                start,
+                end,
+            ));
+        }
+    }
 }
 
 pub fn distance_in_bounds(
@@ -105,34 +360,579 @@ pub fn distance_in_bounds(
     desired_distance: f64,
     percentage_threshold: f64,
 ) -> bool {
-    if (window_distance <= (desired_distance * (1.0 + percentage_threshold)))
+    (window_distance <= (desired_distance * (1.0 + percentage_threshold)))
         && (window_distance >= (desired_distance * (1.0 - percentage_threshold)))
-    {
-        true
-    } else {
-        false
-    }
 }
 
-pub fn get_distance(distances: &Vec<f64>, start: usize, end: usize) -> f64 {
+pub fn get_distance(distances: &[f64], start: usize, end: usize) -> f64 {
     distances[end] - distances[start + 1]
 }
 
+// the device distances are cumulative, records the fill strategy leaves missing keep the last
+// recorded distance, as the next recorded distance already includes the distance in between
+fn fill_device_distances(distances: &mut [f64], times: &[f64], fill: math::FillStrategy) {
+    fill.fill(distances, times);
+    math::fill_nans(distances);
+}
+
+// cumulative distance from the first coordinate up to each coordinate
+pub fn cumulative_distances(
+    coordinates: &[(f64, f64)],
+    distance_model: math::DistanceModel,
+) -> Vec<f64> {
+    let mut distance: f64 = 0.0;
+    let mut distances = vec![distance];
+
+    // loop through coordinates and calculate the distance from one coordinate to the next one,
+    // missing coordinates add no distance
+    for i in 0..coordinates.len() - 1 {
+        if !coordinates[i].nan() && !coordinates[i + 1].nan() {
+            distance += distance_model.distance(coordinates[i], coordinates[i + 1]);
+        }
+        distances.push(distance);
+    }
+    distances
+}
+
+// like `cumulative_distances`, but including the altitude change between the coordinates
+pub fn cumulative_slope_distances(
+    coordinates: &[(f64, f64)],
+    altitudes: &[f64],
+    distance_model: math::DistanceModel,
+) -> Vec<f64> {
+    let mut distance: f64 = 0.0;
+    let mut distances = vec![distance];
+    for i in 0..coordinates.len() - 1 {
+        // missing coordinates add no distance, missing altitudes no altitude change
+        if !coordinates[i].nan() && !coordinates[i + 1].nan() {
+            distance += math::slope_distance(
+                distance_model.distance(coordinates[i], coordinates[i + 1]),
+                altitudes[i],
+                altitudes[i + 1],
+            );
+        }
+        distances.push(distance);
+    }
+    distances
+}
+
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActivitySummary {
+    pub records: usize,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub start_time: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub end_time: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub duration: f64, // in seconds
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub distance: f64, // in meter
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub average_velocity: f64, // in m/s
+    pub elevation_gain: Option<f64>, // in meter, only known if altitudes are given
+    pub elevation_loss: Option<f64>, // in meter, only known if altitudes are given
+    pub calories: Option<u16>,       // only known for activities parsed from fit files
+}
+
+// an activity holding the raw data of e.g. a fit file, data derived from the raw data like the
+// cleaned coordinates and the cumulative distances is computed once on first use and then reused
+// for all searches on the activity
+#[derive(Debug)]
+pub struct Activity {
+    times: Vec<f64>,
+    coordinates: Vec<(f64, f64)>,
+    altitudes: Option<Vec<f64>>,
+    // cumulative distances recorded by the device, used instead of the coordinates
+    device_distances: Option<Vec<f64>>,
+    calories: Option<u16>,
+    distance_model: math::DistanceModel,
+    slope_distance: bool,
+    smoothing: smoothing::Smoothing,
+    elevation_smoothing: smoothing::Smoothing,
+    gain_method: climb::GainMethod,
+    fill: math::FillStrategy,
+    moving_time: pauses::MovingTime,
+    timer_pauses: Vec<(f64, f64)>,
+    // like `FitData::record_messages`, empty if all rows are records
+    record_messages: Vec<bool>,
+    // indexes of the altitudes removed by the outlier filter of fit files
+    altitude_outliers: Vec<usize>,
+    segment_gap: f64,
+    cleaned_coordinates: OnceLock<Vec<(f64, f64)>>,
+    cleaned_altitudes: OnceLock<Option<Vec<f64>>>,
+    smoothed_altitudes: OnceLock<Option<Vec<f64>>>,
+    distances: OnceLock<Vec<f64>>,
+    ascent_descent: OnceLock<Option<(f64, f64)>>,
+}
+
+impl Activity {
+    pub fn new(
+        times: Vec<f64>,
+        coordinates: Vec<(f64, f64)>,
+        altitudes: Option<Vec<f64>>,
+    ) -> Result<Activity, errors::InputDataError> {
+        generic_data_checks(&coordinates, &times)?;
+        if let Some(altitudes) = &altitudes {
+            if altitudes.len() != coordinates.len() {
+                return Err(InputDataError::InconsistentLength);
+            }
+        }
+        Ok(Activity {
+            times,
+            coordinates,
+            altitudes,
+            device_distances: None,
+            calories: None,
+            distance_model: math::DistanceModel::default(),
+            slope_distance: false,
+            smoothing: smoothing::Smoothing::default(),
+            elevation_smoothing: smoothing::Smoothing::default(),
+            gain_method: climb::GainMethod::default(),
+            fill: math::FillStrategy::default(),
+            moving_time: pauses::MovingTime::default(),
+            timer_pauses: vec![],
+            record_messages: vec![],
+            altitude_outliers: vec![],
+            segment_gap: timestamps::DEFAULT_SEGMENT_GAP,
+            cleaned_coordinates: OnceLock::new(),
+            cleaned_altitudes: OnceLock::new(),
+            smoothed_altitudes: OnceLock::new(),
+            distances: OnceLock::new(),
+            ascent_descent: OnceLock::new(),
+        })
+    }
+
+    // activity without coordinates, e.g. an indoor run on a treadmill
+    pub fn from_distances(
+        times: Vec<f64>,
+        distances: Vec<f64>,
+        altitudes: Option<Vec<f64>>,
+    ) -> Result<Activity, errors::InputDataError> {
+        distance_data_checks(&distances, &times)?;
+        if let Some(altitudes) = &altitudes {
+            if altitudes.len() != distances.len() {
+                return Err(InputDataError::InconsistentLength);
+            }
+        }
+        Ok(Activity {
+            coordinates: vec![(f64::NAN, f64::NAN); times.len()],
+            times,
+            altitudes,
+            device_distances: Some(distances),
+            calories: None,
+            distance_model: math::DistanceModel::default(),
+            slope_distance: false,
+            smoothing: smoothing::Smoothing::default(),
+            elevation_smoothing: smoothing::Smoothing::default(),
+            gain_method: climb::GainMethod::default(),
+            fill: math::FillStrategy::default(),
+            moving_time: pauses::MovingTime::default(),
+            timer_pauses: vec![],
+            record_messages: vec![],
+            altitude_outliers: vec![],
+            segment_gap: timestamps::DEFAULT_SEGMENT_GAP,
+            cleaned_coordinates: OnceLock::new(),
+            cleaned_altitudes: OnceLock::new(),
+            smoothed_altitudes: OnceLock::new(),
+            distances: OnceLock::new(),
+            ascent_descent: OnceLock::new(),
+        })
+    }
+
+    pub fn from_fit_data(
+        fit_data: fit_reader::FitData,
+    ) -> Result<Activity, errors::InputDataError> {
+        let outliers =
+            climb::FIT_ALTITUDE_OUTLIER_FILTER.detect(&fit_data.altitudes, &fit_data.times);
+        let altitudes = outliers
+            .iter()
+            .zip(&fit_data.altitudes)
+            .map(|(outlier, altitude)| if *outlier { f64::NAN } else { *altitude })
+            .collect();
+        let mut activity = if fit_data.use_device_distances() {
+            Activity::from_distances(fit_data.times, fit_data.distances, Some(altitudes))?
+        } else {
+            Activity::new(fit_data.times, fit_data.coordinates, Some(altitudes))?
+        };
+        activity.calories = Some(fit_data.calories);
+        activity.timer_pauses = fit_data.timer_pauses;
+        activity.record_messages = fit_data.record_messages;
+        activity.altitude_outliers = (0..outliers.len()).filter(|i| outliers[*i]).collect();
+        Ok(activity)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_fit(path_to_fit: &str) -> Result<Activity, errors::InputDataError> {
+        Activity::from_fit_data(fit_reader::parse_fit(path_to_fit)?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_fit_bytes(bytes: &[u8]) -> Result<Activity, errors::InputDataError> {
+        Activity::from_fit_data(fit_reader::parse_fit_bytes(bytes)?)
+    }
+
+    // the cached data is derived with the options of the activity, it is computed again on the
+    // next access after an option changed
+    fn reset_caches(&mut self) {
+        self.cleaned_coordinates = OnceLock::new();
+        self.cleaned_altitudes = OnceLock::new();
+        self.smoothed_altitudes = OnceLock::new();
+        self.distances = OnceLock::new();
+        self.ascent_descent = OnceLock::new();
+    }
+
+    // the distance model is fixed for the activity, as the cumulative distances depend on it
+    pub fn with_distance_model(mut self, distance_model: math::DistanceModel) -> Activity {
+        self.distance_model = distance_model;
+        self.reset_caches();
+        self
+    }
+
+    pub fn distance_model(&self) -> math::DistanceModel {
+        self.distance_model
+    }
+
+    // like `FinderOptions::slope_distance`, has no effect on activities without altitudes or with
+    // device distances
+    pub fn with_slope_distance(mut self, slope_distance: bool) -> Activity {
+        self.slope_distance = slope_distance;
+        self.reset_caches();
+        self
+    }
+
+    pub fn slope_distance(&self) -> bool {
+        self.slope_distance
+    }
+
+    // like `FinderOptions::smoothing`, has no effect on activities with device distances. The
+    // coordinates of the activity are not smoothed.
+    pub fn with_smoothing(mut self, smoothing: smoothing::Smoothing) -> Activity {
+        self.smoothing = smoothing;
+        self.reset_caches();
+        self
+    }
+
+    pub fn smoothing(&self) -> smoothing::Smoothing {
+        self.smoothing
+    }
+
+    // like `FinderOptions::elevation_smoothing`, the altitudes of the activity are not smoothed
+    pub fn with_elevation_smoothing(
+        mut self,
+        elevation_smoothing: smoothing::Smoothing,
+    ) -> Activity {
+        self.elevation_smoothing = elevation_smoothing;
+        self.reset_caches();
+        self
+    }
+
+    pub fn elevation_smoothing(&self) -> smoothing::Smoothing {
+        self.elevation_smoothing
+    }
+
+    pub fn with_gain_method(mut self, gain_method: climb::GainMethod) -> Activity {
+        self.gain_method = gain_method;
+        self.reset_caches();
+        self
+    }
+
+    pub fn gain_method(&self) -> climb::GainMethod {
+        self.gain_method
+    }
+
+    // like `FinderOptions::fill`
+    pub fn with_fill(mut self, fill: math::FillStrategy) -> Activity {
+        self.fill = fill;
+        self.reset_caches();
+        self
+    }
+
+    pub fn fill(&self) -> math::FillStrategy {
+        self.fill
+    }
+
+    // like `FinderOptions::moving_time`, only applies to the fastest sections
+    pub fn with_moving_time(mut self, moving_time: pauses::MovingTime) -> Activity {
+        self.moving_time = moving_time;
+        self
+    }
+
+    pub fn moving_time(&self) -> pauses::MovingTime {
+        self.moving_time
+    }
+
+    // periods in which the timer of the device was stopped, only known for fit files
+    pub fn timer_pauses(&self) -> &[(f64, f64)] {
+        &self.timer_pauses
+    }
+
+    // like `FinderOptions::segment_gap`
+    pub fn with_segment_gap(mut self, segment_gap: f64) -> Activity {
+        self.segment_gap = segment_gap;
+        self
+    }
+
+    pub fn segment_gap(&self) -> f64 {
+        self.segment_gap
+    }
+
+    pub fn time_issues(&self) -> timestamps::TimeIssues {
+        timestamps::check_times(&self.times, self.segment_gap)
+    }
+
+    // ranges of the records no section spans across
+    pub fn segments(&self) -> Vec<std::ops::Range<usize>> {
+        timestamps::split_segments(&self.times, self.segment_gap)
+    }
+
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    pub fn calories(&self) -> Option<u16> {
+        self.calories
+    }
+
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    // coordinates as given including missing values, None for activities with device distances
+    pub fn raw_coordinates(&self) -> Option<&[(f64, f64)]> {
+        match self.device_distances {
+            Some(_) => None,
+            None => Some(&self.coordinates),
+        }
+    }
+
+    // altitudes as given including missing values, spikes in fit files are already missing
+    pub fn raw_altitudes(&self) -> Option<&[f64]> {
+        self.altitudes.as_deref()
+    }
+
+    // indexes of the altitude spikes removed from fit files, see `raw_altitudes`
+    pub fn altitude_outliers(&self) -> &[usize] {
+        &self.altitude_outliers
+    }
+
+    // whether the row at the index was read from a record message, all rows of activities not
+    // read from fit files are records
+    pub fn is_record(&self, index: usize) -> bool {
+        self.record_messages.get(index).copied().unwrap_or(true)
+    }
+
+    // cumulative distances recorded by the device as given including missing values
+    pub fn device_distances(&self) -> Option<&[f64]> {
+        self.device_distances.as_deref()
+    }
+
+    // coordinates with missing values filled by the fill strategy, gaps it does not fill stay NaN
+    pub fn coordinates(&self) -> &[(f64, f64)] {
+        self.cleaned_coordinates.get_or_init(|| {
+            let mut coordinates = self.coordinates.clone();
+            self.fill.fill(&mut coordinates, &self.times);
+            coordinates
+        })
+    }
+
+    // altitudes with missing values filled by the fill strategy, gaps it does not fill stay NaN
+    pub fn altitudes(&self) -> Option<&[f64]> {
+        self.cleaned_altitudes
+            .get_or_init(|| {
+                self.altitudes.clone().map(|mut altitudes| {
+                    self.fill.fill(&mut altitudes, &self.times);
+                    altitudes
+                })
+            })
+            .as_deref()
+    }
+
+    pub fn distances(&self) -> &[f64] {
+        self.distances.get_or_init(|| {
+            if let Some(distances) = &self.device_distances {
+                let mut distances = distances.clone();
+                fill_device_distances(&mut distances, &self.times, self.fill);
+                return distances;
+            }
+            let coordinates =
+                smoothing::smooth_coordinates(self.coordinates(), &self.times, self.smoothing);
+            let altitudes = if self.slope_distance {
+                self.altitudes()
+            } else {
+                None
+            };
+            match altitudes {
+                Some(altitudes) => {
+                    cumulative_slope_distances(&coordinates, altitudes, self.distance_model)
+                }
+                None => cumulative_distances(&coordinates, self.distance_model),
+            }
+        })
+    }
+
+    // altitudes the gained altitude is computed from
+    fn smoothed_altitudes(&self) -> Option<&[f64]> {
+        self.smoothed_altitudes
+            .get_or_init(|| {
+                self.altitudes().map(|altitudes| {
+                    smoothing::smooth_values(altitudes, &self.times, self.elevation_smoothing)
+                        .into_owned()
+                })
+            })
+            .as_deref()
+    }
+
+    // total ascent and descent of the whole activity
+    pub fn ascent_descent(&self) -> Option<(f64, f64)> {
+        *self.ascent_descent.get_or_init(|| {
+            self.smoothed_altitudes()
+                .filter(|altitudes| altitudes.iter().any(|altitude| !altitude.is_nan()))
+                .map(|altitudes| self.gain_method.ascent_descent(altitudes))
+        })
+    }
+
+    // total gained altitude of the whole activity
+    pub fn elevation_gain(&self) -> Option<f64> {
+        self.ascent_descent().map(|(ascent, _)| ascent)
+    }
+
+    // the search runs on a copy of the cached data, which is still a lot cheaper than recomputing
+    // the distances for every search
+    fn input_data(
+        &self,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> Result<InputData, errors::InputDataError> {
+        if desired_distance <= 0.0 {
+            return Err(InputDataError::InvalidDesiredDistance);
+        }
+        let mut input_data = InputData {
+            desired_distance,
+            coordinates: self.coordinates().to_vec(),
+            times: dtypes::Times {
+                values: self.times.clone(),
+            },
+            distances: dtypes::Distances {
+                values: self.distances().to_vec(),
+            },
+            altitudes: dtypes::Altitudes {
+                values: self
+                    .smoothed_altitudes()
+                    .map(|a| a.to_vec())
+                    .unwrap_or_default(),
+            },
+            tolerance: tolerance.unwrap_or(DEFAULT_TOLERANCE),
+            distance_model: self.distance_model,
+            slope_distance: self.slope_distance,
+            smoothing: self.smoothing,
+            elevation_smoothing: self.elevation_smoothing,
+            gain_method: self.gain_method,
+            fill: self.fill,
+            moving_time: self.moving_time,
+            timer_pauses: self.timer_pauses.clone(),
+            moving_times: dtypes::Times { values: vec![] },
+            segment_gap: self.segment_gap,
+            segment_breaks: vec![],
+        };
+        input_data.check_if_total_distance_suffice()?;
+        input_data.split_segments();
+        input_data.compute_moving_times();
+        Ok(input_data)
+    }
+
+    pub fn fastest_section(
+        &self,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> Result<dtypes::TargetSection, errors::InputDataError> {
+        self.input_data(desired_distance, tolerance)?
+            .search_section(velocity::update_sections_max_velocity)
+    }
+
+    pub fn best_climb_section(
+        &self,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> Result<dtypes::TargetSection, errors::InputDataError> {
+        let mut input_data = self.input_data(desired_distance, tolerance)?;
+        // the data check needs to run on the raw altitudes, filled in values would hide missing data
+        input_data.altitudes.values = self.altitudes.clone().unwrap_or_default();
+        climb::specific_data_check(&input_data)?;
+        input_data.altitudes.values = self.smoothed_altitudes().unwrap_or_default().to_vec();
+        input_data.search_section(climb::update_sections_max_climb)
+    }
+
+    // fastest sections for all given distances, searched in parallel
+    pub fn best_efforts(
+        &self,
+        desired_distances: &[f64],
+        tolerance: Option<f64>,
+    ) -> Vec<Result<dtypes::TargetSection, errors::InputDataError>> {
+        desired_distances
+            .par_iter()
+            .map(|d| self.fastest_section(*d, tolerance))
+            .collect()
+    }
+
+    pub fn summary(&self) -> ActivitySummary {
+        let valid_times: Vec<f64> = self
+            .times
+            .iter()
+            .copied()
+            .filter(|t| t.is_normal())
+            .collect();
+        let start_time = valid_times.first().copied().unwrap_or(f64::NAN);
+        let end_time = valid_times.last().copied().unwrap_or(f64::NAN);
+        let duration = end_time - start_time;
+        let distance = *self.distances().last().unwrap();
+        ActivitySummary {
+            records: self.len(),
+            start_time,
+            end_time,
+            duration,
+            distance,
+            average_velocity: math::velocity_equation(&distance, &duration),
+            elevation_gain: self.elevation_gain(),
+            elevation_loss: self.ascent_descent().map(|(_, descent)| descent),
+            calories: self.calories,
+        }
+    }
+}
+
 fn generic_data_checks(
-    coordinates: &Vec<(f64, f64)>,
-    times: &Vec<f64>,
+    coordinates: &[(f64, f64)],
+    times: &[f64],
 ) -> Result<(), errors::InputDataError> {
     if coordinates.len() != times.len() {
         return Err(errors::InputDataError::InconsistentLength);
     }
-    let mut coordinates_normal = coordinates.clone();
-    let mut times_normal = times.clone();
-    coordinates_normal.retain(|&i| (i.0.is_normal() && i.1.is_normal()));
+    let mut coordinates_normal = coordinates.to_vec();
+    let mut times_normal = times.to_vec();
+    coordinates_normal.retain(|&i| i.0.is_normal() && i.1.is_normal());
     times_normal.retain(|&i| i.is_normal());
     if coordinates_normal.len() < 2 || times_normal.len() < 2 {
-        return Err(errors::InputDataError::TooFewDataPoints);
+        Err(errors::InputDataError::TooFewDataPoints)
     } else {
-        return Ok(());
+        Ok(())
+    }
+}
+
+// like `generic_data_checks`, a distance of zero is valid though
+fn distance_data_checks(distances: &[f64], times: &[f64]) -> Result<(), errors::InputDataError> {
+    if distances.len() != times.len() {
+        return Err(errors::InputDataError::InconsistentLength);
+    }
+    let distances_valid = distances.iter().filter(|i| i.is_finite()).count();
+    let times_normal = times.iter().filter(|i| i.is_normal()).count();
+    if distances_valid < 2 || times_normal < 2 {
+        Err(errors::InputDataError::TooFewDataPoints)
+    } else {
+        Ok(())
     }
 }
 
@@ -179,6 +979,24 @@ mod test_gem_finder {
         assert_eq!(finder, Err(errors::InputDataError::InvalidDesiredDistance));
     }
 
+    #[test]
+    fn test_boundary_time() {
+        let mut finder = InputData::new(
+            10_000.,
+            vec![(48.0, 8.0); 5],
+            vec![f64::NAN, 10., 11., 11., 12.],
+            None,
+            Some(0.01),
+        )
+        .unwrap();
+        finder.split_segments();
+        assert!(finder.times.values[3].is_nan());
+        assert_eq!(finder.boundary_time(3), 11.);
+        assert_eq!(finder.boundary_time(4), 12.);
+        // leading records without a time take the first valid time
+        assert_eq!(finder.boundary_time(0), 10.);
+    }
+
     #[test]
     fn test_compute_vector_of_distances() {
         let mut finder = InputData::new(
@@ -193,12 +1011,100 @@ mod test_gem_finder {
         finder.compute_vector_of_distances();
         assert_eq!(finder.distances.values, vec!(0.0, 7448.684105664539));
     }
+
+    #[test]
+    fn test_compute_vector_of_slope_distances() {
+        let options = FinderOptions {
+            slope_distance: true,
+            ..Default::default()
+        };
+        let mut finder = InputData::with_options(
+            10_000.,
+            vec![(48.0, 8.0), (48.0, 8.1), (48.0, 8.1)],
+            vec![123.4, 124.6, 125.8],
+            Some(vec![500., 1_500., f64::NAN]),
+            &options,
+        )
+        .unwrap();
+
+        finder.compute_vector_of_distances();
+        let slope_distance = 7448.684105664539_f64.hypot(1_000.);
+        assert_eq!(
+            finder.distances.values,
+            vec!(0.0, slope_distance, slope_distance)
+        );
+
+        // without altitudes the slope distance falls back to the distance on the map
+        let mut finder = InputData::with_options(
+            10_000.,
+            vec![(48.0, 8.0), (48.0, 8.1)],
+            vec![123.4, 124.6],
+            None,
+            &options,
+        )
+        .unwrap();
+        finder.compute_vector_of_distances();
+        assert_eq!(finder.distances.values, vec!(0.0, 7448.684105664539));
+    }
+
+    #[test]
+    fn test_compute_vector_of_smoothed_distances() {
+        // the detour of the middle coordinate is mostly smoothed away
+        let coordinates = vec![(48.0, 8.0), (48.001, 8.001), (48.0, 8.002)];
+        let options = FinderOptions {
+            smoothing: smoothing::Smoothing::MovingAverage { window: 3 },
+            ..Default::default()
+        };
+        let mut finder =
+            InputData::with_options(100., coordinates.clone(), vec![0., 1., 2.], None, &options)
+                .unwrap();
+        finder.compute_vector_of_distances();
+        let raw = cumulative_distances(&coordinates, Default::default());
+        assert!(finder.distances.values[2] < 0.6 * raw[2]);
+        // the recorded coordinates are kept
+        assert_eq!(finder.coordinates, coordinates);
+    }
+
+    #[test]
+    fn test_cumulative_distances_skip_missing_coordinates() {
+        let coordinates = vec![
+            (48.0, 8.0),
+            (48.0, 8.001),
+            (f64::NAN, f64::NAN),
+            (48.0, 8.1),
+        ];
+        let distances = cumulative_distances(&coordinates, Default::default());
+        assert_eq!(distances[1], distances[2]);
+        assert_eq!(distances[2], distances[3]);
+        let altitudes = vec![100., f64::NAN, 110., 120.];
+        let distances = cumulative_slope_distances(&coordinates, &altitudes, Default::default());
+        assert_eq!(distances[1], distances[3]);
+        assert!(distances[1] > 70. && distances[1] < 80.);
+    }
 }
 
 #[cfg(test)]
 mod test_checks {
     use super::*;
 
+    #[test]
+    fn test_distance_data_checks() {
+        assert_eq!(distance_data_checks(&[0., 10.], &[1., 2.]), Ok(()));
+        assert_eq!(
+            distance_data_checks(&[0., 10.], &[1.]),
+            Err(errors::InputDataError::InconsistentLength)
+        );
+        assert_eq!(
+            distance_data_checks(&[f64::NAN, 10.], &[1., 2.]),
+            Err(errors::InputDataError::TooFewDataPoints)
+        );
+        assert_eq!(
+            InputData::with_distances(0., vec![0., 10.], vec![1., 2.], None, &Default::default())
+                .err(),
+            Some(errors::InputDataError::InvalidDesiredDistance)
+        );
+    }
+
     #[test]
     fn test_generic_data_checks_too_few_data_points() {
         // generate data with only one data point and assert that the TooFewDataPoints error is returned
@@ -275,3 +1181,337 @@ mod test_checks {
         );
     }
 }
+
+#[cfg(test)]
+mod test_activity {
+    use super::*;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+
+    #[test]
+    fn test_activity_results_match_functions() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        for distance in [1_000., 2_000., 3_000.] {
+            assert_eq!(
+                activity.fastest_section(distance, None),
+                velocity::find_fastest_section_in_fit(distance, FIT_FILE, None)
+            );
+            assert_eq!(
+                activity.best_climb_section(distance, None),
+                climb::find_best_climb_section_in_fit(distance, FIT_FILE, None)
+            );
+        }
+        assert_eq!(
+            activity.fastest_section(100_000., None),
+            Err(InputDataError::DistanceTooSmall)
+        );
+        assert_eq!(
+            activity.best_climb_section(0., None),
+            Err(InputDataError::InvalidDesiredDistance)
+        );
+    }
+
+    #[test]
+    fn test_activity_caches_derived_data() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        let distances = activity.distances().as_ptr();
+        activity.fastest_section(1_000., None).unwrap();
+        assert_eq!(activity.distances().as_ptr(), distances);
+        assert_eq!(activity.distances().len(), activity.len());
+        assert!(activity.coordinates().iter().all(|(lat, _)| !lat.is_nan()));
+    }
+
+    #[test]
+    fn test_activity_distance_model() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        let spherical = *activity.distances().last().unwrap();
+        let activity = activity.with_distance_model(math::DistanceModel::Geodesic);
+        assert_eq!(activity.distance_model(), math::DistanceModel::Geodesic);
+        let geodesic = *activity.distances().last().unwrap();
+        assert_ne!(spherical, geodesic);
+        assert!((spherical - geodesic).abs() / geodesic < 0.01);
+        let options = FinderOptions {
+            distance_model: math::DistanceModel::Geodesic,
+            ..Default::default()
+        };
+        assert_eq!(
+            activity.fastest_section(1_000., None),
+            velocity::find_fastest_section_in_fit_data_with_options(
+                1_000.,
+                &fit_reader::parse_fit(FIT_FILE).unwrap(),
+                &options
+            )
+        );
+    }
+
+    #[test]
+    fn test_activity_slope_distance() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        let distance = *activity.distances().last().unwrap();
+        let activity = activity.with_slope_distance(true);
+        assert!(activity.slope_distance());
+        assert!(*activity.distances().last().unwrap() > distance);
+        let options = FinderOptions {
+            slope_distance: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            activity.best_climb_section(1_000., None),
+            climb::find_best_climb_section_in_fit_data_with_options(
+                1_000.,
+                &fit_reader::parse_fit(FIT_FILE).unwrap(),
+                &options
+            )
+        );
+    }
+
+    #[test]
+    fn test_activity_smoothing() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        let distance = *activity.distances().last().unwrap();
+        let smoothing = smoothing::Smoothing::Kalman {
+            process_noise: 1.0,
+            measurement_noise: 5.0,
+        };
+        let activity = activity.with_smoothing(smoothing);
+        assert_eq!(activity.smoothing(), smoothing);
+        assert!(*activity.distances().last().unwrap() < distance);
+        let options = FinderOptions {
+            smoothing,
+            ..Default::default()
+        };
+        assert_eq!(
+            activity.fastest_section(1_000., None),
+            velocity::find_fastest_section_in_fit_data_with_options(
+                1_000.,
+                &fit_reader::parse_fit(FIT_FILE).unwrap(),
+                &options
+            )
+        );
+    }
+
+    #[test]
+    fn test_activity_gain_method() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        let (ascent, descent) = activity.ascent_descent().unwrap();
+        assert_eq!(activity.summary().elevation_loss, Some(descent));
+        let elevation_smoothing = smoothing::Smoothing::MovingAverage { window: 5 };
+        let gain_method = climb::GainMethod::Hysteresis { threshold: 2. };
+        let activity = activity
+            .with_elevation_smoothing(elevation_smoothing)
+            .with_gain_method(gain_method);
+        assert_eq!(activity.gain_method(), gain_method);
+        assert_eq!(activity.elevation_smoothing(), elevation_smoothing);
+        let (smoothed_ascent, smoothed_descent) = activity.ascent_descent().unwrap();
+        assert!(smoothed_ascent < ascent && smoothed_descent < descent);
+        assert_eq!(activity.elevation_gain(), Some(smoothed_ascent));
+        let options = FinderOptions {
+            elevation_smoothing,
+            gain_method,
+            ..Default::default()
+        };
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        assert_eq!(
+            activity.best_climb_section(1_000., None),
+            climb::find_best_climb_section_in_fit_data_with_options(1_000., &fit_data, &options)
+        );
+        assert_eq!(
+            activity.fastest_section(1_000., None),
+            velocity::find_fastest_section_in_fit_data_with_options(1_000., &fit_data, &options)
+        );
+    }
+
+    #[test]
+    fn test_activity_fill() {
+        let fill = math::FillStrategy::Linear { max_gap: 30. };
+        let activity = Activity::from_fit(FIT_FILE).unwrap().with_fill(fill);
+        assert_eq!(activity.fill(), fill);
+        // the gaps in the recorded data are filled, the messages before the first and after the
+        // last record stay missing
+        let coordinates = activity.coordinates();
+        let first = coordinates
+            .iter()
+            .position(|c| !math::IsNaN::nan(c))
+            .unwrap();
+        let last = coordinates
+            .iter()
+            .rposition(|c| !math::IsNaN::nan(c))
+            .unwrap();
+        assert!(coordinates[first..=last]
+            .iter()
+            .all(|c| !math::IsNaN::nan(c)));
+        assert!(math::IsNaN::nan(&coordinates[0]) && math::IsNaN::nan(&coordinates[last + 1]));
+        let altitudes = activity.altitudes().unwrap();
+        assert!(altitudes[first..=last].iter().all(|a| !a.is_nan()));
+        let options = FinderOptions {
+            fill,
+            ..Default::default()
+        };
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        assert_eq!(
+            activity.best_climb_section(1_000., None),
+            climb::find_best_climb_section_in_fit_data_with_options(1_000., &fit_data, &options)
+        );
+        assert_eq!(
+            activity.fastest_section(1_000., None),
+            velocity::find_fastest_section_in_fit_data_with_options(1_000., &fit_data, &options)
+        );
+    }
+
+    #[test]
+    fn test_activity_moving_time() {
+        let mut fit_data = crate::synthetic::generate_activity(1_200);
+        let start = crate::synthetic::START_TIME;
+        fit_data.timer_pauses = vec![(start + 100., start + 200.)];
+        let activity = Activity::from_fit_data(fit_data.clone()).unwrap();
+        assert_eq!(activity.timer_pauses(), fit_data.timer_pauses);
+        let elapsed = activity.fastest_section(2_000., None).unwrap();
+        let activity = activity.with_moving_time(pauses::MovingTime::Timer);
+        assert_eq!(activity.moving_time(), pauses::MovingTime::Timer);
+        let timer = activity.fastest_section(2_000., None).unwrap();
+        // the section spans the pause, which costs no time
+        assert!(timer.start_time < start + 200.);
+        assert!(timer.target_value > elapsed.target_value);
+        let options = FinderOptions {
+            moving_time: pauses::MovingTime::Timer,
+            ..Default::default()
+        };
+        assert_eq!(
+            Ok(timer),
+            velocity::find_fastest_section_in_fit_data_with_options(2_000., &fit_data, &options)
+        );
+    }
+
+    #[test]
+    fn test_activity_segments() {
+        let mut fit_data = crate::synthetic::generate_activity(1_200);
+        // the device is switched off for 10 minutes after 10 minutes
+        for time in &mut fit_data.times[600..] {
+            *time += 600.;
+        }
+        let activity = Activity::from_fit_data(fit_data.clone()).unwrap();
+        assert_eq!(activity.segments(), vec![0..600, 600..1_200]);
+        assert_eq!(activity.time_issues().gaps, vec![600]);
+        assert_eq!(
+            activity.fastest_section(1_000., None).unwrap().end_time,
+            velocity::find_fastest_section_in_fit_data(1_000., &fit_data, None)
+                .unwrap()
+                .end_time
+        );
+        // each segment is about 1.8 km long
+        assert_eq!(
+            activity.fastest_section(3_000., Some(0.1)),
+            Err(InputDataError::NoSectionFound)
+        );
+        let activity = activity.with_segment_gap(f64::INFINITY);
+        assert_eq!(activity.segments(), vec![0..1_200]);
+        assert!(activity.fastest_section(3_000., Some(0.1)).is_ok());
+    }
+
+    #[test]
+    fn test_activity_from_distances() {
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        let activity = Activity::from_distances(
+            fit_data.times.clone(),
+            fit_data.distances.clone(),
+            Some(fit_data.altitudes.clone()),
+        )
+        .unwrap();
+        assert_eq!(activity.distances()[0], 1.6);
+        assert_eq!(*activity.distances().last().unwrap(), 5839.77);
+        // coordinates of the sections are unknown (NaN), so the sections cannot be compared as a whole
+        let section = activity.fastest_section(1_000., None).unwrap();
+        let expected = velocity::find_fastest_section_with_distances(
+            1_000.,
+            fit_data.distances.clone(),
+            fit_data.times.clone(),
+            Some(fit_data.altitudes.clone()),
+            &FinderOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            (section.start, section.end, section.target_value),
+            (expected.start, expected.end, expected.target_value)
+        );
+        assert!(section.start_coordinate.0.is_nan());
+        assert!(activity.summary().distance > 5_800.);
+
+        // fit files without coordinates are read as activity on the recorded distances
+        let indoor = fit_reader::FitData {
+            coordinates: vec![(f64::NAN, f64::NAN); fit_data.times.len()],
+            ..fit_data.clone()
+        };
+        let activity = Activity::from_fit_data(indoor).unwrap();
+        assert_eq!(*activity.distances().last().unwrap(), 5839.77);
+        assert!(activity.best_climb_section(1_000., None).is_ok());
+        assert_eq!(
+            Activity::from_distances(vec![1., 2.], vec![0.], None).err(),
+            Some(InputDataError::InconsistentLength)
+        );
+    }
+
+    #[test]
+    fn test_activity_best_efforts() {
+        let activity = Activity::from_fit(FIT_FILE).unwrap();
+        let efforts = activity.best_efforts(&[1_000., 2_000., 100_000.], None);
+        assert_eq!(efforts[0], activity.fastest_section(1_000., None));
+        assert_eq!(efforts[1], activity.fastest_section(2_000., None));
+        assert_eq!(efforts[2], Err(InputDataError::DistanceTooSmall));
+    }
+
+    #[test]
+    fn test_activity_summary() {
+        let activity = Activity::from_fit_bytes(&std::fs::read(FIT_FILE).unwrap()).unwrap();
+        let summary = activity.summary();
+        assert_eq!(summary.records, 1231);
+        assert_eq!(summary.calories, Some(432));
+        assert_eq!(summary.duration, summary.end_time - summary.start_time);
+        assert_eq!(summary.distance, *activity.distances().last().unwrap());
+        assert!(summary.distance > 5_000. && summary.distance < 6_000.);
+        assert_eq!(
+            summary.average_velocity,
+            summary.distance / summary.duration
+        );
+        assert!(summary.elevation_gain.unwrap() > 0.);
+    }
+
+    #[test]
+    fn test_activity_from_arrays() {
+        let activity = Activity::new(
+            vec![1608228953.8, 1608228954.8, 1608228955.8, 1608228956.8],
+            vec![
+                (48.123, 9.35),
+                (48.123, 9.36),
+                (48.123, 9.37),
+                (48.123, 9.38),
+            ],
+            None,
+        )
+        .unwrap();
+        let result = activity.fastest_section(1_000., Some(0.5)).unwrap();
+        assert_eq!((result.start, result.end), (0, 3));
+        assert_eq!(activity.elevation_gain(), None);
+        assert_eq!(activity.summary().calories, None);
+        assert_eq!(
+            activity.best_climb_section(1_000., Some(0.5)),
+            Err(InputDataError::InconsistentLength)
+        );
+    }
+
+    #[test]
+    fn test_activity_invalid_input() {
+        assert_eq!(
+            Activity::new(vec![1., 2.], vec![(48.0, 8.0)], None).unwrap_err(),
+            InputDataError::InconsistentLength
+        );
+        assert_eq!(
+            Activity::new(vec![1., 2.], vec![(48.0, 8.0), (48.0, 8.1)], Some(vec![1.]))
+                .unwrap_err(),
+            InputDataError::InconsistentLength
+        );
+        assert_eq!(
+            Activity::from_fit_bytes(b"no fit file").unwrap_err(),
+            InputDataError::InvalidFitFile
+        );
+    }
+}
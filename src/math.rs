@@ -24,6 +24,151 @@ pub fn calculate_distance(coordinate1: dtypes::Coordinate, coordinate2: dtypes::
     }
 }
 
+// mean earth radius (IUGG) in meter, used by the haversine and equirectangular models
+pub const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+// semi-major axis in meter and flattening of the WGS84 ellipsoid
+pub const WGS84_A: f64 = 6_378_137.0;
+pub const WGS84_F: f64 = 1. / 298.257_223_563;
+
+// model used to compute the distance between two consecutive coordinates
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DistanceModel {
+    // spherical law of cosines, which loses precision for steps below a few meters
+    #[default]
+    Spherical,
+    Haversine,
+    // flat earth approximation, fastest and accurate enough for the short steps of 1 Hz recordings
+    Equirectangular,
+    // vincenty's inverse formula on the WGS84 ellipsoid
+    Geodesic,
+}
+
+impl DistanceModel {
+    pub const NAMES: [&'static str; 4] = ["spherical", "haversine", "equirectangular", "geodesic"];
+
+    pub fn name(self) -> &'static str {
+        DistanceModel::NAMES[self as usize]
+    }
+
+    pub fn distance(self, coordinate1: (f64, f64), coordinate2: (f64, f64)) -> f64 {
+        match self {
+            DistanceModel::Spherical => calculate_distance(
+                dtypes::Coordinate {
+                    lat: coordinate1.0,
+                    lon: coordinate1.1,
+                },
+                dtypes::Coordinate {
+                    lat: coordinate2.0,
+                    lon: coordinate2.1,
+                },
+            ),
+            DistanceModel::Haversine => haversine_distance(coordinate1, coordinate2),
+            DistanceModel::Equirectangular => equirectangular_distance(coordinate1, coordinate2),
+            DistanceModel::Geodesic => geodesic_distance(coordinate1, coordinate2),
+        }
+    }
+}
+
+impl std::str::FromStr for DistanceModel {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<DistanceModel, String> {
+        match name {
+            "spherical" => Ok(DistanceModel::Spherical),
+            "haversine" => Ok(DistanceModel::Haversine),
+            "equirectangular" => Ok(DistanceModel::Equirectangular),
+            "geodesic" => Ok(DistanceModel::Geodesic),
+            _ => Err(format!(
+                "Unknown distance model '{}', expected one of: {}.",
+                name,
+                DistanceModel::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+pub fn haversine_distance(coordinate1: (f64, f64), coordinate2: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (to_rad(coordinate1.0), to_rad(coordinate2.0));
+    let delta_lat = lat2 - lat1;
+    let delta_lon = to_rad(coordinate2.1 - coordinate1.1);
+    let a =
+        (delta_lat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (delta_lon / 2.).sin().powi(2);
+    2. * MEAN_EARTH_RADIUS * a.sqrt().min(1.).asin()
+}
+
+pub fn equirectangular_distance(coordinate1: (f64, f64), coordinate2: (f64, f64)) -> f64 {
+    let mean_lat = to_rad((coordinate1.0 + coordinate2.0) / 2.);
+    let x = to_rad(coordinate2.1 - coordinate1.1) * mean_lat.cos();
+    let y = to_rad(coordinate2.0 - coordinate1.0);
+    MEAN_EARTH_RADIUS * x.hypot(y)
+}
+
+pub fn geodesic_distance(coordinate1: (f64, f64), coordinate2: (f64, f64)) -> f64 {
+    let b = (1. - WGS84_F) * WGS84_A;
+    let l = to_rad(coordinate2.1 - coordinate1.1);
+    let u1 = ((1. - WGS84_F) * to_rad(coordinate1.0).tan()).atan();
+    let u2 = ((1. - WGS84_F) * to_rad(coordinate2.0).tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+        if sin_sigma == 0. {
+            // coincident points
+            return 0.;
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos_sq_alpha = 1. - sin_alpha * sin_alpha;
+        // cos_sq_alpha is 0 for lines along the equator
+        let cos_2sigma_m = if cos_sq_alpha != 0. {
+            cos_sigma - 2. * sin_u1 * sin_u2 / cos_sq_alpha
+        } else {
+            0.
+        };
+        let c = WGS84_F / 16. * cos_sq_alpha * (4. + WGS84_F * (4. - 3. * cos_sq_alpha));
+        let previous_lambda = lambda;
+        lambda = l
+            + (1. - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1. + 2. * cos_2sigma_m.powi(2))));
+        if (lambda - previous_lambda).abs() < 1e-12 {
+            let u_sq = cos_sq_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+            let big_a = 1. + u_sq / 16384. * (4096. + u_sq * (-768. + u_sq * (320. - 175. * u_sq)));
+            let big_b = u_sq / 1024. * (256. + u_sq * (-128. + u_sq * (74. - 47. * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + big_b / 4.
+                        * (cos_sigma * (-1. + 2. * cos_2sigma_m.powi(2))
+                            - big_b / 6.
+                                * cos_2sigma_m
+                                * (-3. + 4. * sin_sigma.powi(2))
+                                * (-3. + 4. * cos_2sigma_m.powi(2))));
+            return b * big_a * (sigma - delta_sigma);
+        }
+    }
+    // vincenty's formula does not converge for nearly antipodal points, which do not occur
+    // between consecutive records of an activity
+    haversine_distance(coordinate1, coordinate2)
+}
+
+// distance along the slope, a missing altitude on either end counts as flat
+pub fn slope_distance(horizontal_distance: f64, altitude1: f64, altitude2: f64) -> f64 {
+    let altitude_change = altitude2 - altitude1;
+    if altitude_change.is_nan() {
+        horizontal_distance
+    } else {
+        horizontal_distance.hypot(altitude_change)
+    }
+}
+
 pub fn velocity_equation(distance: &f64, time: &f64) -> f64 {
     let velocity: f64 = distance / time;
     if !velocity.is_normal() {
@@ -43,29 +188,6 @@ pub fn climb_equation(gained_altitude: &f64, time: &f64) -> f64 {
     }
 }
 
-fn get_average(vec: &Vec<f64>) -> f64 {
-    let mut new_vec = vec.clone();
-    // drop NAN values
-    new_vec.retain(|&i| i.is_normal());
-    // compute average and return
-    new_vec.iter().sum::<f64>() as f64 / new_vec.len() as f64
-}
-
-pub fn remove_outliers(input_vector: &Vec<f64>, percentage_threshold: f64) -> Vec<f64> {
-    let avg: f64 = get_average(&input_vector);
-    let mut output_vector: Vec<f64> = vec![];
-    for element in input_vector {
-        if element > &(avg * (1. + percentage_threshold)) {
-            output_vector.push(f64::NAN);
-        } else if element < &(avg * (1. - percentage_threshold)) {
-            output_vector.push(f64::NAN);
-        } else {
-            output_vector.push(*element)
-        }
-    }
-    return output_vector;
-}
-
 pub trait IsNaN {
     fn nan(&self) -> bool;
 }
@@ -109,6 +231,127 @@ pub fn fill_nans<T: IsNaN + Copy>(vec: &mut [T]) {
     }
 }
 
+pub trait Lerp {
+    // value at `weight` between self (0) and other (1)
+    fn lerp(self, other: Self, weight: f64) -> Self;
+}
+
+impl Lerp for (f64, f64) {
+    fn lerp(self, other: Self, weight: f64) -> Self {
+        (self.0.lerp(other.0, weight), self.1.lerp(other.1, weight))
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(self, other: Self, weight: f64) -> Self {
+        self + (other - self) * weight
+    }
+}
+
+// interpolates missing values linearly in time between their neighbours, gaps longer than
+// `max_gap` seconds and missing values at the beginning or end stay missing. Where the times of
+// a gap are missing or the end is not after the start the records are assumed to be one second
+// apart. Times inside a gap which are out of order are clamped to the gap, so interpolated values
+// never leave the range of their neighbours.
+pub fn interpolate_nans<T: IsNaN + Lerp + Copy>(vec: &mut [T], times: &[f64], max_gap: f64) {
+    let mut last_valid: Option<usize> = None;
+    for i in 0..vec.len() {
+        if vec[i].nan() {
+            continue;
+        }
+        if let Some(start) = last_valid.filter(|start| i - start > 1) {
+            let (t_start, t_end) = (times[start], times[i]);
+            let timed = t_end > t_start && (start..=i).all(|j| !times[j].is_nan());
+            let gap = if timed {
+                t_end - t_start
+            } else {
+                (i - start) as f64
+            };
+            if gap <= max_gap {
+                for j in start + 1..i {
+                    let weight = if timed {
+                        ((times[j] - t_start) / gap).clamp(0., 1.)
+                    } else {
+                        (j - start) as f64 / gap
+                    };
+                    vec[j] = vec[start].lerp(vec[i], weight);
+                }
+            }
+        }
+        last_valid = Some(i);
+    }
+}
+
+pub const DEFAULT_MAX_GAP: f64 = 60.0;
+
+// strategy of the finders to fill missing coordinates, altitudes and device distances
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum FillStrategy {
+    // repeat the last valid value, see `fill_nans`
+    #[default]
+    Forward,
+    // interpolate gaps up to `max_gap` seconds, see `interpolate_nans`. Longer gaps and missing
+    // values at the beginning or end stay missing, the distances and the gained altitude skip them,
+    // so a long dropout adds no straight line to the distance.
+    Linear {
+        max_gap: f64,
+    },
+}
+
+impl FillStrategy {
+    pub const NAMES: [&'static str; 2] = ["forward", "linear"];
+
+    pub fn fill<T: IsNaN + Lerp + Copy>(self, vec: &mut [T], times: &[f64]) {
+        if vec.is_empty() {
+            return;
+        }
+        match self {
+            FillStrategy::Linear { max_gap } if times.len() == vec.len() => {
+                interpolate_nans(vec, times, max_gap)
+            }
+            _ => fill_nans(vec),
+        }
+    }
+}
+
+// `linear` takes the maximum gap in seconds as optional parameter, e.g. `linear:30`
+impl std::str::FromStr for FillStrategy {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<FillStrategy, String> {
+        let (name, param) = match spec.split_once(':') {
+            Some((name, param)) => (name, Some(param)),
+            None => (spec, None),
+        };
+        let invalid = || format!("Invalid parameters for fill strategy '{}'.", spec);
+        match (name, param) {
+            ("forward", None) => Ok(FillStrategy::Forward),
+            ("linear", None) => Ok(FillStrategy::Linear {
+                max_gap: DEFAULT_MAX_GAP,
+            }),
+            ("linear", Some(param)) => match param.parse::<f64>() {
+                Ok(max_gap) if max_gap > 0.0 => Ok(FillStrategy::Linear { max_gap }),
+                _ => Err(invalid()),
+            },
+            _ if FillStrategy::NAMES.contains(&name) => Err(invalid()),
+            _ => Err(format!(
+                "Unknown fill strategy '{}', expected one of: {}.",
+                spec,
+                FillStrategy::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for FillStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FillStrategy::Forward => write!(f, "forward"),
+            FillStrategy::Linear { max_gap } => write!(f, "linear:{}", max_gap),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_calculate_distance {
     use super::*;
@@ -189,6 +432,106 @@ mod test_calculate_distance {
     }
 }
 
+#[cfg(test)]
+mod test_distance_models {
+    use super::*;
+
+    fn assert_close(value: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (value - expected).abs() <= tolerance,
+            "{} != {} +- {}",
+            value,
+            expected,
+            tolerance
+        );
+    }
+
+    #[test]
+    fn test_geodesic_reference_values() {
+        // flinders peak to buninyong, reference of vincenty's paper and geographiclib
+        assert_close(
+            geodesic_distance(
+                (-37.951_033_416_666_67, 144.424_867_888_888_9),
+                (-37.652_821_138_888_89, 143.926_495_527_777_8),
+            ),
+            54_972.271,
+            0.001,
+        );
+        // one degree along the equator is exactly the semi-major axis times one degree in radian
+        assert_close(
+            geodesic_distance((0., 0.), (0., 1.)),
+            WGS84_A * PI / 180.,
+            1e-6,
+        );
+        // one degree of latitude starting at the equator
+        assert_close(geodesic_distance((0., 0.), (1., 0.)), 110_574.389, 0.001);
+        assert_eq!(geodesic_distance((48.0, 8.0), (48.0, 8.0)), 0.0);
+        assert_close(
+            geodesic_distance((48.0, 8.0), (48.1, 8.1)),
+            geodesic_distance((48.1, 8.1), (48.0, 8.0)),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_haversine_and_equirectangular() {
+        let one_degree = MEAN_EARTH_RADIUS * PI / 180.;
+        assert_close(haversine_distance((0., 0.), (0., 1.)), one_degree, 1e-6);
+        assert_close(haversine_distance((0., 0.), (1., 0.)), one_degree, 1e-6);
+        assert_close(
+            equirectangular_distance((0., 0.), (1., 0.)),
+            one_degree,
+            1e-6,
+        );
+        // the flat earth approximation is accurate for short distances
+        let (a, b) = ((49.4056, 8.7001), (49.4057, 8.7003));
+        assert_close(
+            equirectangular_distance(a, b),
+            haversine_distance(a, b),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn test_sub_meter_steps() {
+        // steps of about 0.5 m as recorded at 1 Hz while walking
+        let (a, b) = (
+            (49.09024318680168, 7.9677597898989925),
+            (49.09024318680168, 7.96776666),
+        );
+        let geodesic = geodesic_distance(a, b);
+        assert!(geodesic > 0.4 && geodesic < 0.6);
+        assert_close(haversine_distance(a, b), geodesic, 0.005);
+        assert_close(equirectangular_distance(a, b), geodesic, 0.005);
+        // the law of cosines already suffers from the limited precision of acos
+        assert!((DistanceModel::Spherical.distance(a, b) - geodesic).abs() > 0.005);
+    }
+
+    #[test]
+    fn test_distance_model_from_str() {
+        for name in DistanceModel::NAMES {
+            let model: DistanceModel = name.parse().unwrap();
+            assert_eq!(model.name(), name);
+            assert_eq!(model.distance((48.0, 8.0), (48.0, 8.0)), 0.0);
+        }
+        assert_eq!(
+            "geodesic".parse::<DistanceModel>(),
+            Ok(DistanceModel::Geodesic)
+        );
+        assert_eq!(DistanceModel::default(), DistanceModel::Spherical);
+        assert!("vincenty".parse::<DistanceModel>().is_err());
+    }
+
+    #[test]
+    fn test_slope_distance() {
+        assert_eq!(slope_distance(3., 100., 104.), 5.);
+        assert_eq!(slope_distance(3., 104., 100.), 5.);
+        assert_eq!(slope_distance(3., 100., 100.), 3.);
+        assert_eq!(slope_distance(3., f64::NAN, 100.), 3.);
+        assert_eq!(slope_distance(0., 100., 110.), 10.);
+    }
+}
+
 #[cfg(test)]
 mod test_optimized_target_value_formulas {
     use super::*;
@@ -218,61 +561,6 @@ mod test_optimized_target_value_formulas {
     }
 }
 
-#[cfg(test)]
-mod test_remove_outliers {
-    use super::*;
-
-    #[test]
-    fn test_get_average() {
-        assert_eq!(get_average(&vec![1., 2., 3.]), 2.);
-        assert_eq!(get_average(&vec![-10., 20., 20.]), 10.);
-    }
-
-    #[test]
-    fn test_get_average_with_nan() {
-        assert_eq!(get_average(&vec![1., 2., f64::NAN, 3.]), 2.);
-        assert_eq!(get_average(&vec![f64::NAN, -10., 20., 20.]), 10.);
-    }
-
-    // helper functions to compare two vectors containing f64::NAN to be equal
-    fn eq_with_nan_eq(a: f64, b: f64) -> bool {
-        (a.is_nan() && b.is_nan()) || (a == b)
-    }
-
-    fn vec_compare(va: &[f64], vb: &[f64]) -> bool {
-        (va.len() == vb.len()) &&  // zip stops at the shortest
-         va.iter()
-           .zip(vb)
-           .all(|(a,b)| eq_with_nan_eq(*a,*b))
-    }
-
-    #[test]
-    fn test_remove_outliers_low_threshold() {
-        let result_vec = remove_outliers(&vec![1., 1., 1., 1., 5., 1., 1., 1., 1., 1.], 0.50); // 50% threshold
-
-        // value of 5.0 is replace by NAN
-        let expected_vec = vec![1., 1., 1., 1., f64::NAN, 1., 1., 1., 1., 1.];
-        assert_eq!(vec_compare(&expected_vec, &result_vec), true)
-    }
-
-    #[test]
-    fn test_remove_outliers_threshold_too_large() {
-        let result_vec = remove_outliers(&vec![1., 1., 1., 1., 5., 1., 1., 1., 1., 1.], 3.00); // 300% threshold
-
-        // value of 5.0 is not replaced
-        let expected_vec = vec![1., 1., 1., 1., 5., 1., 1., 1., 1., 1.];
-        assert_eq!(vec_compare(&expected_vec, &result_vec), true)
-    }
-
-    #[test]
-    fn test_remove_outliers_with_nan() {
-        let result_vec = remove_outliers(&vec![1., f64::NAN, 1., 1., 5., 1., 1., 1., 1., 1.], 0.50);
-        // value of 5.0 is replaced and original NAN value is kept
-        let expected_vec = vec![1., f64::NAN, 1., 1., f64::NAN, 1., 1., 1., 1., 1.];
-        assert_eq!(vec_compare(&expected_vec, &result_vec), true)
-    }
-}
-
 #[cfg(test)]
 mod test_fill_nans_coordinates {
     use super::*;
@@ -432,3 +720,90 @@ mod test_fill_nans_altitudes {
         assert_eq!(expected_vec, my_vec);
     }
 }
+
+#[cfg(test)]
+mod test_interpolate_nans {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_nans_in_time() {
+        let times = vec![0., 1., 2., 4., 5., 6., 7.];
+        let mut my_vec = vec![f64::NAN, 1., f64::NAN, f64::NAN, 5., f64::NAN, 7.];
+        interpolate_nans(&mut my_vec, &times, 10.);
+        assert!(my_vec[0].is_nan());
+        assert_eq!(my_vec[1..], [1., 2., 4., 5., 6., 7.]);
+    }
+
+    #[test]
+    fn test_interpolate_nans_max_gap() {
+        // the gap between 1 and 5 takes 4 seconds and stays missing
+        let times = vec![0., 1., 2., 4., 5., 6., 7.];
+        let mut my_vec = vec![0., 1., f64::NAN, f64::NAN, 5., f64::NAN, 7.];
+        interpolate_nans(&mut my_vec, &times, 3.);
+        assert!(my_vec[2].is_nan() && my_vec[3].is_nan());
+        assert_eq!(my_vec[5], 6.);
+        // missing times count as one second per record
+        let times = vec![0., f64::NAN, 2., 3.];
+        let mut my_vec = vec![
+            (48., 8.),
+            (f64::NAN, f64::NAN),
+            (f64::NAN, f64::NAN),
+            (51., 11.),
+        ];
+        interpolate_nans(&mut my_vec, &times, 3.);
+        assert_eq!(my_vec, vec![(48., 8.), (49., 9.), (50., 10.), (51., 11.)]);
+    }
+
+    #[test]
+    fn test_interpolate_nans_out_of_order_times() {
+        // times inside the gap before its start or after its end are clamped to the gap
+        let times = vec![10., 8., 15., 12., 14.];
+        let mut my_vec = vec![0., f64::NAN, f64::NAN, f64::NAN, 4.];
+        interpolate_nans(&mut my_vec, &times, 10.);
+        assert_eq!(my_vec, vec![0., 0., 4., 2., 4.]);
+        // a gap ending before its start is interpolated per record
+        let times = vec![10., 11., 12., 5.];
+        let mut my_vec = vec![0., f64::NAN, f64::NAN, 3.];
+        interpolate_nans(&mut my_vec, &times, 10.);
+        assert_eq!(my_vec, vec![0., 1., 2., 3.]);
+    }
+
+    #[test]
+    fn test_fill_strategy() {
+        let times = vec![0., 1., 2., 3., 4., 5., 100., 101.];
+        let mut my_vec = vec![f64::NAN, 1., f64::NAN, 3., f64::NAN, 5., f64::NAN, 7.];
+        FillStrategy::Linear { max_gap: 10. }.fill(&mut my_vec, &times);
+        // the leading value and the gap of 96 seconds stay missing
+        assert!(my_vec[0].is_nan() && my_vec[6].is_nan());
+        assert_eq!(my_vec[1..6], [1., 2., 3., 4., 5.]);
+        assert_eq!(my_vec[7], 7.);
+        let mut my_vec = vec![f64::NAN, 1., f64::NAN, 3.];
+        FillStrategy::Forward.fill(&mut my_vec, &times[..4]);
+        assert_eq!(my_vec, vec![1., 1., 1., 3.]);
+        FillStrategy::Forward.fill(&mut Vec::<f64>::new(), &[]);
+    }
+
+    #[test]
+    fn test_fill_strategy_from_str() {
+        assert_eq!("forward".parse(), Ok(FillStrategy::Forward));
+        assert_eq!(
+            "linear".parse(),
+            Ok(FillStrategy::Linear {
+                max_gap: DEFAULT_MAX_GAP
+            })
+        );
+        assert_eq!(
+            "linear:30".parse(),
+            Ok(FillStrategy::Linear { max_gap: 30. })
+        );
+        for spec in ["forward", "linear:30", "linear:inf"] {
+            assert_eq!(spec.parse::<FillStrategy>().unwrap().to_string(), spec);
+        }
+        assert!("linear:0".parse::<FillStrategy>().is_err());
+        assert!("forward:3".parse::<FillStrategy>().is_err());
+        assert_eq!(
+            "spline".parse::<FillStrategy>(),
+            Err("Unknown fill strategy 'spline', expected one of: forward, linear.".to_string())
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use crate::math;
+
+pub const DEFAULT_MEDIAN_THRESHOLD: f64 = 3.5;
+pub const DEFAULT_HAMPEL_WINDOW: usize = 9;
+pub const DEFAULT_HAMPEL_THRESHOLD: f64 = 3.0;
+// lower bound of the deviation, the median absolute deviation of quantized or constant values
+// like the altitudes of a flat section is zero, which would turn every change into an outlier
+pub const DEFAULT_MIN_DEVIATION: f64 = 1.0;
+pub const DEFAULT_MAX_RATE: f64 = 10.0;
+// scales the median absolute deviation to the standard deviation of normal distributed values
+const MAD_SCALE: f64 = 1.4826;
+
+// detection of spikes in a series, e.g. altitudes, speeds or coordinates. All filters are
+// robust against the spikes themselves, unlike a comparison with the mean of the series.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum OutlierFilter {
+    #[default]
+    None,
+    // modified z-score of each value against the median of the whole series
+    Median {
+        threshold: f64,
+        min_deviation: f64,
+    },
+    // modified z-score of each value against the median of the surrounding `window` values
+    Hampel {
+        window: usize,
+        threshold: f64,
+        min_deviation: f64,
+    },
+    // values reached and left with more than `max_rate` units per second, while the neighbours
+    // are within the rate, e.g. m/s for altitudes and coordinates
+    RateOfChange {
+        max_rate: f64,
+    },
+}
+
+impl OutlierFilter {
+    pub const NAMES: [&'static str; 4] = ["none", "median", "hampel", "rate"];
+
+    // true for every outlier, missing values are never outliers
+    pub fn detect(&self, values: &[f64], times: &[f64]) -> Vec<bool> {
+        match *self {
+            OutlierFilter::None => vec![false; values.len()],
+            OutlierFilter::Median {
+                threshold,
+                min_deviation,
+            } => median_outliers(values, threshold, min_deviation),
+            OutlierFilter::Hampel {
+                window,
+                threshold,
+                min_deviation,
+            } => hampel_outliers(values, window, threshold, min_deviation),
+            OutlierFilter::RateOfChange { max_rate } => rate_outliers(
+                values.len(),
+                times,
+                max_rate,
+                |a, b| (values[a] - values[b]).abs(),
+                |i| !values[i].is_nan(),
+            ),
+        }
+    }
+
+    // copy of the values with the outliers replaced by NaN, see `math::fill_nans`
+    pub fn apply(&self, values: &[f64], times: &[f64]) -> Vec<f64> {
+        self.detect(values, times)
+            .iter()
+            .zip(values)
+            .map(|(outlier, value)| if *outlier { f64::NAN } else { *value })
+            .collect()
+    }
+
+    // the coordinates are projected to meters around their median, the rate is the speed in m/s
+    pub fn detect_coordinates(&self, coordinates: &[(f64, f64)], times: &[f64]) -> Vec<bool> {
+        let valid: Vec<(f64, f64)> = coordinates
+            .iter()
+            .copied()
+            .filter(|coordinate| !math::IsNaN::nan(coordinate))
+            .collect();
+        let lats: Vec<f64> = valid.iter().map(|c| c.0).collect();
+        let lons: Vec<f64> = valid.iter().map(|c| c.1).collect();
+        let origin = match (median(&lats), median(&lons)) {
+            (Some(lat), Some(lon)) => (lat, lon),
+            _ => return vec![false; coordinates.len()],
+        };
+        let meters_per_degree = math::MEAN_EARTH_RADIUS * math::PI / 180.0;
+        let meters_per_degree_lon = meters_per_degree * math::to_rad(origin.0).cos();
+        let north: Vec<f64> = coordinates
+            .iter()
+            .map(|(lat, lon)| match lon.is_nan() {
+                true => f64::NAN,
+                false => (lat - origin.0) * meters_per_degree,
+            })
+            .collect();
+        let east: Vec<f64> = coordinates
+            .iter()
+            .map(|(lat, lon)| match lat.is_nan() {
+                true => f64::NAN,
+                false => (lon - origin.1) * meters_per_degree_lon,
+            })
+            .collect();
+        match *self {
+            OutlierFilter::RateOfChange { max_rate } => rate_outliers(
+                coordinates.len(),
+                times,
+                max_rate,
+                |a, b| (north[a] - north[b]).hypot(east[a] - east[b]),
+                |i| !north[i].is_nan(),
+            ),
+            _ => self
+                .detect(&north, times)
+                .iter()
+                .zip(self.detect(&east, times))
+                .map(|(north, east)| *north || east)
+                .collect(),
+        }
+    }
+
+    pub fn apply_coordinates(&self, coordinates: &[(f64, f64)], times: &[f64]) -> Vec<(f64, f64)> {
+        self.detect_coordinates(coordinates, times)
+            .iter()
+            .zip(coordinates)
+            .map(|(outlier, coordinate)| match outlier {
+                true => (f64::NAN, f64::NAN),
+                false => *coordinate,
+            })
+            .collect()
+    }
+}
+
+// filters are given by name with optional parameters separated by colons like the smoothing,
+// e.g. `median:3.5`, `hampel:9:3:1` or `rate:10`
+impl std::str::FromStr for OutlierFilter {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<OutlierFilter, String> {
+        let mut parts = spec.split(':');
+        let name = parts.next().unwrap_or_default();
+        let params: Vec<&str> = parts.collect();
+        let invalid = || format!("Invalid parameters for outlier filter '{}'.", spec);
+        let param = |i: usize, default: f64| -> Result<f64, String> {
+            match params.get(i) {
+                Some(param) => param.parse::<f64>().map_err(|_| invalid()),
+                None => Ok(default),
+            }
+        };
+        // windows are counts of values, fractions like `hampel:9.7` are rejected instead of being
+        // truncated
+        let window = |i: usize, default: usize| -> Result<usize, String> {
+            match params.get(i) {
+                Some(param) => param.parse::<usize>().map_err(|_| invalid()),
+                None => Ok(default),
+            }
+        };
+        let filter = match name {
+            "none" if params.is_empty() => OutlierFilter::None,
+            "median" if params.len() <= 2 => OutlierFilter::Median {
+                threshold: param(0, DEFAULT_MEDIAN_THRESHOLD)?,
+                min_deviation: param(1, DEFAULT_MIN_DEVIATION)?,
+            },
+            "hampel" if params.len() <= 3 => OutlierFilter::Hampel {
+                window: window(0, DEFAULT_HAMPEL_WINDOW)?,
+                threshold: param(1, DEFAULT_HAMPEL_THRESHOLD)?,
+                min_deviation: param(2, DEFAULT_MIN_DEVIATION)?,
+            },
+            "rate" if params.len() <= 1 => OutlierFilter::RateOfChange {
+                max_rate: param(0, DEFAULT_MAX_RATE)?,
+            },
+            _ if OutlierFilter::NAMES.contains(&name) => return Err(invalid()),
+            _ => {
+                return Err(format!(
+                    "Unknown outlier filter '{}', expected one of: {}.",
+                    spec,
+                    OutlierFilter::NAMES.join(", ")
+                ))
+            }
+        };
+        let valid = match filter {
+            OutlierFilter::None => true,
+            OutlierFilter::Median {
+                threshold,
+                min_deviation,
+            } => threshold > 0.0 && min_deviation >= 0.0,
+            OutlierFilter::Hampel {
+                window,
+                threshold,
+                min_deviation,
+            } => window % 2 == 1 && threshold > 0.0 && min_deviation >= 0.0,
+            OutlierFilter::RateOfChange { max_rate } => max_rate > 0.0,
+        };
+        if valid {
+            Ok(filter)
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+impl std::fmt::Display for OutlierFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            OutlierFilter::None => write!(f, "none"),
+            OutlierFilter::Median {
+                threshold,
+                min_deviation,
+            } => write!(f, "median:{}:{}", threshold, min_deviation),
+            OutlierFilter::Hampel {
+                window,
+                threshold,
+                min_deviation,
+            } => write!(f, "hampel:{}:{}:{}", window, threshold, min_deviation),
+            OutlierFilter::RateOfChange { max_rate } => write!(f, "rate:{}", max_rate),
+        }
+    }
+}
+
+// median of the valid values
+pub fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+// median and median absolute deviation of the valid values
+fn median_deviation(values: &[f64]) -> Option<(f64, f64)> {
+    let center = median(values)?;
+    let deviations: Vec<f64> = values.iter().map(|v| (v - center).abs()).collect();
+    Some((center, median(&deviations)?))
+}
+
+fn is_outlier(value: f64, center: f64, deviation: f64, threshold: f64, min_deviation: f64) -> bool {
+    (value - center).abs() > threshold * (MAD_SCALE * deviation).max(min_deviation)
+}
+
+pub fn median_outliers(values: &[f64], threshold: f64, min_deviation: f64) -> Vec<bool> {
+    match median_deviation(values) {
+        Some((center, deviation)) => values
+            .iter()
+            .map(|value| is_outlier(*value, center, deviation, threshold, min_deviation))
+            .collect(),
+        None => vec![false; values.len()],
+    }
+}
+
+// hampel filter over the `window` nearest valid values, the window is shifted at both ends to
+// keep its size and missing values are skipped, so spikes between gaps are still compared
+pub fn hampel_outliers(
+    values: &[f64],
+    window: usize,
+    threshold: f64,
+    min_deviation: f64,
+) -> Vec<bool> {
+    let indices: Vec<usize> = (0..values.len()).filter(|i| !values[*i].is_nan()).collect();
+    let valid: Vec<f64> = indices.iter().map(|i| values[*i]).collect();
+    let n = valid.len();
+    let window = window.min(n);
+    let half = window / 2;
+    let mut outliers = vec![false; values.len()];
+    for (k, &i) in indices.iter().enumerate() {
+        let start = k.saturating_sub(half).min(n - window);
+        if let Some((center, deviation)) = median_deviation(&valid[start..start + window]) {
+            outliers[i] = is_outlier(valid[k], center, deviation, threshold, min_deviation);
+        }
+    }
+    outliers
+}
+
+// a value is a spike if it is reached and left faster than `max_rate`, while its neighbours are
+// within the rate among each other, so steps in the series are kept. Missing and non increasing
+// times are not checked.
+fn rate_outliers(
+    n: usize,
+    times: &[f64],
+    max_rate: f64,
+    distance: impl Fn(usize, usize) -> f64,
+    valid: impl Fn(usize) -> bool,
+) -> Vec<bool> {
+    let indices: Vec<usize> = (0..n).filter(|i| valid(*i)).collect();
+    let too_fast = |a: usize, b: usize| {
+        let dt = (times[b] - times[a]).abs();
+        dt > 0.0 && distance(a, b) / dt > max_rate
+    };
+    let mut outliers = vec![false; n];
+    for (k, &i) in indices.iter().enumerate() {
+        let previous = k.checked_sub(1).map(|k| indices[k]);
+        let next = indices.get(k + 1).copied();
+        outliers[i] = match (previous, next) {
+            (Some(previous), Some(next)) => {
+                too_fast(previous, i) && too_fast(i, next) && !too_fast(previous, next)
+            }
+            // the first and last values are compared to the neighbour of their neighbour
+            (None, Some(next)) => match indices.get(k + 2) {
+                Some(&after) => too_fast(i, next) && !too_fast(next, after),
+                None => false,
+            },
+            (Some(previous), None) => match k.checked_sub(2).map(|k| indices[k]) {
+                Some(before) => too_fast(previous, i) && !too_fast(before, previous),
+                None => false,
+            },
+            (None, None) => false,
+        };
+    }
+    outliers
+}
+
+#[cfg(test)]
+mod test_outliers {
+    use super::*;
+
+    pub const FIT_FILE: &str = "tests/data/2019-09-14-17-22-05.fit";
+    const TIMES: [f64; 10] = [0., 1., 2., 3., 4., 5., 6., 7., 8., 9.];
+
+    fn outlier_indices(outliers: &[bool]) -> Vec<usize> {
+        (0..outliers.len()).filter(|i| outliers[*i]).collect()
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[3., 1., 2.]), Some(2.));
+        assert_eq!(median(&[4., f64::NAN, 1., 2., 3.]), Some(2.5));
+        assert_eq!(median(&[f64::NAN]), None);
+    }
+
+    #[test]
+    fn test_spikes_below_sea_level() {
+        // altitudes at the dead sea with a spike
+        let altitudes = [
+            -430., -431., -430., -429., -250., -430., -431., -430., -432., -431.,
+        ];
+        for filter in ["median", "hampel", "rate"] {
+            let filter: OutlierFilter = filter.parse().unwrap();
+            assert_eq!(
+                outlier_indices(&filter.detect(&altitudes, &TIMES)),
+                vec![4],
+                "{}",
+                filter
+            );
+            assert!(filter.apply(&altitudes, &TIMES)[4].is_nan());
+        }
+        assert!(OutlierFilter::None
+            .detect(&altitudes, &TIMES)
+            .iter()
+            .all(|o| !o));
+    }
+
+    #[test]
+    fn test_steps_and_nans_are_kept() {
+        // a step is no spike for the rate filter, the hampel filter only looks at its window
+        let altitudes = [
+            100.,
+            100.2,
+            f64::NAN,
+            100.4,
+            100.2,
+            150.,
+            150.2,
+            150.,
+            149.8,
+            150.,
+        ];
+        let filter = OutlierFilter::RateOfChange { max_rate: 10. };
+        assert!(filter.detect(&altitudes, &TIMES).iter().all(|o| !o));
+        let filter: OutlierFilter = "hampel:3".parse().unwrap();
+        assert!(filter.detect(&altitudes, &TIMES).iter().all(|o| !o));
+        // the median absolute deviation of constant values is zero
+        let constant = [250., 250., 250., 250.2, 250., 250.];
+        assert!(crate::climb::FIT_ALTITUDE_OUTLIER_FILTER
+            .detect(&constant, &TIMES[..6])
+            .iter()
+            .all(|o| !o));
+    }
+
+    #[test]
+    fn test_fit_altitude_spikes_between_missing_values() {
+        // the fit file holds two spikes of 6551.7 m surrounded by missing altitudes
+        let fit_data = crate::fit_reader::parse_fit(FIT_FILE).unwrap();
+        let altitudes =
+            crate::climb::FIT_ALTITUDE_OUTLIER_FILTER.apply(&fit_data.altitudes, &fit_data.times);
+        assert!(fit_data.altitudes.contains(&6551.7));
+        assert!(altitudes
+            .iter()
+            .all(|altitude| altitude.is_nan() || *altitude < 400.));
+    }
+
+    #[test]
+    fn test_rate_outliers_at_the_ends() {
+        let speeds = [30., 3., 3.1, 3.2, 3.1, 3.0, 2.9, 3., 3.1, 45.];
+        let filter = OutlierFilter::RateOfChange { max_rate: 5. };
+        assert_eq!(outlier_indices(&filter.detect(&speeds, &TIMES)), vec![0, 9]);
+        // without valid times nothing can be checked
+        assert!(filter.detect(&speeds, &[f64::NAN; 10]).iter().all(|o| !o));
+    }
+
+    #[test]
+    fn test_coordinate_outliers() {
+        // 3 m/s to the east with a jump of about 1 km to the north
+        let mut coordinates: Vec<(f64, f64)> =
+            (0..10).map(|i| (48.0, 8.0 + i as f64 * 4e-5)).collect();
+        coordinates[6].0 += 0.01;
+        coordinates[2] = (f64::NAN, f64::NAN);
+        for filter in ["median", "hampel", "rate:50"] {
+            let filter: OutlierFilter = filter.parse().unwrap();
+            assert_eq!(
+                outlier_indices(&filter.detect_coordinates(&coordinates, &TIMES)),
+                vec![6],
+                "{}",
+                filter
+            );
+            let filtered = filter.apply_coordinates(&coordinates, &TIMES);
+            assert!(filtered[6].0.is_nan() && filtered[2].0.is_nan());
+            assert_eq!(filtered[7], coordinates[7]);
+        }
+    }
+
+    #[test]
+    fn test_outlier_filter_from_str() {
+        assert_eq!("none".parse(), Ok(OutlierFilter::None));
+        assert_eq!(
+            "hampel:5:2".parse(),
+            Ok(OutlierFilter::Hampel {
+                window: 5,
+                threshold: 2.,
+                min_deviation: DEFAULT_MIN_DEVIATION
+            })
+        );
+        assert_eq!(
+            "rate".parse(),
+            Ok(OutlierFilter::RateOfChange {
+                max_rate: DEFAULT_MAX_RATE
+            })
+        );
+        for spec in ["none", "median:3.5:1", "hampel:9:3:5", "rate:20"] {
+            assert_eq!(spec.parse::<OutlierFilter>().unwrap().to_string(), spec);
+        }
+        assert!("hampel:4".parse::<OutlierFilter>().is_err());
+        assert!("hampel:9.7".parse::<OutlierFilter>().is_err());
+        assert!("hampel:-9".parse::<OutlierFilter>().is_err());
+        assert!("rate:0".parse::<OutlierFilter>().is_err());
+        assert!("median:1:2:3".parse::<OutlierFilter>().is_err());
+        assert_eq!(
+            "mean".parse::<OutlierFilter>(),
+            Err(
+                "Unknown outlier filter 'mean', expected one of: none, median, hampel, rate."
+                    .to_string()
+            )
+        );
+    }
+}
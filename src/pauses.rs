@@ -0,0 +1,288 @@
+// speed in m/s below which a record counts as standing still
+pub const DEFAULT_STATIONARY_SPEED: f64 = 0.5;
+// duration in seconds a stop needs to last to count as pause, e.g. at a red light
+pub const DEFAULT_MIN_PAUSE: f64 = 10.0;
+
+// time a section takes, the velocity of the fastest section is its distance over this time
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum MovingTime {
+    // wall-clock time between the start and the end of a section
+    #[default]
+    Elapsed,
+    // without the pauses between the timer stop and start events of fit files
+    Timer,
+    // without the timer pauses and periods slower than `max_speed` for at least `min_duration`
+    Stationary {
+        max_speed: f64,
+        min_duration: f64,
+    },
+}
+
+impl MovingTime {
+    pub const NAMES: [&'static str; 3] = ["elapsed", "timer", "stationary"];
+
+    // cumulative moving time up to each record, None for the elapsed time
+    pub fn cumulative_times(
+        &self,
+        times: &[f64],
+        distances: &[f64],
+        timer_pauses: &[(f64, f64)],
+    ) -> Option<Vec<f64>> {
+        let pauses = match *self {
+            MovingTime::Elapsed => return None,
+            MovingTime::Timer => merge_periods(timer_pauses.to_vec()),
+            MovingTime::Stationary {
+                max_speed,
+                min_duration,
+            } => {
+                let mut pauses = stationary_periods(distances, times, max_speed, min_duration);
+                pauses.extend_from_slice(timer_pauses);
+                merge_periods(pauses)
+            }
+        };
+        Some(cumulative_moving_times(times, &pauses))
+    }
+}
+
+// `stationary` takes the maximum speed in m/s and the minimum duration in seconds as optional
+// parameters, e.g. `stationary:0.5:10`
+impl std::str::FromStr for MovingTime {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<MovingTime, String> {
+        let mut parts = spec.split(':');
+        let name = parts.next().unwrap_or_default();
+        let params: Vec<&str> = parts.collect();
+        let invalid = || format!("Invalid parameters for moving time '{}'.", spec);
+        let param = |i: usize, default: f64| -> Result<f64, String> {
+            match params.get(i) {
+                Some(param) => param.parse::<f64>().map_err(|_| invalid()),
+                None => Ok(default),
+            }
+        };
+        match name {
+            "elapsed" if params.is_empty() => Ok(MovingTime::Elapsed),
+            "timer" if params.is_empty() => Ok(MovingTime::Timer),
+            "stationary" if params.len() <= 2 => {
+                let max_speed = param(0, DEFAULT_STATIONARY_SPEED)?;
+                let min_duration = param(1, DEFAULT_MIN_PAUSE)?;
+                if max_speed > 0.0 && min_duration >= 0.0 {
+                    Ok(MovingTime::Stationary {
+                        max_speed,
+                        min_duration,
+                    })
+                } else {
+                    Err(invalid())
+                }
+            }
+            _ if MovingTime::NAMES.contains(&name) => Err(invalid()),
+            _ => Err(format!(
+                "Unknown moving time '{}', expected one of: {}.",
+                spec,
+                MovingTime::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for MovingTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MovingTime::Elapsed => write!(f, "elapsed"),
+            MovingTime::Timer => write!(f, "timer"),
+            MovingTime::Stationary {
+                max_speed,
+                min_duration,
+            } => write!(f, "stationary:{}:{}", max_speed, min_duration),
+        }
+    }
+}
+
+// periods as (start, end) in seconds, in which the speed between consecutive records stays at or
+// below `max_speed` for at least `min_duration`. Records with missing times or distances are
+// skipped.
+pub fn stationary_periods(
+    distances: &[f64],
+    times: &[f64],
+    max_speed: f64,
+    min_duration: f64,
+) -> Vec<(f64, f64)> {
+    let mut periods = vec![];
+    let mut start: Option<f64> = None;
+    let mut last: Option<(f64, f64)> = None;
+    for (time, distance) in times.iter().zip(distances) {
+        if time.is_nan() || distance.is_nan() {
+            continue;
+        }
+        if let Some((last_time, last_distance)) = last {
+            let duration = time - last_time;
+            if duration == 0.0 {
+                continue;
+            }
+            // times going back, e.g. after a clock reset, end a stationary period
+            let stationary = duration > 0.0 && distance - last_distance <= max_speed * duration;
+            match (stationary, start) {
+                (true, None) => start = Some(last_time),
+                (false, Some(begin)) => {
+                    if last_time - begin >= min_duration {
+                        periods.push((begin, last_time));
+                    }
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+        last = Some((*time, *distance));
+    }
+    if let (Some(begin), Some((end, _))) = (start, last) {
+        if end - begin >= min_duration {
+            periods.push((begin, end));
+        }
+    }
+    periods
+}
+
+// sorted periods without overlaps
+pub fn merge_periods(mut periods: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    periods.retain(|(start, end)| start < end);
+    periods.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut merged: Vec<(f64, f64)> = vec![];
+    for (start, end) in periods {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// time between `start` and `end` covered by the merged pauses
+fn paused_between(pauses: &[(f64, f64)], start: f64, end: f64) -> f64 {
+    let first = pauses.partition_point(|pause| pause.1 <= start);
+    pauses[first..]
+        .iter()
+        .take_while(|pause| pause.0 < end)
+        .map(|pause| pause.1.min(end) - pause.0.max(start))
+        .sum()
+}
+
+// cumulative time without the merged pauses up to each record, like the times the difference
+// between two records is the duration in between. Missing times stay missing.
+pub fn cumulative_moving_times(times: &[f64], pauses: &[(f64, f64)]) -> Vec<f64> {
+    let mut moving_time = 0.0;
+    let mut last: Option<f64> = None;
+    times
+        .iter()
+        .map(|time| {
+            if time.is_nan() {
+                return f64::NAN;
+            }
+            if let Some(last) = last {
+                moving_time += time - last;
+                if *time > last {
+                    moving_time -= paused_between(pauses, last, *time);
+                }
+            }
+            last = Some(*time);
+            moving_time
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_pauses {
+    use super::*;
+
+    #[test]
+    fn test_stationary_periods() {
+        // 3 m/s, standing at a red light for 20 s, jogging slowly for 5 s and then 3 m/s again
+        let mut times = vec![];
+        let mut distances = vec![];
+        let mut distance = 0.0;
+        for t in 0..60 {
+            times.push(t as f64);
+            distances.push(distance);
+            distance += match t {
+                10..=29 => 0.2,
+                40..=44 => 0.3,
+                _ => 3.0,
+            };
+        }
+        assert_eq!(
+            stationary_periods(&distances, &times, 0.5, 10.),
+            vec![(10., 30.)]
+        );
+        assert_eq!(
+            stationary_periods(&distances, &times, 0.5, 5.),
+            vec![(10., 30.), (40., 45.)]
+        );
+        // missing records are skipped
+        distances[20] = f64::NAN;
+        times[25] = f64::NAN;
+        assert_eq!(
+            stationary_periods(&distances, &times, 0.5, 10.),
+            vec![(10., 30.)]
+        );
+    }
+
+    #[test]
+    fn test_merge_periods() {
+        assert_eq!(
+            merge_periods(vec![(10., 20.), (0., 5.), (15., 30.), (40., 40.)]),
+            vec![(0., 5.), (10., 30.)]
+        );
+    }
+
+    #[test]
+    fn test_cumulative_moving_times() {
+        let times = vec![0., 1., 2., f64::NAN, 12., 13.];
+        let moving = cumulative_moving_times(&times, &[(2., 11.), (12.5, 13.)]);
+        assert_eq!(moving[..3], [0., 1., 2.]);
+        assert!(moving[3].is_nan());
+        assert_eq!(moving[4..], [3., 3.5]);
+        assert_eq!(cumulative_moving_times(&times[..3], &[]), vec![0., 1., 2.]);
+    }
+
+    #[test]
+    fn test_moving_time() {
+        let times = vec![0., 10., 20., 30.];
+        let distances = vec![0., 30., 30., 60.];
+        assert_eq!(
+            MovingTime::Elapsed.cumulative_times(&times, &distances, &[]),
+            None
+        );
+        assert_eq!(
+            MovingTime::Timer.cumulative_times(&times, &distances, &[(25., 30.)]),
+            Some(vec![0., 10., 20., 25.])
+        );
+        let stationary: MovingTime = "stationary".parse().unwrap();
+        assert_eq!(
+            stationary.cumulative_times(&times, &distances, &[(25., 30.)]),
+            Some(vec![0., 10., 10., 15.])
+        );
+    }
+
+    #[test]
+    fn test_moving_time_from_str() {
+        assert_eq!("elapsed".parse(), Ok(MovingTime::Elapsed));
+        assert_eq!(
+            "stationary:1".parse(),
+            Ok(MovingTime::Stationary {
+                max_speed: 1.,
+                min_duration: DEFAULT_MIN_PAUSE
+            })
+        );
+        for spec in ["elapsed", "timer", "stationary:0.5:10"] {
+            assert_eq!(spec.parse::<MovingTime>().unwrap().to_string(), spec);
+        }
+        assert!("stationary:0".parse::<MovingTime>().is_err());
+        assert!("timer:3".parse::<MovingTime>().is_err());
+        assert_eq!(
+            "moving".parse::<MovingTime>(),
+            Err(
+                "Unknown moving time 'moving', expected one of: elapsed, timer, stationary."
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,2141 @@
+use crate::{
+    batch, climb, dtypes, errors, export, fit_reader, gem_finder, math, outliers, pauses, quality,
+    smoothing, timestamps, velocity,
+};
+use arrow::ffi::{FFI_ArrowArray, FFI_ArrowSchema};
+use numpy::{IntoPyArray, PyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use pyo3::wrap_pyfunction;
+use pyo3::Python;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
+
+const COORDINATES_SHAPE_MSG: &str = "Coordinates array needs to be of shape (n, 2).";
+
+// input series can be passed either as numpy array or as plain python list. Contiguous float64
+// numpy arrays are borrowed for the duration of the call (or copied in one go, where the search
+// takes ownership) instead of converting each element to a python float first, any other
+// sequence goes through the element-wise conversion. Only objects exposing the numpy array
+// interface are checked for being an array, so numpy is not needed when passing lists.
+enum Series<'a> {
+    Array(PyReadonlyArray1<'a, f64>),
+    List(Vec<f64>),
+}
+
+fn is_array(ob: &PyAny) -> bool {
+    ob.hasattr("__array_interface__").unwrap_or(false)
+}
+
+impl<'a> FromPyObject<'a> for Series<'a> {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        if is_array(ob) {
+            if let Ok(array) = ob.extract() {
+                return Ok(Series::Array(array));
+            }
+        }
+        Ok(Series::List(ob.extract()?))
+    }
+}
+
+impl Series<'_> {
+    fn as_slice(&self) -> Cow<'_, [f64]> {
+        match self {
+            Series::Array(array) => match array.as_slice() {
+                Ok(slice) => Cow::Borrowed(slice),
+                // non-contiguous arrays, e.g. a column view of a larger array
+                Err(_) => Cow::Owned(array.as_array().to_vec()),
+            },
+            Series::List(list) => Cow::Borrowed(list),
+        }
+    }
+
+    fn into_vec(self) -> Vec<f64> {
+        match self {
+            Series::Array(_) => self.as_slice().into_owned(),
+            Series::List(list) => list,
+        }
+    }
+}
+
+enum Coordinates<'a> {
+    Array(PyReadonlyArray2<'a, f64>),
+    List(Vec<(f64, f64)>),
+}
+
+impl<'a> FromPyObject<'a> for Coordinates<'a> {
+    fn extract(ob: &'a PyAny) -> PyResult<Self> {
+        if is_array(ob) {
+            if let Ok(array) = ob.extract() {
+                return Ok(Coordinates::Array(array));
+            }
+        }
+        Ok(Coordinates::List(ob.extract()?))
+    }
+}
+
+impl Coordinates<'_> {
+    fn into_vec(self) -> PyResult<Vec<(f64, f64)>> {
+        match self {
+            Coordinates::Array(array) => {
+                let array = array.as_array();
+                if array.ncols() != 2 {
+                    return Err(PyValueError::new_err(COORDINATES_SHAPE_MSG));
+                }
+                Ok(array
+                    .rows()
+                    .into_iter()
+                    .map(|row| (row[0], row[1]))
+                    .collect())
+            }
+            Coordinates::List(list) => Ok(list),
+        }
+    }
+}
+
+// field values of the result classes, repr, equality, hashing, pickling and to_dict are all
+// implemented on top of the list of fields. Floats are compared by their bits, so that results
+// holding NaN values are still equal to themselves and equal objects have equal hashes.
+enum Field {
+    Int(u32),
+    Float(f64),
+    OptionalFloat(Option<f64>),
+    Coordinate((f64, f64)),
+}
+
+impl ToPyObject for Field {
+    fn to_object(&self, py: Python) -> PyObject {
+        match self {
+            Field::Int(value) => value.to_object(py),
+            Field::Float(value) => value.to_object(py),
+            Field::OptionalFloat(value) => value.to_object(py),
+            Field::Coordinate(value) => value.to_object(py),
+        }
+    }
+}
+
+fn float_bits(value: f64) -> u64 {
+    if value.is_nan() {
+        f64::NAN.to_bits()
+    } else if value == 0. {
+        0
+    } else {
+        value.to_bits()
+    }
+}
+
+fn fields_key(fields: &[(&str, Field)]) -> Vec<u64> {
+    let mut key = vec![];
+    for (_, field) in fields {
+        match field {
+            Field::Int(value) => key.push(*value as u64),
+            Field::Float(value) => key.push(float_bits(*value)),
+            Field::OptionalFloat(value) => {
+                key.extend([value.is_some() as u64, float_bits(value.unwrap_or(0.))])
+            }
+            Field::Coordinate((lat, lon)) => key.extend([float_bits(*lat), float_bits(*lon)]),
+        }
+    }
+    key
+}
+
+fn hash_key<T: Hash>(key: T) -> isize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish() as isize
+}
+
+fn fields_repr(py: Python, name: &str, fields: &[(&str, Field)]) -> PyResult<String> {
+    let mut values = vec![];
+    for (field_name, field) in fields {
+        let value = field.to_object(py);
+        values.push(format!("{}={}", field_name, value.as_ref(py).repr()?));
+    }
+    Ok(format!("{}({})", name, values.join(", ")))
+}
+
+fn fields_dict<'py>(py: Python<'py>, fields: &[(&str, Field)]) -> PyResult<&'py PyDict> {
+    let dict = PyDict::new(py);
+    for (name, field) in fields {
+        dict.set_item(name, field)?;
+    }
+    Ok(dict)
+}
+
+// arguments to recreate the object with its constructor when unpickling
+fn fields_args<'py>(py: Python<'py>, fields: &[(&str, Field)]) -> &'py PyTuple {
+    PyTuple::new(py, fields.iter().map(|(_, field)| field.to_object(py)))
+}
+
+// zero-argument methods take `slf: PyRef<Self>` instead of `py: Python`, pyo3 otherwise generates a
+// fastcall wrapper, which builds a slice from the null argument pointer passed by python
+// only equality is defined for the result classes, ordering comparisons are left to python
+fn compare(py: Python, equal: bool, op: CompareOp) -> PyObject {
+    match op {
+        CompareOp::Eq => equal.into_py(py),
+        CompareOp::Ne => (!equal).into_py(py),
+        _ => py.NotImplemented(),
+    }
+}
+
+#[pyclass(name = "FastestSection", module = "sportgems", dict)]
+struct PyFastestSection {
+    #[pyo3(get)]
+    pub start: u32,
+    #[pyo3(get)]
+    pub end: u32,
+    #[pyo3(get)]
+    pub velocity: f64,
+    #[pyo3(get)]
+    pub distance: f64,
+    #[pyo3(get)]
+    pub duration: f64,
+    #[pyo3(get)]
+    pub start_time: f64,
+    #[pyo3(get)]
+    pub end_time: f64,
+    #[pyo3(get)]
+    pub pace: f64,
+    #[pyo3(get)]
+    pub elevation_gain: Option<f64>,
+    #[pyo3(get)]
+    pub start_coordinate: (f64, f64),
+    #[pyo3(get)]
+    pub end_coordinate: (f64, f64),
+}
+
+#[pymethods]
+impl PyFastestSection {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        start: u32,
+        end: u32,
+        velocity: f64,
+        distance: f64,
+        duration: f64,
+        start_time: f64,
+        end_time: f64,
+        pace: f64,
+        elevation_gain: Option<f64>,
+        start_coordinate: (f64, f64),
+        end_coordinate: (f64, f64),
+    ) -> PyFastestSection {
+        PyFastestSection {
+            start,
+            end,
+            velocity,
+            distance,
+            duration,
+            start_time,
+            end_time,
+            pace,
+            elevation_gain,
+            start_coordinate,
+            end_coordinate,
+        }
+    }
+
+    fn to_dict(slf: PyRef<Self>) -> PyResult<Py<PyDict>> {
+        Ok(fields_dict(slf.py(), &slf.fields())?.into())
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        fields_repr(py, "FastestSection", &self.fields())
+    }
+
+    fn __richcmp__(&self, other: PyRef<PyFastestSection>, op: CompareOp) -> PyObject {
+        let equal = fields_key(&self.fields()) == fields_key(&other.fields());
+        compare(other.py(), equal, op)
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_key(fields_key(&self.fields()))
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> (PyObject, PyObject) {
+        let py = slf.py();
+        let args = fields_args(py, &slf.fields());
+        (py.get_type::<PyFastestSection>().into(), args.into())
+    }
+}
+
+impl PyFastestSection {
+    fn fields(&self) -> Vec<(&'static str, Field)> {
+        vec![
+            ("start", Field::Int(self.start)),
+            ("end", Field::Int(self.end)),
+            ("velocity", Field::Float(self.velocity)),
+            ("distance", Field::Float(self.distance)),
+            ("duration", Field::Float(self.duration)),
+            ("start_time", Field::Float(self.start_time)),
+            ("end_time", Field::Float(self.end_time)),
+            ("pace", Field::Float(self.pace)),
+            ("elevation_gain", Field::OptionalFloat(self.elevation_gain)),
+            ("start_coordinate", Field::Coordinate(self.start_coordinate)),
+            ("end_coordinate", Field::Coordinate(self.end_coordinate)),
+        ]
+    }
+}
+
+impl From<dtypes::TargetSection> for PyFastestSection {
+    fn from(section: dtypes::TargetSection) -> PyFastestSection {
+        PyFastestSection {
+            start: section.start,
+            end: section.end,
+            velocity: section.target_value,
+            distance: section.distance,
+            duration: section.duration,
+            start_time: section.start_time,
+            end_time: section.end_time,
+            pace: section.pace(),
+            elevation_gain: section.elevation_gain,
+            start_coordinate: section.start_coordinate,
+            end_coordinate: section.end_coordinate,
+        }
+    }
+}
+
+#[pyclass(name = "ClimbSection", module = "sportgems", dict)]
+struct PyClimbSection {
+    #[pyo3(get)]
+    pub start: u32,
+    #[pyo3(get)]
+    pub end: u32,
+    #[pyo3(get)]
+    pub climb: f64,
+    #[pyo3(get)]
+    pub distance: f64,
+    #[pyo3(get)]
+    pub duration: f64,
+    #[pyo3(get)]
+    pub start_time: f64,
+    #[pyo3(get)]
+    pub end_time: f64,
+    #[pyo3(get)]
+    pub pace: f64,
+    #[pyo3(get)]
+    pub elevation_gain: Option<f64>,
+    #[pyo3(get)]
+    pub start_coordinate: (f64, f64),
+    #[pyo3(get)]
+    pub end_coordinate: (f64, f64),
+}
+
+#[pymethods]
+impl PyClimbSection {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        start: u32,
+        end: u32,
+        climb: f64,
+        distance: f64,
+        duration: f64,
+        start_time: f64,
+        end_time: f64,
+        pace: f64,
+        elevation_gain: Option<f64>,
+        start_coordinate: (f64, f64),
+        end_coordinate: (f64, f64),
+    ) -> PyClimbSection {
+        PyClimbSection {
+            start,
+            end,
+            climb,
+            distance,
+            duration,
+            start_time,
+            end_time,
+            pace,
+            elevation_gain,
+            start_coordinate,
+            end_coordinate,
+        }
+    }
+
+    fn to_dict(slf: PyRef<Self>) -> PyResult<Py<PyDict>> {
+        Ok(fields_dict(slf.py(), &slf.fields())?.into())
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        fields_repr(py, "ClimbSection", &self.fields())
+    }
+
+    fn __richcmp__(&self, other: PyRef<PyClimbSection>, op: CompareOp) -> PyObject {
+        let equal = fields_key(&self.fields()) == fields_key(&other.fields());
+        compare(other.py(), equal, op)
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_key(fields_key(&self.fields()))
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> (PyObject, PyObject) {
+        let py = slf.py();
+        let args = fields_args(py, &slf.fields());
+        (py.get_type::<PyClimbSection>().into(), args.into())
+    }
+}
+
+impl PyClimbSection {
+    fn fields(&self) -> Vec<(&'static str, Field)> {
+        vec![
+            ("start", Field::Int(self.start)),
+            ("end", Field::Int(self.end)),
+            ("climb", Field::Float(self.climb)),
+            ("distance", Field::Float(self.distance)),
+            ("duration", Field::Float(self.duration)),
+            ("start_time", Field::Float(self.start_time)),
+            ("end_time", Field::Float(self.end_time)),
+            ("pace", Field::Float(self.pace)),
+            ("elevation_gain", Field::OptionalFloat(self.elevation_gain)),
+            ("start_coordinate", Field::Coordinate(self.start_coordinate)),
+            ("end_coordinate", Field::Coordinate(self.end_coordinate)),
+        ]
+    }
+}
+
+impl From<dtypes::TargetSection> for PyClimbSection {
+    fn from(section: dtypes::TargetSection) -> PyClimbSection {
+        PyClimbSection {
+            start: section.start,
+            end: section.end,
+            climb: section.target_value,
+            distance: section.distance,
+            duration: section.duration,
+            start_time: section.start_time,
+            end_time: section.end_time,
+            pace: section.pace(),
+            elevation_gain: section.elevation_gain,
+            start_coordinate: section.start_coordinate,
+            end_coordinate: section.end_coordinate,
+        }
+    }
+}
+
+// unknown distance model names and invalid smoothing specs are raised as ValueError before any
+// work is done
+fn finder_options(
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+) -> PyResult<gem_finder::FinderOptions> {
+    let distance_model = match distance_model {
+        Some(name) => name.parse().map_err(PyValueError::new_err)?,
+        None => math::DistanceModel::default(),
+    };
+    let smoothing = match smoothing {
+        Some(spec) => spec.parse().map_err(PyValueError::new_err)?,
+        None => smoothing::Smoothing::default(),
+    };
+    Ok(gem_finder::FinderOptions {
+        distance_model,
+        slope_distance: slope_distance.unwrap_or(false),
+        smoothing,
+        ..gem_finder::FinderOptions::with_tolerance(tolerance)
+    })
+}
+
+// options of the gained altitude, only used by the climb finders
+fn elevation_options(
+    options: gem_finder::FinderOptions,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+) -> PyResult<gem_finder::FinderOptions> {
+    let elevation_smoothing = match elevation_smoothing {
+        Some(spec) => spec.parse().map_err(PyValueError::new_err)?,
+        None => smoothing::Smoothing::default(),
+    };
+    let gain_method = match gain_method {
+        Some(spec) => spec.parse().map_err(PyValueError::new_err)?,
+        None => climb::GainMethod::default(),
+    };
+    Ok(gem_finder::FinderOptions {
+        elevation_smoothing,
+        gain_method,
+        ..options
+    })
+}
+
+// strategy to fill missing records, taken by all finders
+fn fill_options(
+    options: gem_finder::FinderOptions,
+    fill: Option<String>,
+) -> PyResult<gem_finder::FinderOptions> {
+    let fill = match fill {
+        Some(spec) => spec.parse().map_err(PyValueError::new_err)?,
+        None => math::FillStrategy::default(),
+    };
+    Ok(gem_finder::FinderOptions { fill, ..options })
+}
+
+// infinite gaps are valid, sections then span all gaps
+fn segment_gap(segment_gap: Option<f64>) -> PyResult<f64> {
+    let segment_gap = segment_gap.unwrap_or(timestamps::DEFAULT_SEGMENT_GAP);
+    if segment_gap.is_nan() || segment_gap <= 0.0 {
+        return Err(PyValueError::new_err(format!(
+            "Invalid segment gap '{}', expected a positive number of seconds.",
+            segment_gap
+        )));
+    }
+    Ok(segment_gap)
+}
+
+// sections never span gaps longer than the segment gap, taken by all finders
+fn segment_options(
+    options: gem_finder::FinderOptions,
+    gap: Option<f64>,
+) -> PyResult<gem_finder::FinderOptions> {
+    Ok(gem_finder::FinderOptions {
+        segment_gap: segment_gap(gap)?,
+        ..options
+    })
+}
+
+// time the velocity is computed on, only taken by the fastest section finders
+fn moving_time_options(
+    options: gem_finder::FinderOptions,
+    moving_time: Option<String>,
+) -> PyResult<gem_finder::FinderOptions> {
+    let moving_time = match moving_time {
+        Some(spec) => spec.parse().map_err(PyValueError::new_err)?,
+        None => pauses::MovingTime::default(),
+    };
+    Ok(gem_finder::FinderOptions {
+        moving_time,
+        ..options
+    })
+}
+
+// all pyfunctions below run the actual parsing and searching inside of `allow_threads`, so other
+// python threads are not blocked while sportgems is crunching numbers
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn find_fastest_section(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    coordinates: Coordinates,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    altitudes: Option<Series>,
+    smoothing: Option<String>,
+    fill: Option<String>,
+    moving_time: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<Py<PyFastestSection>> {
+    let options = segment_options(
+        moving_time_options(
+            fill_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                fill,
+            )?,
+            moving_time,
+        )?,
+        segment_gap,
+    )?;
+    let (times, coordinates, altitudes) = (
+        times.into_vec(),
+        coordinates.into_vec()?,
+        altitudes.map(|altitudes| altitudes.into_vec()),
+    );
+    let result = py.allow_threads(|| {
+        velocity::find_fastest_section_with_options(
+            desired_distance,
+            coordinates,
+            times,
+            altitudes,
+            &options,
+        )
+    })?;
+    Py::new(py, PyFastestSection::from(result))
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn find_fastest_section_in_fit(
+    py: Python,
+    desired_distance: f64,
+    path_to_fit: &str,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+    fill: Option<String>,
+    moving_time: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<Py<PyFastestSection>> {
+    let options = segment_options(
+        moving_time_options(
+            fill_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                fill,
+            )?,
+            moving_time,
+        )?,
+        segment_gap,
+    )?;
+    let result = py.allow_threads(|| {
+        velocity::find_fastest_section_in_fit_with_options(desired_distance, path_to_fit, &options)
+    })?;
+    Py::new(py, PyFastestSection::from(result))
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn find_best_climb_section(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    coordinates: Coordinates,
+    altitudes: Series,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<Py<PyClimbSection>> {
+    let options = segment_options(
+        fill_options(
+            elevation_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                elevation_smoothing,
+                gain_method,
+            )?,
+            fill,
+        )?,
+        segment_gap,
+    )?;
+    let (times, coordinates, altitudes) = (
+        times.into_vec(),
+        coordinates.into_vec()?,
+        altitudes.into_vec(),
+    );
+    let result = py.allow_threads(|| {
+        climb::find_best_climb_section_with_options(
+            desired_distance,
+            coordinates,
+            times,
+            altitudes,
+            &options,
+        )
+    })?;
+    Py::new(py, PyClimbSection::from(result))
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn find_best_climb_section_in_fit(
+    py: Python,
+    desired_distance: f64,
+    path_to_fit: &str,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<Py<PyClimbSection>> {
+    let options = segment_options(
+        fill_options(
+            elevation_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                elevation_smoothing,
+                gain_method,
+            )?,
+            fill,
+        )?,
+        segment_gap,
+    )?;
+    let result = py.allow_threads(|| {
+        climb::find_best_climb_section_in_fit_with_options(desired_distance, path_to_fit, &options)
+    })?;
+    Py::new(py, PyClimbSection::from(result))
+}
+
+// variants of the functions above searching on cumulative distances, e.g. recorded by a footpod
+// during an indoor run, instead of coordinates
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn find_fastest_section_from_distances(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    distances: Series,
+    tolerance: Option<f64>,
+    altitudes: Option<Series>,
+    fill: Option<String>,
+    moving_time: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<Py<PyFastestSection>> {
+    let options = segment_options(
+        moving_time_options(
+            fill_options(gem_finder::FinderOptions::with_tolerance(tolerance), fill)?,
+            moving_time,
+        )?,
+        segment_gap,
+    )?;
+    let (times, distances, altitudes) = (
+        times.into_vec(),
+        distances.into_vec(),
+        altitudes.map(|altitudes| altitudes.into_vec()),
+    );
+    let result = py.allow_threads(|| {
+        velocity::find_fastest_section_with_distances(
+            desired_distance,
+            distances,
+            times,
+            altitudes,
+            &options,
+        )
+    })?;
+    Py::new(py, PyFastestSection::from(result))
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+fn find_best_climb_section_from_distances(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    distances: Series,
+    altitudes: Series,
+    tolerance: Option<f64>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<Py<PyClimbSection>> {
+    let options = segment_options(
+        fill_options(
+            elevation_options(
+                gem_finder::FinderOptions::with_tolerance(tolerance),
+                elevation_smoothing,
+                gain_method,
+            )?,
+            fill,
+        )?,
+        segment_gap,
+    )?;
+    let (times, distances, altitudes) =
+        (times.into_vec(), distances.into_vec(), altitudes.into_vec());
+    let result = py.allow_threads(|| {
+        climb::find_best_climb_section_with_distances(
+            desired_distance,
+            distances,
+            times,
+            altitudes,
+            &options,
+        )
+    })?;
+    Py::new(py, PyClimbSection::from(result))
+}
+
+#[pyfunction]
+fn total_ascent_descent(
+    py: Python,
+    altitudes: Series,
+    times: Option<Series>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+) -> PyResult<(f64, f64)> {
+    let options = fill_options(
+        elevation_options(
+            gem_finder::FinderOptions::default(),
+            elevation_smoothing,
+            gain_method,
+        )?,
+        fill,
+    )?;
+    let altitudes = altitudes.as_slice();
+    let times = series_times(&times, altitudes.len())?;
+    Ok(py.allow_threads(|| climb::total_ascent_descent(&altitudes, &times, &options)))
+}
+
+// without times the records are assumed to be one second apart
+fn series_times<'a>(times: &'a Option<Series>, len: usize) -> PyResult<Cow<'a, [f64]>> {
+    let times = match times {
+        Some(times) => times.as_slice(),
+        None => Cow::Owned((0..len).map(|i| i as f64).collect()),
+    };
+    if times.len() != len {
+        return Err(errors::InputDataError::InconsistentLength.into());
+    }
+    Ok(times)
+}
+
+fn outlier_filter(method: Option<String>) -> PyResult<outliers::OutlierFilter> {
+    match method {
+        Some(spec) => spec.parse().map_err(PyValueError::new_err),
+        None => Ok(outliers::OutlierFilter::Hampel {
+            window: outliers::DEFAULT_HAMPEL_WINDOW,
+            threshold: outliers::DEFAULT_HAMPEL_THRESHOLD,
+            min_deviation: outliers::DEFAULT_MIN_DEVIATION,
+        }),
+    }
+}
+
+#[pyfunction]
+fn detect_outliers(
+    py: Python,
+    values: Series,
+    times: Option<Series>,
+    method: Option<String>,
+) -> PyResult<Vec<bool>> {
+    let filter = outlier_filter(method)?;
+    let values = values.as_slice();
+    let times = series_times(&times, values.len())?;
+    Ok(py.allow_threads(|| filter.detect(&values, &times)))
+}
+
+#[pyfunction]
+fn remove_outliers(
+    py: Python,
+    values: Series,
+    times: Option<Series>,
+    method: Option<String>,
+) -> PyResult<Vec<f64>> {
+    let filter = outlier_filter(method)?;
+    let values = values.as_slice();
+    let times = series_times(&times, values.len())?;
+    Ok(py.allow_threads(|| filter.apply(&values, &times)))
+}
+
+#[pyfunction]
+fn remove_coordinate_outliers(
+    py: Python,
+    coordinates: Vec<(f64, f64)>,
+    times: Option<Series>,
+    method: Option<String>,
+) -> PyResult<Vec<(f64, f64)>> {
+    let filter = outlier_filter(method)?;
+    let times = series_times(&times, coordinates.len())?;
+    Ok(py.allow_threads(|| filter.apply_coordinates(&coordinates, &times)))
+}
+
+fn time_issues_to_dict(py: Python, issues: &timestamps::TimeIssues) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("duplicates", &issues.duplicates)?;
+    dict.set_item("out_of_order", &issues.out_of_order)?;
+    dict.set_item("clock_resets", &issues.clock_resets)?;
+    dict.set_item("gaps", &issues.gaps)?;
+    Ok(dict.into())
+}
+
+// ranges are handed over as (start, end) tuples, which can be used for slicing
+fn to_ranges(segments: Vec<std::ops::Range<usize>>) -> Vec<(usize, usize)> {
+    segments
+        .into_iter()
+        .map(|segment| (segment.start, segment.end))
+        .collect()
+}
+
+#[pyfunction]
+fn check_times(py: Python, times: Series, max_gap: Option<f64>) -> PyResult<Py<PyDict>> {
+    let issues = timestamps::check_times(&times.as_slice(), segment_gap(max_gap)?);
+    time_issues_to_dict(py, &issues)
+}
+
+#[pyfunction]
+fn repair_times(times: Series) -> Vec<f64> {
+    timestamps::repair_times(&times.as_slice())
+}
+
+#[pyfunction]
+fn split_segments(times: Series, max_gap: Option<f64>) -> PyResult<Vec<(usize, usize)>> {
+    Ok(to_ranges(timestamps::split_segments(
+        &times.as_slice(),
+        segment_gap(max_gap)?,
+    )))
+}
+
+#[pyclass(name = "FitData", module = "sportgems", dict)]
+struct PyFitData {
+    fit_data: fit_reader::FitData,
+    arrays: FitDataArrays,
+}
+
+// numpy arrays of the series, created on first access. They are read-only, so that the cached
+// arrays cannot get out of sync with the series.
+#[derive(Default)]
+struct FitDataArrays {
+    times: OnceLock<Py<PyArray1<f64>>>,
+    coordinates: OnceLock<Py<PyArray2<f64>>>,
+    altitudes: OnceLock<Py<PyArray1<f64>>>,
+    distances: OnceLock<Py<PyArray1<f64>>>,
+}
+
+fn cached_array<'py, D: 'py>(
+    py: Python<'py>,
+    cache: &OnceLock<Py<PyArray<f64, D>>>,
+    create: impl FnOnce() -> PyResult<&'py PyArray<f64, D>>,
+) -> PyResult<Py<PyArray<f64, D>>> {
+    if let Some(array) = cache.get() {
+        return Ok(array.clone_ref(py));
+    }
+    let array = create()?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("write", false)?;
+    array.call_method("setflags", (), Some(kwargs))?;
+    Ok(cache.get_or_init(|| array.into()).clone_ref(py))
+}
+
+#[pymethods]
+impl PyFitData {
+    #[new]
+    #[args(distances = "None", timer_pauses = "None", record_messages = "None")]
+    fn new(
+        calories: u16,
+        times: Vec<f64>,
+        coordinates: Vec<(f64, f64)>,
+        altitudes: Vec<f64>,
+        distances: Option<Vec<f64>>,
+        timer_pauses: Option<Vec<(f64, f64)>>,
+        record_messages: Option<Vec<bool>>,
+    ) -> PyFitData {
+        PyFitData::from(fit_reader::FitData {
+            calories,
+            times,
+            coordinates,
+            altitudes,
+            distances: distances.unwrap_or_default(),
+            timer_pauses: timer_pauses.unwrap_or_default(),
+            record_messages: record_messages.unwrap_or_default(),
+        })
+    }
+
+    #[getter]
+    fn calories(&self) -> u16 {
+        self.fit_data.calories
+    }
+
+    #[getter]
+    fn times(&self) -> Vec<f64> {
+        self.fit_data.times.clone()
+    }
+
+    #[getter]
+    fn coordinates(&self) -> Vec<(f64, f64)> {
+        self.fit_data.coordinates.clone()
+    }
+
+    #[getter]
+    fn altitudes(&self) -> Vec<f64> {
+        self.fit_data.altitudes.clone()
+    }
+
+    #[getter]
+    fn distances(&self) -> Vec<f64> {
+        self.fit_data.distances.clone()
+    }
+
+    #[getter]
+    fn timer_pauses(&self) -> Vec<(f64, f64)> {
+        self.fit_data.timer_pauses.clone()
+    }
+
+    #[getter]
+    fn record_messages(&self) -> Vec<bool> {
+        self.fit_data.record_messages.clone()
+    }
+
+    #[getter]
+    fn times_array(&self, py: Python) -> PyResult<Py<PyArray1<f64>>> {
+        cached_array(py, &self.arrays.times, || {
+            Ok(PyArray1::from_slice(py, &self.fit_data.times))
+        })
+    }
+
+    #[getter]
+    fn coordinates_array(&self, py: Python) -> PyResult<Py<PyArray2<f64>>> {
+        cached_array(py, &self.arrays.coordinates, || {
+            let flat: Vec<f64> = self
+                .fit_data
+                .coordinates
+                .iter()
+                .flat_map(|(lat, lon)| [*lat, *lon])
+                .collect();
+            flat.into_pyarray(py)
+                .reshape([self.fit_data.coordinates.len(), 2])
+        })
+    }
+
+    #[getter]
+    fn altitudes_array(&self, py: Python) -> PyResult<Py<PyArray1<f64>>> {
+        cached_array(py, &self.arrays.altitudes, || {
+            Ok(PyArray1::from_slice(py, &self.fit_data.altitudes))
+        })
+    }
+
+    #[getter]
+    fn distances_array(&self, py: Python) -> PyResult<Py<PyArray1<f64>>> {
+        cached_array(py, &self.arrays.distances, || {
+            Ok(PyArray1::from_slice(py, &self.fit_data.distances))
+        })
+    }
+
+    // the columns are handed over to pyarrow via the arrow c data interface, pyarrow takes over
+    // ownership of the exported buffers and releases them once the record batch is dropped
+    fn to_arrow(slf: PyRef<Self>) -> PyResult<PyObject> {
+        let py = slf.py();
+        let (array, schema) = export::to_ffi(&slf.fit_data)?;
+        let (array, schema) = (Box::new(array), Box::new(schema));
+        let batch = py.import("pyarrow")?.getattr("RecordBatch")?.call_method1(
+            "_import_from_c",
+            (
+                &*array as *const FFI_ArrowArray as usize,
+                &*schema as *const FFI_ArrowSchema as usize,
+            ),
+        )?;
+        Ok(batch.into())
+    }
+
+    fn to_dict(slf: PyRef<Self>) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(slf.py());
+        dict.set_item("calories", slf.fit_data.calories)?;
+        dict.set_item("times", &slf.fit_data.times)?;
+        dict.set_item("coordinates", &slf.fit_data.coordinates)?;
+        dict.set_item("altitudes", &slf.fit_data.altitudes)?;
+        dict.set_item("distances", &slf.fit_data.distances)?;
+        dict.set_item("timer_pauses", &slf.fit_data.timer_pauses)?;
+        dict.set_item("record_messages", &slf.fit_data.record_messages)?;
+        Ok(dict.into())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FitData(calories={}, records={})",
+            self.fit_data.calories,
+            self.fit_data.times.len()
+        )
+    }
+
+    fn __richcmp__(&self, other: PyRef<PyFitData>, op: CompareOp) -> PyObject {
+        compare(other.py(), self.key() == other.key(), op)
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_key(self.key())
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> (PyObject, PyObject) {
+        let py = slf.py();
+        let fit_data = &slf.fit_data;
+        let args = (
+            fit_data.calories,
+            fit_data.times.clone(),
+            fit_data.coordinates.clone(),
+            fit_data.altitudes.clone(),
+            fit_data.distances.clone(),
+            fit_data.timer_pauses.clone(),
+            fit_data.record_messages.clone(),
+        );
+        (py.get_type::<PyFitData>().into(), args.into_py(py))
+    }
+}
+
+impl PyFitData {
+    // the lengths are part of the key, so that values cannot shift from one series to the next
+    fn key(&self) -> Vec<u64> {
+        let fit_data = &self.fit_data;
+        let mut key = vec![fit_data.calories as u64, fit_data.times.len() as u64];
+        key.extend(fit_data.times.iter().map(|v| float_bits(*v)));
+        key.push(fit_data.coordinates.len() as u64);
+        for (lat, lon) in &fit_data.coordinates {
+            key.extend([float_bits(*lat), float_bits(*lon)]);
+        }
+        key.push(fit_data.altitudes.len() as u64);
+        key.extend(fit_data.altitudes.iter().map(|v| float_bits(*v)));
+        key.push(fit_data.distances.len() as u64);
+        key.extend(fit_data.distances.iter().map(|v| float_bits(*v)));
+        key.push(fit_data.timer_pauses.len() as u64);
+        for (stop, start) in &fit_data.timer_pauses {
+            key.extend([float_bits(*stop), float_bits(*start)]);
+        }
+        key.push(fit_data.record_messages.len() as u64);
+        key.extend(fit_data.record_messages.iter().map(|r| *r as u64));
+        key
+    }
+}
+
+impl From<fit_reader::FitData> for PyFitData {
+    fn from(fit_data: fit_reader::FitData) -> PyFitData {
+        PyFitData {
+            fit_data,
+            arrays: FitDataArrays::default(),
+        }
+    }
+}
+
+#[pyfunction]
+fn parse_fit_data(py: Python, path_to_fit: &str) -> PyResult<Py<PyFitData>> {
+    let fit_data: fit_reader::FitData = py.allow_threads(|| fit_reader::parse_fit(path_to_fit))?;
+    Py::new(py, PyFitData::from(fit_data))
+}
+
+#[pyclass(name = "FileAnalysis", module = "sportgems", dict)]
+struct PyFileAnalysis {
+    #[pyo3(get)]
+    pub path: String,
+    #[pyo3(get)]
+    pub fastest_sections: Vec<Option<Py<PyFastestSection>>>,
+    #[pyo3(get)]
+    pub climb_sections: Vec<Option<Py<PyClimbSection>>>,
+    // exception per distance, for which no section could be found
+    #[pyo3(get)]
+    pub fastest_section_errors: Vec<Option<PyObject>>,
+    #[pyo3(get)]
+    pub climb_section_errors: Vec<Option<PyObject>>,
+    #[pyo3(get)]
+    pub error: Option<PyObject>,
+}
+
+#[pymethods]
+impl PyFileAnalysis {
+    #[new]
+    #[args(
+        fastest_section_errors = "Vec::new()",
+        climb_section_errors = "Vec::new()",
+        error = "None"
+    )]
+    fn new(
+        path: String,
+        fastest_sections: Vec<Option<Py<PyFastestSection>>>,
+        climb_sections: Vec<Option<Py<PyClimbSection>>>,
+        fastest_section_errors: Vec<Option<PyObject>>,
+        climb_section_errors: Vec<Option<PyObject>>,
+        error: Option<PyObject>,
+    ) -> PyFileAnalysis {
+        PyFileAnalysis {
+            path,
+            fastest_sections,
+            climb_sections,
+            fastest_section_errors,
+            climb_section_errors,
+            error,
+        }
+    }
+
+    // nested sections are converted to dicts as well and the errors to their messages
+    fn to_dict(slf: PyRef<Self>) -> PyResult<Py<PyDict>> {
+        let py = slf.py();
+        let dict = PyDict::new(py);
+        dict.set_item("path", &slf.path)?;
+        let mut fastest_sections = vec![];
+        for section in &slf.fastest_sections {
+            fastest_sections.push(match section {
+                Some(section) => Some(fields_dict(py, &section.borrow(py).fields())?),
+                None => None,
+            });
+        }
+        dict.set_item("fastest_sections", fastest_sections)?;
+        let mut climb_sections = vec![];
+        for section in &slf.climb_sections {
+            climb_sections.push(match section {
+                Some(section) => Some(fields_dict(py, &section.borrow(py).fields())?),
+                None => None,
+            });
+        }
+        dict.set_item("climb_sections", climb_sections)?;
+        dict.set_item(
+            "fastest_section_errors",
+            error_strings(py, &slf.fastest_section_errors, false)?,
+        )?;
+        dict.set_item(
+            "climb_section_errors",
+            error_strings(py, &slf.climb_section_errors, false)?,
+        )?;
+        let error = match &slf.error {
+            Some(error) => Some(error.as_ref(py).str()?),
+            None => None,
+        };
+        dict.set_item("error", error)?;
+        Ok(dict.into())
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "FileAnalysis(path={}, fastest_sections={}, climb_sections={}, fastest_section_errors={}, climb_section_errors={}, error={})",
+            self.path.to_object(py).as_ref(py).repr()?,
+            self.fastest_sections.to_object(py).as_ref(py).repr()?,
+            self.climb_sections.to_object(py).as_ref(py).repr()?,
+            self.fastest_section_errors.to_object(py).as_ref(py).repr()?,
+            self.climb_section_errors.to_object(py).as_ref(py).repr()?,
+            self.error.to_object(py).as_ref(py).repr()?,
+        ))
+    }
+
+    fn __richcmp__(&self, other: PyRef<PyFileAnalysis>, op: CompareOp) -> PyResult<PyObject> {
+        let py = other.py();
+        let equal = self
+            .key(py)?
+            .rich_compare(other.key(py)?, CompareOp::Eq)?
+            .is_true()?;
+        Ok(compare(py, equal, op))
+    }
+
+    fn __hash__(&self, py: Python) -> PyResult<isize> {
+        self.key(py)?.hash()
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> (PyObject, PyObject) {
+        let py = slf.py();
+        let args = (
+            slf.path.clone(),
+            slf.fastest_sections.clone(),
+            slf.climb_sections.clone(),
+            slf.fastest_section_errors.clone(),
+            slf.climb_section_errors.clone(),
+            slf.error.clone(),
+        );
+        (py.get_type::<PyFileAnalysis>().into(), args.into_py(py))
+    }
+}
+
+impl PyFileAnalysis {
+    // exceptions are compared by identity in python, therefore errors are compared by their repr
+    fn key<'py>(&self, py: Python<'py>) -> PyResult<&'py PyTuple> {
+        let error = match &self.error {
+            Some(error) => Some(error.as_ref(py).repr()?),
+            None => None,
+        };
+        Ok(PyTuple::new(
+            py,
+            &[
+                self.path.to_object(py),
+                PyTuple::new(py, &self.fastest_sections).to_object(py),
+                PyTuple::new(py, &self.climb_sections).to_object(py),
+                PyTuple::new(py, error_strings(py, &self.fastest_section_errors, true)?)
+                    .to_object(py),
+                PyTuple::new(py, error_strings(py, &self.climb_section_errors, true)?)
+                    .to_object(py),
+                error.to_object(py),
+            ],
+        ))
+    }
+}
+
+// messages (or reprs) of optional exceptions
+fn error_strings(
+    py: Python,
+    errors: &[Option<PyObject>],
+    repr: bool,
+) -> PyResult<Vec<Option<String>>> {
+    errors
+        .iter()
+        .map(|error| match error {
+            Some(error) if repr => Ok(Some(error.as_ref(py).repr()?.to_str()?.to_owned())),
+            Some(error) => Ok(Some(error.as_ref(py).str()?.to_str()?.to_owned())),
+            None => Ok(None),
+        })
+        .collect()
+}
+
+// the fastest sections and the exceptions per distance returned by `Activity.best_efforts`
+type BestEfforts = (Vec<Option<Py<PyFastestSection>>>, Vec<Option<PyObject>>);
+
+// splits the results of a search per distance into the found sections and the exceptions
+fn split_section_results(
+    py: Python,
+    results: Vec<Result<dtypes::TargetSection, errors::InputDataError>>,
+) -> (Vec<Option<dtypes::TargetSection>>, Vec<Option<PyObject>>) {
+    results
+        .into_iter()
+        .map(|result| match result {
+            Ok(section) => (Some(section), None),
+            Err(e) => (None, Some(PyErr::from(e).instance(py).into())),
+        })
+        .unzip()
+}
+
+fn to_py_file_analyses(
+    py: Python,
+    paths: Vec<String>,
+    results: Vec<Result<batch::FileAnalysis, errors::InputDataError>>,
+) -> PyResult<Vec<Py<PyFileAnalysis>>> {
+    let mut analyses = vec![];
+    for (path, result) in paths.into_iter().zip(results) {
+        let analysis = match result {
+            Ok(analysis) => {
+                let (fastest_sections, fastest_section_errors) =
+                    split_section_results(py, analysis.fastest_sections);
+                let (climb_sections, climb_section_errors) =
+                    split_section_results(py, analysis.climb_sections);
+                PyFileAnalysis {
+                    path,
+                    fastest_sections: fastest_sections
+                        .into_iter()
+                        .map(|section| section.map(|s| Py::new(py, PyFastestSection::from(s))))
+                        .map(Option::transpose)
+                        .collect::<PyResult<_>>()?,
+                    climb_sections: climb_sections
+                        .into_iter()
+                        .map(|section| section.map(|s| Py::new(py, PyClimbSection::from(s))))
+                        .map(Option::transpose)
+                        .collect::<PyResult<_>>()?,
+                    fastest_section_errors,
+                    climb_section_errors,
+                    error: None,
+                }
+            }
+            Err(e) => PyFileAnalysis {
+                path,
+                fastest_sections: vec![],
+                climb_sections: vec![],
+                fastest_section_errors: vec![],
+                climb_section_errors: vec![],
+                error: Some(PyErr::from(e).instance(py).into()),
+            },
+        };
+        analyses.push(Py::new(py, analysis)?);
+    }
+    Ok(analyses)
+}
+
+#[pyfunction]
+fn analyze_files(
+    py: Python,
+    paths: Vec<String>,
+    distances: Vec<f64>,
+    tolerance: Option<f64>,
+) -> PyResult<Vec<Py<PyFileAnalysis>>> {
+    let results = py.allow_threads(|| batch::analyze_files(&paths, &distances, tolerance));
+    to_py_file_analyses(py, paths, results)
+}
+
+// stateful counterpart of the functions above, the activity is parsed once and derived data like
+// the cumulative distances is cached between searches
+#[pyclass(name = "Activity", module = "sportgems")]
+struct PyActivity {
+    activity: gem_finder::Activity,
+}
+
+#[pymethods]
+impl PyActivity {
+    #[new]
+    #[args(
+        altitudes = "None",
+        distance_model = "None",
+        slope_distance = "false",
+        smoothing = "None",
+        elevation_smoothing = "None",
+        gain_method = "None",
+        fill = "None",
+        moving_time = "None",
+        segment_gap = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python,
+        times: Series,
+        coordinates: Coordinates,
+        altitudes: Option<Series>,
+        distance_model: Option<String>,
+        slope_distance: bool,
+        smoothing: Option<String>,
+        elevation_smoothing: Option<String>,
+        gain_method: Option<String>,
+        fill: Option<String>,
+        moving_time: Option<String>,
+        segment_gap: Option<f64>,
+    ) -> PyResult<PyActivity> {
+        let options = segment_options(
+            moving_time_options(
+                fill_options(
+                    elevation_options(
+                        finder_options(None, distance_model, Some(slope_distance), smoothing)?,
+                        elevation_smoothing,
+                        gain_method,
+                    )?,
+                    fill,
+                )?,
+                moving_time,
+            )?,
+            segment_gap,
+        )?;
+        let (times, coordinates, altitudes) = (
+            times.into_vec(),
+            coordinates.into_vec()?,
+            altitudes.map(|altitudes| altitudes.into_vec()),
+        );
+        let activity =
+            py.allow_threads(|| gem_finder::Activity::new(times, coordinates, altitudes))?;
+        Ok(PyActivity {
+            activity: activity
+                .with_distance_model(options.distance_model)
+                .with_slope_distance(options.slope_distance)
+                .with_smoothing(options.smoothing)
+                .with_elevation_smoothing(options.elevation_smoothing)
+                .with_gain_method(options.gain_method)
+                .with_fill(options.fill)
+                .with_moving_time(options.moving_time)
+                .with_segment_gap(options.segment_gap),
+        })
+    }
+
+    #[staticmethod]
+    #[args(
+        distance_model = "None",
+        slope_distance = "false",
+        smoothing = "None",
+        elevation_smoothing = "None",
+        gain_method = "None",
+        fill = "None",
+        moving_time = "None",
+        segment_gap = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn from_fit(
+        py: Python,
+        path_to_fit: &str,
+        distance_model: Option<String>,
+        slope_distance: bool,
+        smoothing: Option<String>,
+        elevation_smoothing: Option<String>,
+        gain_method: Option<String>,
+        fill: Option<String>,
+        moving_time: Option<String>,
+        segment_gap: Option<f64>,
+    ) -> PyResult<PyActivity> {
+        let options = segment_options(
+            moving_time_options(
+                fill_options(
+                    elevation_options(
+                        finder_options(None, distance_model, Some(slope_distance), smoothing)?,
+                        elevation_smoothing,
+                        gain_method,
+                    )?,
+                    fill,
+                )?,
+                moving_time,
+            )?,
+            segment_gap,
+        )?;
+        let activity = py.allow_threads(|| gem_finder::Activity::from_fit(path_to_fit))?;
+        Ok(PyActivity {
+            activity: activity
+                .with_distance_model(options.distance_model)
+                .with_slope_distance(options.slope_distance)
+                .with_smoothing(options.smoothing)
+                .with_elevation_smoothing(options.elevation_smoothing)
+                .with_gain_method(options.gain_method)
+                .with_fill(options.fill)
+                .with_moving_time(options.moving_time)
+                .with_segment_gap(options.segment_gap),
+        })
+    }
+
+    #[staticmethod]
+    #[args(
+        distance_model = "None",
+        slope_distance = "false",
+        smoothing = "None",
+        elevation_smoothing = "None",
+        gain_method = "None",
+        fill = "None",
+        moving_time = "None",
+        segment_gap = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn from_bytes(
+        py: Python,
+        data: &[u8],
+        distance_model: Option<String>,
+        slope_distance: bool,
+        smoothing: Option<String>,
+        elevation_smoothing: Option<String>,
+        gain_method: Option<String>,
+        fill: Option<String>,
+        moving_time: Option<String>,
+        segment_gap: Option<f64>,
+    ) -> PyResult<PyActivity> {
+        let options = segment_options(
+            moving_time_options(
+                fill_options(
+                    elevation_options(
+                        finder_options(None, distance_model, Some(slope_distance), smoothing)?,
+                        elevation_smoothing,
+                        gain_method,
+                    )?,
+                    fill,
+                )?,
+                moving_time,
+            )?,
+            segment_gap,
+        )?;
+        let activity = py.allow_threads(|| gem_finder::Activity::from_fit_bytes(data))?;
+        Ok(PyActivity {
+            activity: activity
+                .with_distance_model(options.distance_model)
+                .with_slope_distance(options.slope_distance)
+                .with_smoothing(options.smoothing)
+                .with_elevation_smoothing(options.elevation_smoothing)
+                .with_gain_method(options.gain_method)
+                .with_fill(options.fill)
+                .with_moving_time(options.moving_time)
+                .with_segment_gap(options.segment_gap),
+        })
+    }
+
+    #[staticmethod]
+    #[args(
+        altitudes = "None",
+        elevation_smoothing = "None",
+        gain_method = "None",
+        fill = "None",
+        moving_time = "None",
+        segment_gap = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn from_distances(
+        py: Python,
+        times: Series,
+        distances: Series,
+        altitudes: Option<Series>,
+        elevation_smoothing: Option<String>,
+        gain_method: Option<String>,
+        fill: Option<String>,
+        moving_time: Option<String>,
+        segment_gap: Option<f64>,
+    ) -> PyResult<PyActivity> {
+        let options = segment_options(
+            moving_time_options(
+                fill_options(
+                    elevation_options(
+                        gem_finder::FinderOptions::default(),
+                        elevation_smoothing,
+                        gain_method,
+                    )?,
+                    fill,
+                )?,
+                moving_time,
+            )?,
+            segment_gap,
+        )?;
+        let (times, distances, altitudes) = (
+            times.into_vec(),
+            distances.into_vec(),
+            altitudes.map(|altitudes| altitudes.into_vec()),
+        );
+        let activity =
+            py.allow_threads(|| gem_finder::Activity::from_distances(times, distances, altitudes))?;
+        Ok(PyActivity {
+            activity: activity
+                .with_elevation_smoothing(options.elevation_smoothing)
+                .with_gain_method(options.gain_method)
+                .with_fill(options.fill)
+                .with_moving_time(options.moving_time)
+                .with_segment_gap(options.segment_gap),
+        })
+    }
+
+    #[getter]
+    fn distance_model(&self) -> &'static str {
+        self.activity.distance_model().name()
+    }
+
+    #[getter]
+    fn slope_distance(&self) -> bool {
+        self.activity.slope_distance()
+    }
+
+    #[getter]
+    fn smoothing(&self) -> String {
+        self.activity.smoothing().to_string()
+    }
+
+    #[getter]
+    fn elevation_smoothing(&self) -> String {
+        self.activity.elevation_smoothing().to_string()
+    }
+
+    #[getter]
+    fn gain_method(&self) -> String {
+        self.activity.gain_method().to_string()
+    }
+
+    #[getter]
+    fn fill(&self) -> String {
+        self.activity.fill().to_string()
+    }
+
+    #[getter]
+    fn moving_time(&self) -> String {
+        self.activity.moving_time().to_string()
+    }
+
+    #[getter]
+    fn timer_pauses(&self) -> Vec<(f64, f64)> {
+        self.activity.timer_pauses().to_vec()
+    }
+
+    #[getter]
+    fn segment_gap(&self) -> f64 {
+        self.activity.segment_gap()
+    }
+
+    fn time_issues(slf: PyRef<Self>) -> PyResult<Py<PyDict>> {
+        time_issues_to_dict(slf.py(), &slf.activity.time_issues())
+    }
+
+    fn segments(&self) -> Vec<(usize, usize)> {
+        to_ranges(self.activity.segments())
+    }
+
+    #[getter]
+    fn calories(&self) -> Option<u16> {
+        self.activity.calories()
+    }
+
+    #[getter]
+    fn times(&self) -> Vec<f64> {
+        self.activity.times().to_vec()
+    }
+
+    #[getter]
+    fn coordinates(&self, py: Python) -> Vec<(f64, f64)> {
+        let activity = &self.activity;
+        py.allow_threads(|| activity.coordinates().to_vec())
+    }
+
+    #[getter]
+    fn altitudes(&self, py: Python) -> Option<Vec<f64>> {
+        let activity = &self.activity;
+        py.allow_threads(|| activity.altitudes().map(|altitudes| altitudes.to_vec()))
+    }
+
+    #[getter]
+    fn distances(&self, py: Python) -> Vec<f64> {
+        let activity = &self.activity;
+        py.allow_threads(|| activity.distances().to_vec())
+    }
+
+    #[getter]
+    fn elevation_gain(&self, py: Python) -> Option<f64> {
+        let activity = &self.activity;
+        py.allow_threads(|| activity.elevation_gain())
+    }
+
+    fn ascent_descent(slf: PyRef<Self>) -> Option<(f64, f64)> {
+        let activity = &slf.activity;
+        slf.py().allow_threads(|| activity.ascent_descent())
+    }
+
+    #[args(tolerance = "None")]
+    fn fastest_section(
+        &self,
+        py: Python,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> PyResult<Py<PyFastestSection>> {
+        let activity = &self.activity;
+        let result = py.allow_threads(|| activity.fastest_section(desired_distance, tolerance))?;
+        Py::new(py, PyFastestSection::from(result))
+    }
+
+    #[args(tolerance = "None")]
+    fn best_climb_section(
+        &self,
+        py: Python,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> PyResult<Py<PyClimbSection>> {
+        let activity = &self.activity;
+        let result =
+            py.allow_threads(|| activity.best_climb_section(desired_distance, tolerance))?;
+        Py::new(py, PyClimbSection::from(result))
+    }
+
+    // returns the sections and the exceptions per distance, like `fastest_sections` and
+    // `fastest_section_errors` of the results of `analyze_files`
+    #[args(tolerance = "None")]
+    fn best_efforts(
+        &self,
+        py: Python,
+        desired_distances: Vec<f64>,
+        tolerance: Option<f64>,
+    ) -> PyResult<BestEfforts> {
+        let activity = &self.activity;
+        let results = py.allow_threads(|| activity.best_efforts(&desired_distances, tolerance));
+        let (sections, errors) = split_section_results(py, results);
+        let sections = sections
+            .into_iter()
+            .map(|section| section.map(|s| Py::new(py, PyFastestSection::from(s))))
+            .map(Option::transpose)
+            .collect::<PyResult<_>>()?;
+        Ok((sections, errors))
+    }
+
+    fn summary(slf: PyRef<Self>) -> PyResult<Py<PyDict>> {
+        let py = slf.py();
+        let activity = &slf.activity;
+        let summary = py.allow_threads(|| activity.summary());
+        let dict = PyDict::new(py);
+        dict.set_item("records", summary.records)?;
+        dict.set_item("start_time", summary.start_time)?;
+        dict.set_item("end_time", summary.end_time)?;
+        dict.set_item("duration", summary.duration)?;
+        dict.set_item("distance", summary.distance)?;
+        dict.set_item("average_velocity", summary.average_velocity)?;
+        dict.set_item("elevation_gain", summary.elevation_gain)?;
+        dict.set_item("elevation_loss", summary.elevation_loss)?;
+        dict.set_item("calories", summary.calories)?;
+        Ok(dict.into())
+    }
+
+    fn __len__(&self) -> usize {
+        self.activity.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Activity(records={})", self.activity.len())
+    }
+}
+
+#[pyfunction]
+fn quality_report(
+    py: Python,
+    activity: PyRef<PyActivity>,
+    desired_distance: Option<f64>,
+) -> PyResult<Py<PyDict>> {
+    let desired_distance = desired_distance.unwrap_or(quality::DEFAULT_REPORT_DISTANCE);
+    if desired_distance.is_nan() || desired_distance <= 0.0 {
+        return Err(errors::InputDataError::InvalidDesiredDistance.into());
+    }
+    let activity = &activity.activity;
+    let report = py.allow_threads(|| quality::quality_report(activity));
+    let missing = PyDict::new(py);
+    missing.set_item("times", report.missing.times)?;
+    missing.set_item("coordinates", report.missing.coordinates)?;
+    missing.set_item("altitudes", report.missing.altitudes)?;
+    missing.set_item("distances", report.missing.distances)?;
+    let intervals = PyDict::new(py);
+    intervals.set_item("min", report.sampling_intervals.min)?;
+    intervals.set_item("median", report.sampling_intervals.median)?;
+    intervals.set_item("mean", report.sampling_intervals.mean)?;
+    intervals.set_item("max", report.sampling_intervals.max)?;
+    let dict = PyDict::new(py);
+    dict.set_item("records", report.records)?;
+    dict.set_item("missing", missing)?;
+    dict.set_item("sampling_intervals", intervals)?;
+    dict.set_item("time_issues", time_issues_to_dict(py, &report.time_issues)?)?;
+    dict.set_item("speed_spikes", &report.speed_spikes)?;
+    dict.set_item("altitude_spikes", &report.altitude_spikes)?;
+    dict.set_item("step_distance", report.step_distance)?;
+    dict.set_item(
+        "suggested_tolerance",
+        report.suggested_tolerance_for(desired_distance),
+    )?;
+    Ok(dict.into())
+}
+
+// awaitable variants of the functions above, registered in the `aio` submodule. The work is
+// spawned on the rayon thread pool and the result is handed back to the asyncio event loop of
+// the caller via `call_soon_threadsafe`, so the event loop is never blocked.
+
+#[pyfunction]
+fn set_future_result(future: &PyAny, value: PyObject, is_error: bool) -> PyResult<()> {
+    // the awaiting task might have been cancelled in the meantime
+    if future.call_method0("cancelled")?.is_true()? {
+        return Ok(());
+    }
+    if is_error {
+        future.call_method1("set_exception", (value,))?;
+    } else {
+        future.call_method1("set_result", (value,))?;
+    }
+    Ok(())
+}
+
+fn spawn_awaitable<T, F, C>(py: Python, job: F, convert: C) -> PyResult<PyObject>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, errors::InputDataError> + Send + 'static,
+    C: FnOnce(Python, T) -> PyResult<PyObject> + Send + 'static,
+{
+    let event_loop: PyObject = py
+        .import("asyncio")?
+        .call_method0("get_running_loop")?
+        .into();
+    let future = event_loop.call_method0(py, "create_future")?;
+    let set_result: PyObject = wrap_pyfunction!(set_future_result, py)?.into();
+    let awaitable = future.clone_ref(py);
+    rayon::spawn(move || {
+        let result = job();
+        Python::with_gil(|py| {
+            let (value, is_error) = match result.map_err(PyErr::from).and_then(|r| convert(py, r)) {
+                Ok(value) => (value, false),
+                Err(e) => (e.instance(py).into(), true),
+            };
+            // the event loop might be closed already, then there is nobody left to notify
+            let _ = event_loop.call_method1(
+                py,
+                "call_soon_threadsafe",
+                (set_result, future, value, is_error),
+            );
+        });
+    });
+    Ok(awaitable)
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(name = "find_fastest_section")]
+fn aio_find_fastest_section(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    coordinates: Coordinates,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    altitudes: Option<Series>,
+    smoothing: Option<String>,
+    fill: Option<String>,
+    moving_time: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = segment_options(
+        moving_time_options(
+            fill_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                fill,
+            )?,
+            moving_time,
+        )?,
+        segment_gap,
+    )?;
+    let (times, coordinates, altitudes) = (
+        times.into_vec(),
+        coordinates.into_vec()?,
+        altitudes.map(|altitudes| altitudes.into_vec()),
+    );
+    spawn_awaitable(
+        py,
+        move || {
+            velocity::find_fastest_section_with_options(
+                desired_distance,
+                coordinates,
+                times,
+                altitudes,
+                &options,
+            )
+        },
+        |py, result| Ok(Py::new(py, PyFastestSection::from(result))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(name = "find_fastest_section_in_fit")]
+fn aio_find_fastest_section_in_fit(
+    py: Python,
+    desired_distance: f64,
+    path_to_fit: String,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+    fill: Option<String>,
+    moving_time: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = segment_options(
+        moving_time_options(
+            fill_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                fill,
+            )?,
+            moving_time,
+        )?,
+        segment_gap,
+    )?;
+    spawn_awaitable(
+        py,
+        move || {
+            velocity::find_fastest_section_in_fit_with_options(
+                desired_distance,
+                &path_to_fit,
+                &options,
+            )
+        },
+        |py, result| Ok(Py::new(py, PyFastestSection::from(result))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(name = "find_best_climb_section")]
+fn aio_find_best_climb_section(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    coordinates: Coordinates,
+    altitudes: Series,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = segment_options(
+        fill_options(
+            elevation_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                elevation_smoothing,
+                gain_method,
+            )?,
+            fill,
+        )?,
+        segment_gap,
+    )?;
+    let (times, coordinates, altitudes) = (
+        times.into_vec(),
+        coordinates.into_vec()?,
+        altitudes.into_vec(),
+    );
+    spawn_awaitable(
+        py,
+        move || {
+            climb::find_best_climb_section_with_options(
+                desired_distance,
+                coordinates,
+                times,
+                altitudes,
+                &options,
+            )
+        },
+        |py, result| Ok(Py::new(py, PyClimbSection::from(result))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(name = "find_best_climb_section_in_fit")]
+fn aio_find_best_climb_section_in_fit(
+    py: Python,
+    desired_distance: f64,
+    path_to_fit: String,
+    tolerance: Option<f64>,
+    distance_model: Option<String>,
+    slope_distance: Option<bool>,
+    smoothing: Option<String>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = segment_options(
+        fill_options(
+            elevation_options(
+                finder_options(tolerance, distance_model, slope_distance, smoothing)?,
+                elevation_smoothing,
+                gain_method,
+            )?,
+            fill,
+        )?,
+        segment_gap,
+    )?;
+    spawn_awaitable(
+        py,
+        move || {
+            climb::find_best_climb_section_in_fit_with_options(
+                desired_distance,
+                &path_to_fit,
+                &options,
+            )
+        },
+        |py, result| Ok(Py::new(py, PyClimbSection::from(result))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(name = "find_fastest_section_from_distances")]
+fn aio_find_fastest_section_from_distances(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    distances: Series,
+    tolerance: Option<f64>,
+    altitudes: Option<Series>,
+    fill: Option<String>,
+    moving_time: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = segment_options(
+        moving_time_options(
+            fill_options(gem_finder::FinderOptions::with_tolerance(tolerance), fill)?,
+            moving_time,
+        )?,
+        segment_gap,
+    )?;
+    let (times, distances, altitudes) = (
+        times.into_vec(),
+        distances.into_vec(),
+        altitudes.map(|altitudes| altitudes.into_vec()),
+    );
+    spawn_awaitable(
+        py,
+        move || {
+            velocity::find_fastest_section_with_distances(
+                desired_distance,
+                distances,
+                times,
+                altitudes,
+                &options,
+            )
+        },
+        |py, result| Ok(Py::new(py, PyFastestSection::from(result))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(name = "find_best_climb_section_from_distances")]
+fn aio_find_best_climb_section_from_distances(
+    py: Python,
+    desired_distance: f64,
+    times: Series,
+    distances: Series,
+    altitudes: Series,
+    tolerance: Option<f64>,
+    elevation_smoothing: Option<String>,
+    gain_method: Option<String>,
+    fill: Option<String>,
+    segment_gap: Option<f64>,
+) -> PyResult<PyObject> {
+    let options = segment_options(
+        fill_options(
+            elevation_options(
+                gem_finder::FinderOptions::with_tolerance(tolerance),
+                elevation_smoothing,
+                gain_method,
+            )?,
+            fill,
+        )?,
+        segment_gap,
+    )?;
+    let (times, distances, altitudes) =
+        (times.into_vec(), distances.into_vec(), altitudes.into_vec());
+    spawn_awaitable(
+        py,
+        move || {
+            climb::find_best_climb_section_with_distances(
+                desired_distance,
+                distances,
+                times,
+                altitudes,
+                &options,
+            )
+        },
+        |py, result| Ok(Py::new(py, PyClimbSection::from(result))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[pyo3(name = "parse_fit_data")]
+fn aio_parse_fit_data(py: Python, path_to_fit: String) -> PyResult<PyObject> {
+    spawn_awaitable(
+        py,
+        move || fit_reader::parse_fit(&path_to_fit),
+        |py, fit_data| Ok(Py::new(py, PyFitData::from(fit_data))?.into_py(py)),
+    )
+}
+
+#[pyfunction]
+#[pyo3(name = "analyze_files")]
+fn aio_analyze_files(
+    py: Python,
+    paths: Vec<String>,
+    distances: Vec<f64>,
+    tolerance: Option<f64>,
+) -> PyResult<PyObject> {
+    spawn_awaitable(
+        py,
+        move || Ok((batch::analyze_files(&paths, &distances, tolerance), paths)),
+        |py, (results, paths)| Ok(to_py_file_analyses(py, paths, results)?.into_py(py)),
+    )
+}
+
+#[pymodule]
+fn sportgems(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_wrapped(wrap_pyfunction!(find_fastest_section))?;
+    m.add_wrapped(wrap_pyfunction!(find_fastest_section_in_fit))?;
+    m.add_wrapped(wrap_pyfunction!(find_best_climb_section))?;
+    m.add_wrapped(wrap_pyfunction!(find_best_climb_section_in_fit))?;
+    m.add_wrapped(wrap_pyfunction!(find_fastest_section_from_distances))?;
+    m.add_wrapped(wrap_pyfunction!(find_best_climb_section_from_distances))?;
+    m.add_wrapped(wrap_pyfunction!(total_ascent_descent))?;
+    m.add_wrapped(wrap_pyfunction!(detect_outliers))?;
+    m.add_wrapped(wrap_pyfunction!(remove_outliers))?;
+    m.add_wrapped(wrap_pyfunction!(remove_coordinate_outliers))?;
+    m.add_wrapped(wrap_pyfunction!(check_times))?;
+    m.add_wrapped(wrap_pyfunction!(repair_times))?;
+    m.add_wrapped(wrap_pyfunction!(split_segments))?;
+    m.add_wrapped(wrap_pyfunction!(quality_report))?;
+    m.add_wrapped(wrap_pyfunction!(parse_fit_data))?;
+    m.add_wrapped(wrap_pyfunction!(analyze_files))?;
+    m.add_class::<PyFastestSection>()?;
+    m.add_class::<PyClimbSection>()?;
+    m.add_class::<PyFitData>()?;
+    m.add_class::<PyFileAnalysis>()?;
+    m.add_class::<PyActivity>()?;
+    let aio = PyModule::new(_py, "aio")?;
+    aio.add_wrapped(wrap_pyfunction!(aio_find_fastest_section))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_find_fastest_section_in_fit))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_find_best_climb_section))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_find_best_climb_section_in_fit))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_find_fastest_section_from_distances))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_find_best_climb_section_from_distances))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_parse_fit_data))?;
+    aio.add_wrapped(wrap_pyfunction!(aio_analyze_files))?;
+    m.add_submodule(aio)?;
+    m.add(
+        "InconsistentLengthException",
+        _py.get_type::<errors::InconsistentLengthException>(),
+    )?;
+    m.add(
+        "TooFewDataPointsException",
+        _py.get_type::<errors::TooFewDataPointsException>(),
+    )?;
+    m.add(
+        "DistanceTooSmallException",
+        _py.get_type::<errors::DistanceTooSmallException>(),
+    )?;
+    m.add(
+        "NoSectionFoundException",
+        _py.get_type::<errors::NoSectionFoundException>(),
+    )?;
+    m.add(
+        "InvalidDesiredDistanceException",
+        _py.get_type::<errors::InvalidDesiredDistanceException>(),
+    )?;
+    m.add(
+        "InvalidFitFileException",
+        _py.get_type::<errors::InvalidFitFileException>(),
+    )?;
+    m.add(
+        "FileNotReadableException",
+        _py.get_type::<errors::FileNotReadableException>(),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,299 @@
+use crate::climb;
+#[cfg(feature = "serde")]
+use crate::dtypes::nan_as_null;
+use crate::gem_finder::{self, Activity};
+use crate::math::IsNaN;
+use crate::outliers::{self, OutlierFilter};
+use crate::timestamps;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// distance in meters the suggested tolerance of a report is computed for
+pub const DEFAULT_REPORT_DISTANCE: f64 = 1_000.0;
+// jumps of the speed between two records in m/s, e.g. from a gps jump
+pub const SPEED_SPIKE_FILTER: OutlierFilter = OutlierFilter::Hampel {
+    window: outliers::DEFAULT_HAMPEL_WINDOW,
+    threshold: outliers::DEFAULT_HAMPEL_THRESHOLD,
+    min_deviation: 2.0,
+};
+// share of the distances between two records the suggested tolerance covers
+const STEP_QUANTILE: f64 = 0.95;
+
+// number of missing (NaN) values per series in the records, None for series the activity does
+// not have
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MissingValues {
+    pub times: usize,
+    pub coordinates: Option<usize>,
+    pub altitudes: Option<usize>,
+    pub distances: Option<usize>,
+}
+
+// seconds between consecutive valid times, NaN if the times never increase
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SamplingIntervals {
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub min: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub median: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub mean: f64,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub max: f64,
+}
+
+// overview of the problems in the data of an activity, which let a search fail with
+// `NoSectionFound` or give odd results
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QualityReport {
+    // rows read from record messages, fit files also hold rows of e.g. events and laps
+    pub records: usize,
+    pub missing: MissingValues,
+    pub sampling_intervals: SamplingIntervals,
+    // duplicated and out of order times, clock resets and gaps longer than the segment gap
+    pub time_issues: timestamps::TimeIssues,
+    // records reached with a speed spike
+    pub speed_spikes: Vec<usize>,
+    // records with an altitude spike, including the spikes removed from fit files
+    pub altitude_spikes: Vec<usize>,
+    // distance in meters between two records, which 95% of the records stay below
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub step_distance: f64,
+    // tolerance for sections of `DEFAULT_REPORT_DISTANCE`
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null::value"))]
+    pub suggested_tolerance: f64,
+}
+
+impl QualityReport {
+    // smallest tolerance (rounded up to 0.1%), at which the search of `desired_distance` cannot
+    // step over the allowed distances, at least the default tolerance
+    pub fn suggested_tolerance_for(&self, desired_distance: f64) -> f64 {
+        let tolerance = self.step_distance / (2.0 * desired_distance);
+        if tolerance.is_nan() {
+            return gem_finder::DEFAULT_TOLERANCE;
+        }
+        ((tolerance * 1_000.0).ceil() / 1_000.0).max(gem_finder::DEFAULT_TOLERANCE)
+    }
+}
+
+pub fn quality_report(activity: &Activity) -> QualityReport {
+    let times = activity.times();
+    let time_issues = activity.time_issues();
+    let speeds = record_speeds(activity.distances(), times);
+    let speed_spikes = SPEED_SPIKE_FILTER.detect(&speeds, times);
+    let mut altitude_spikes = activity
+        .raw_altitudes()
+        .map(|altitudes| climb::FIT_ALTITUDE_OUTLIER_FILTER.detect(altitudes, times))
+        .unwrap_or_default();
+    for i in activity.altitude_outliers() {
+        altitude_spikes[*i] = true;
+    }
+    // rows of other messages in fit files may hold unrelated values in the altitude field
+    for (i, spike) in altitude_spikes.iter_mut().enumerate() {
+        *spike &= activity.is_record(i);
+    }
+    // windows never span the segment breaks, the spikes are no regular steps
+    let breaks = time_issues.segment_breaks();
+    let steps: Vec<f64> = activity
+        .distances()
+        .windows(2)
+        .enumerate()
+        .filter(|(i, _)| !speeds[i + 1].is_nan())
+        .filter(|(i, _)| !speed_spikes[i + 1] && !breaks.contains(&(i + 1)))
+        .map(|(_, pair)| pair[1] - pair[0])
+        .collect();
+    let mut report = QualityReport {
+        records: (0..activity.len())
+            .filter(|i| activity.is_record(*i))
+            .count(),
+        missing: MissingValues {
+            times: count_missing(activity, times),
+            coordinates: activity
+                .raw_coordinates()
+                .map(|coordinates| count_missing(activity, coordinates)),
+            // the removed spikes are reported as spikes
+            altitudes: activity.raw_altitudes().map(|altitudes| {
+                let outliers = activity.altitude_outliers();
+                (0..altitudes.len())
+                    .filter(|i| altitudes[*i].is_nan() && activity.is_record(*i))
+                    .filter(|i| outliers.binary_search(i).is_err())
+                    .count()
+            }),
+            distances: activity
+                .device_distances()
+                .map(|distances| count_missing(activity, distances)),
+        },
+        sampling_intervals: sampling_intervals(times),
+        time_issues,
+        speed_spikes: indexes(&speed_spikes),
+        altitude_spikes: indexes(&altitude_spikes),
+        step_distance: quantile(steps, STEP_QUANTILE),
+        suggested_tolerance: gem_finder::DEFAULT_TOLERANCE,
+    };
+    report.suggested_tolerance = report.suggested_tolerance_for(DEFAULT_REPORT_DISTANCE);
+    report
+}
+
+fn count_missing<T: IsNaN>(activity: &Activity, values: &[T]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(i, value)| value.nan() && activity.is_record(*i))
+        .count()
+}
+
+fn indexes(flags: &[bool]) -> Vec<usize> {
+    flags
+        .iter()
+        .enumerate()
+        .filter(|(_, flag)| **flag)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// speed in m/s each record is reached with from the last record with a valid time before, NaN
+// for the first record and records with missing, duplicated or earlier times
+pub fn record_speeds(distances: &[f64], times: &[f64]) -> Vec<f64> {
+    let mut last: Option<(f64, f64)> = None;
+    times
+        .iter()
+        .zip(distances)
+        .map(|(time, distance)| {
+            if time.is_nan() || distance.is_nan() {
+                return f64::NAN;
+            }
+            match last {
+                Some((last_time, _)) if *time <= last_time => f64::NAN,
+                Some((last_time, last_distance)) => {
+                    last = Some((*time, *distance));
+                    (distance - last_distance) / (time - last_time)
+                }
+                None => {
+                    last = Some((*time, *distance));
+                    f64::NAN
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn sampling_intervals(times: &[f64]) -> SamplingIntervals {
+    let valid: Vec<f64> = times.iter().copied().filter(|t| !t.is_nan()).collect();
+    let intervals: Vec<f64> = valid
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .filter(|interval| *interval > 0.0)
+        .collect();
+    SamplingIntervals {
+        min: intervals.iter().copied().fold(f64::NAN, f64::min),
+        median: outliers::median(&intervals).unwrap_or(f64::NAN),
+        mean: intervals.iter().sum::<f64>() / intervals.len() as f64,
+        max: intervals.iter().copied().fold(f64::NAN, f64::max),
+    }
+}
+
+// nearest rank quantile, NaN without values
+fn quantile(mut values: Vec<f64>, quantile: f64) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let rank = (quantile * values.len() as f64).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+#[cfg(test)]
+mod test_quality {
+    use super::*;
+    use crate::synthetic;
+
+    #[test]
+    fn test_quality_report() {
+        let mut fit_data = synthetic::generate_activity(1_200);
+        fit_data.altitudes[100] += 300.;
+        fit_data.coordinates[200] = (f64::NAN, f64::NAN);
+        fit_data.coordinates[300].0 += 0.01;
+        for time in &mut fit_data.times[600..] {
+            *time += 600.;
+        }
+        let clean = Activity::from_fit_data(synthetic::generate_activity(1_200)).unwrap();
+        let activity = Activity::new(
+            fit_data.times.clone(),
+            fit_data.coordinates.clone(),
+            Some(fit_data.altitudes.clone()),
+        )
+        .unwrap();
+        let report = quality_report(&activity);
+        assert_eq!(report.records, 1_200);
+        assert_eq!(
+            report.missing,
+            MissingValues {
+                times: 0,
+                coordinates: Some(1),
+                altitudes: Some(0),
+                distances: None,
+            }
+        );
+        assert_eq!(report.sampling_intervals.min, 1.);
+        assert_eq!(report.sampling_intervals.median, 1.);
+        assert_eq!(report.sampling_intervals.max, 601.);
+        assert_eq!(report.time_issues.gaps, vec![600]);
+        // the gps jump is reached and left with a spike
+        assert_eq!(report.speed_spikes, vec![300, 301]);
+        assert_eq!(report.altitude_spikes, vec![100]);
+        // the spikes and the gap do not change the steps
+        assert!((report.step_distance - quality_report(&clean).step_distance).abs() < 1e-6);
+        assert_eq!(report.suggested_tolerance, gem_finder::DEFAULT_TOLERANCE);
+        // spikes removed from fit files are still reported as spikes, not as missing
+        let report = quality_report(&Activity::from_fit_data(fit_data.clone()).unwrap());
+        assert_eq!(report.missing.altitudes, Some(0));
+        assert_eq!(report.altitude_spikes, vec![100]);
+        // only the records count, not the rows of other messages
+        fit_data.record_messages = vec![true; 1_200];
+        fit_data.record_messages[0] = false;
+        fit_data.times[0] = f64::NAN;
+        let report = quality_report(&Activity::from_fit_data(fit_data).unwrap());
+        assert_eq!(report.records, 1_199);
+        assert_eq!(report.missing.times, 0);
+        assert_eq!(report.missing.coordinates, Some(1));
+    }
+
+    #[test]
+    fn test_suggested_tolerance() {
+        // a record every 30 seconds, which is about 95 m apart
+        let times: Vec<f64> = (0..200).map(|i| i as f64 * 30.).collect();
+        let distances: Vec<f64> = (0..200).map(|i| i as f64 * 95.).collect();
+        let activity = Activity::from_distances(times, distances, None).unwrap();
+        let report = quality_report(&activity);
+        assert_eq!(report.missing.coordinates, None);
+        assert_eq!(report.missing.distances, Some(0));
+        assert_eq!(report.sampling_intervals.mean, 30.);
+        assert_eq!(report.step_distance, 95.);
+        assert_eq!(report.suggested_tolerance, 0.048);
+        assert_eq!(report.suggested_tolerance_for(10_000.), 0.01);
+        // no section hits 1 km within the default tolerance
+        assert!(activity.fastest_section(1_000., None).is_err());
+        assert!(activity
+            .fastest_section(1_000., Some(report.suggested_tolerance))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_record_speeds() {
+        let nan = f64::NAN;
+        let speeds = record_speeds(&[0., 2., 4., 5., 8.], &[0., 1., 1., nan, 3.]);
+        assert!(speeds[0].is_nan() && speeds[2].is_nan() && speeds[3].is_nan());
+        assert_eq!(speeds[1], 2.);
+        assert_eq!(speeds[4], 3.);
+    }
+
+    #[test]
+    fn test_quantile() {
+        assert_eq!(quantile(vec![3., 1., 2., 4.], 0.5), 2.);
+        assert_eq!(quantile(vec![3., 1., 2., 4.], 0.95), 4.);
+        assert!(quantile(vec![], 0.95).is_nan());
+    }
+}
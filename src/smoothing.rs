@@ -0,0 +1,580 @@
+use crate::math;
+use std::borrow::Cow;
+
+pub const DEFAULT_MOVING_AVERAGE_WINDOW: usize = 5;
+pub const DEFAULT_SAVITZKY_GOLAY_WINDOW: usize = 7;
+pub const DEFAULT_SAVITZKY_GOLAY_ORDER: usize = 2;
+// standard deviation of the acceleration in m/s^2 and of the gps position in m
+pub const DEFAULT_KALMAN_PROCESS_NOISE: f64 = 1.0;
+pub const DEFAULT_KALMAN_MEASUREMENT_NOISE: f64 = 5.0;
+
+const METERS_PER_DEGREE: f64 = math::MEAN_EARTH_RADIUS * math::PI / 180.0;
+
+// filter applied to the coordinates before the distances between them are computed, jittery gps
+// tracks e.g. between tall buildings otherwise inflate the distances
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Smoothing {
+    #[default]
+    None,
+    // centered moving average over `window` coordinates
+    MovingAverage {
+        window: usize,
+    },
+    // local polynomial of degree `order` fitted to `window` coordinates
+    SavitzkyGolay {
+        window: usize,
+        order: usize,
+    },
+    // constant velocity kalman filter over the positions in meter and the times
+    Kalman {
+        process_noise: f64,
+        measurement_noise: f64,
+    },
+}
+
+impl Smoothing {
+    pub const NAMES: [&'static str; 4] = ["none", "moving-average", "savitzky-golay", "kalman"];
+}
+
+// filters are given by name with optional parameters separated by colons, e.g. `moving-average:9`,
+// `savitzky-golay:7:2` or `kalman:1.0:5.0`
+impl std::str::FromStr for Smoothing {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Smoothing, String> {
+        let mut parts = spec.split(':');
+        let name = parts.next().unwrap_or_default();
+        let params: Vec<&str> = parts.collect();
+        let invalid = || format!("Invalid parameters for smoothing '{}'.", spec);
+        let param = |i: usize, default: f64| -> Result<f64, String> {
+            match params.get(i) {
+                Some(param) => param.parse::<f64>().map_err(|_| invalid()),
+                None => Ok(default),
+            }
+        };
+        // windows and orders are counts, fractions like `moving-average:4.5` are rejected instead
+        // of being truncated
+        let count = |i: usize, default: usize| -> Result<usize, String> {
+            match params.get(i) {
+                Some(param) => param.parse::<usize>().map_err(|_| invalid()),
+                None => Ok(default),
+            }
+        };
+        let smoothing = match name {
+            "none" if params.is_empty() => Smoothing::None,
+            "moving-average" if params.len() <= 1 => Smoothing::MovingAverage {
+                window: count(0, DEFAULT_MOVING_AVERAGE_WINDOW)?,
+            },
+            "savitzky-golay" if params.len() <= 2 => Smoothing::SavitzkyGolay {
+                window: count(0, DEFAULT_SAVITZKY_GOLAY_WINDOW)?,
+                order: count(1, DEFAULT_SAVITZKY_GOLAY_ORDER)?,
+            },
+            "kalman" if params.len() <= 2 => Smoothing::Kalman {
+                process_noise: param(0, DEFAULT_KALMAN_PROCESS_NOISE)?,
+                measurement_noise: param(1, DEFAULT_KALMAN_MEASUREMENT_NOISE)?,
+            },
+            _ if Smoothing::NAMES.contains(&name) => return Err(invalid()),
+            _ => {
+                return Err(format!(
+                    "Unknown smoothing '{}', expected one of: {}.",
+                    spec,
+                    Smoothing::NAMES.join(", ")
+                ))
+            }
+        };
+        smoothing.validate()?;
+        Ok(smoothing)
+    }
+}
+
+// the spec string accepted by `from_str`, including all parameters
+impl std::fmt::Display for Smoothing {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Smoothing::None => write!(f, "none"),
+            Smoothing::MovingAverage { window } => write!(f, "moving-average:{}", window),
+            Smoothing::SavitzkyGolay { window, order } => {
+                write!(f, "savitzky-golay:{}:{}", window, order)
+            }
+            Smoothing::Kalman {
+                process_noise,
+                measurement_noise,
+            } => write!(f, "kalman:{}:{}", process_noise, measurement_noise),
+        }
+    }
+}
+
+impl Smoothing {
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = match *self {
+            Smoothing::None => true,
+            Smoothing::MovingAverage { window } => window >= 1,
+            Smoothing::SavitzkyGolay { window, order } => window % 2 == 1 && order < window,
+            Smoothing::Kalman {
+                process_noise,
+                measurement_noise,
+            } => process_noise > 0.0 && measurement_noise > 0.0,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("Invalid parameters for smoothing {:?}.", self))
+        }
+    }
+}
+
+// missing coordinates are kept, the runs of valid coordinates in between are smoothed on their own,
+// so a gap left by the fill strategy does not spread into its neighbours
+pub fn smooth_coordinates<'a>(
+    coordinates: &'a [(f64, f64)],
+    times: &[f64],
+    smoothing: Smoothing,
+) -> Cow<'a, [(f64, f64)]> {
+    match smoothing {
+        Smoothing::None => Cow::Borrowed(coordinates),
+        _ => Cow::Owned(smooth_runs(coordinates, times, |coordinates, times| {
+            smooth_valid_coordinates(coordinates, times, smoothing)
+        })),
+    }
+}
+
+fn smooth_valid_coordinates(
+    coordinates: &[(f64, f64)],
+    times: &[f64],
+    smoothing: Smoothing,
+) -> Vec<(f64, f64)> {
+    let (lats, lons): (Vec<f64>, Vec<f64>) = coordinates.iter().copied().unzip();
+    let (lats, lons) = match smoothing {
+        Smoothing::None => return coordinates.to_vec(),
+        Smoothing::MovingAverage { window } => {
+            (moving_average(&lats, window), moving_average(&lons, window))
+        }
+        Smoothing::SavitzkyGolay { window, order } => (
+            savitzky_golay(&lats, window, order),
+            savitzky_golay(&lons, window, order),
+        ),
+        Smoothing::Kalman {
+            process_noise,
+            measurement_noise,
+        } => return kalman(coordinates, times, process_noise, measurement_noise),
+    };
+    lats.into_iter().zip(lons).collect()
+}
+
+// smoothing of a single series like the altitudes, the kalman filter treats the values as
+// positions in meter. Missing values are kept like in `smooth_coordinates`.
+pub fn smooth_values<'a>(values: &'a [f64], times: &[f64], smoothing: Smoothing) -> Cow<'a, [f64]> {
+    match smoothing {
+        Smoothing::None => Cow::Borrowed(values),
+        _ => Cow::Owned(smooth_runs(values, times, |values, times| {
+            smooth_valid_values(values, times, smoothing)
+        })),
+    }
+}
+
+fn smooth_valid_values(values: &[f64], times: &[f64], smoothing: Smoothing) -> Vec<f64> {
+    match smoothing {
+        Smoothing::None => values.to_vec(),
+        Smoothing::MovingAverage { window } => moving_average(values, window),
+        Smoothing::SavitzkyGolay { window, order } => savitzky_golay(values, window, order),
+        Smoothing::Kalman {
+            process_noise,
+            measurement_noise,
+        } => kalman_axis(values, &time_steps(times), process_noise, measurement_noise),
+    }
+}
+
+// applies the filter to each run of valid values, the times are expected to be as long as the
+// values
+fn smooth_runs<T: math::IsNaN + Copy>(
+    values: &[T],
+    times: &[f64],
+    filter: impl Fn(&[T], &[f64]) -> Vec<T>,
+) -> Vec<T> {
+    let mut smoothed = values.to_vec();
+    let mut start = 0;
+    while start < values.len() {
+        if values[start].nan() {
+            start += 1;
+            continue;
+        }
+        let end = (start..values.len())
+            .find(|&i| values[i].nan())
+            .unwrap_or(values.len());
+        smoothed[start..end].copy_from_slice(&filter(&values[start..end], &times[start..end]));
+        start = end;
+    }
+    smoothed
+}
+
+// centered moving average, the window shrinks towards both ends
+pub fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let half = window / 2;
+    (0..values.len())
+        .map(|i| {
+            let start = i.saturating_sub(half);
+            let end = (i + half + 1).min(values.len());
+            values[start..end].iter().sum::<f64>() / (end - start) as f64
+        })
+        .collect()
+}
+
+// savitzky-golay filter, the first and last values are taken from the polynomial fitted to the
+// first and last window
+pub fn savitzky_golay(values: &[f64], window: usize, order: usize) -> Vec<f64> {
+    let n = values.len();
+    if window > n || order >= window {
+        return values.to_vec();
+    }
+    let half = window / 2;
+    // weights for each position of the smoothed value within the window
+    let weights: Vec<Vec<f64>> = (0..window)
+        .map(|position| savitzky_golay_weights(window, order, position))
+        .collect();
+    (0..n)
+        .map(|i| {
+            let start = i.saturating_sub(half).min(n - window);
+            weights[i - start]
+                .iter()
+                .zip(&values[start..start + window])
+                .map(|(weight, value)| weight * value)
+                .sum()
+        })
+        .collect()
+}
+
+// least squares fit of a polynomial to the window evaluated at `position`, which is linear in the
+// values of the window: c0 = e0^T (A^T A)^-1 A^T y with A_jp = (j - position)^p
+fn savitzky_golay_weights(window: usize, order: usize, position: usize) -> Vec<f64> {
+    let size = order + 1;
+    let powers = |j: usize| -> Vec<f64> {
+        let x = j as f64 - position as f64;
+        (0..size).map(|p| x.powi(p as i32)).collect()
+    };
+    let mut normal = vec![vec![0.0; size]; size];
+    for j in 0..window {
+        let row = powers(j);
+        for a in 0..size {
+            for b in 0..size {
+                normal[a][b] += row[a] * row[b];
+            }
+        }
+    }
+    let mut unit = vec![0.0; size];
+    unit[0] = 1.0;
+    let u = solve(normal, unit);
+    (0..window)
+        .map(|j| powers(j).iter().zip(&u).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+// gaussian elimination with partial pivoting, the matrix is small and regular
+fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Vec<f64> {
+    let size = rhs.len();
+    for col in 0..size {
+        let pivot = (col..size)
+            .max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))
+            .unwrap();
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+        for row in col + 1..size {
+            let factor = matrix[row][col] / matrix[col][col];
+            let pivot_row = matrix[col].clone();
+            for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row).skip(col) {
+                *value -= factor * pivot_value;
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+    let mut solution = vec![0.0; size];
+    for row in (0..size).rev() {
+        let sum: f64 = (row + 1..size).map(|k| matrix[row][k] * solution[k]).sum();
+        solution[row] = (rhs[row] - sum) / matrix[row][row];
+    }
+    solution
+}
+
+type State = [f64; 2];
+type Covariance = [[f64; 2]; 2];
+
+// state transition of the constant velocity model
+fn predict(
+    state: State,
+    covariance: Covariance,
+    dt: f64,
+    process_noise: f64,
+) -> (State, Covariance) {
+    let [[p00, p01], [p10, p11]] = covariance;
+    let q = process_noise.powi(2);
+    (
+        [state[0] + state[1] * dt, state[1]],
+        [
+            [
+                p00 + dt * (p10 + p01) + dt * dt * p11 + q * dt.powi(4) / 4.0,
+                p01 + dt * p11 + q * dt.powi(3) / 2.0,
+            ],
+            [p10 + dt * p11 + q * dt.powi(3) / 2.0, p11 + q * dt * dt],
+        ],
+    )
+}
+
+// one dimensional constant velocity kalman filter over the positions, followed by a backward
+// (rauch-tung-striebel) pass, so that the smoothed positions do not lag behind in curves
+fn kalman_axis(
+    positions: &[f64],
+    dts: &[f64],
+    process_noise: f64,
+    measurement_noise: f64,
+) -> Vec<f64> {
+    let r = measurement_noise.powi(2);
+    let mut predicted: Vec<(State, Covariance)> = Vec::with_capacity(positions.len());
+    let mut filtered: Vec<(State, Covariance)> = Vec::with_capacity(positions.len());
+    // the initial velocity is unknown
+    let mut state = [positions[0], 0.0];
+    let mut covariance = [[r, 0.0], [0.0, 1e6]];
+    for (i, position) in positions.iter().enumerate() {
+        if i > 0 {
+            (state, covariance) = predict(state, covariance, dts[i], process_noise);
+        }
+        predicted.push((state, covariance));
+        let [[p00, p01], [p10, p11]] = covariance;
+        let s = p00 + r;
+        let (k0, k1) = (p00 / s, p10 / s);
+        let residual = position - state[0];
+        state = [state[0] + k0 * residual, state[1] + k1 * residual];
+        covariance = [
+            [(1.0 - k0) * p00, (1.0 - k0) * p01],
+            [p10 - k1 * p00, p11 - k1 * p01],
+        ];
+        filtered.push((state, covariance));
+    }
+    let mut smoothed = vec![state; positions.len()];
+    for i in (0..positions.len() - 1).rev() {
+        let (state, [[p00, p01], [p10, p11]]) = filtered[i];
+        let (next_state, [[n00, n01], [n10, n11]]) = predicted[i + 1];
+        let dt = dts[i + 1];
+        // gain = P F^T P_next^-1
+        let (a00, a01, a10, a11) = (p00 + p01 * dt, p01, p10 + p11 * dt, p11);
+        let det = n00 * n11 - n01 * n10;
+        if det.abs() < f64::EPSILON {
+            smoothed[i] = state;
+            continue;
+        }
+        let (i00, i01, i10, i11) = (n11 / det, -n01 / det, -n10 / det, n00 / det);
+        let gain = [
+            [a00 * i00 + a01 * i10, a00 * i01 + a01 * i11],
+            [a10 * i00 + a11 * i10, a10 * i01 + a11 * i11],
+        ];
+        let diff = [
+            smoothed[i + 1][0] - next_state[0],
+            smoothed[i + 1][1] - next_state[1],
+        ];
+        smoothed[i] = [
+            state[0] + gain[0][0] * diff[0] + gain[0][1] * diff[1],
+            state[1] + gain[1][0] * diff[0] + gain[1][1] * diff[1],
+        ];
+    }
+    smoothed.iter().map(|state| state[0]).collect()
+}
+
+// seconds since the previous record, missing or non increasing times give a step of zero
+fn time_steps(times: &[f64]) -> Vec<f64> {
+    (0..times.len())
+        .map(|i| {
+            let dt = if i > 0 { times[i] - times[i - 1] } else { 0.0 };
+            if dt > 0.0 {
+                dt
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+// the coordinates are projected to meters around the first coordinate, where both axes are
+// smoothed independently. Missing or non increasing times skip the prediction step.
+pub fn kalman(
+    coordinates: &[(f64, f64)],
+    times: &[f64],
+    process_noise: f64,
+    measurement_noise: f64,
+) -> Vec<(f64, f64)> {
+    let origin = match coordinates.first() {
+        Some(origin) => *origin,
+        None => return vec![],
+    };
+    let meters_per_degree_lon = METERS_PER_DEGREE * math::to_rad(origin.0).cos();
+    let dts = time_steps(times);
+    let north: Vec<f64> = coordinates
+        .iter()
+        .map(|(lat, _)| (lat - origin.0) * METERS_PER_DEGREE)
+        .collect();
+    let east: Vec<f64> = coordinates
+        .iter()
+        .map(|(_, lon)| (lon - origin.1) * meters_per_degree_lon)
+        .collect();
+    let north = kalman_axis(&north, &dts, process_noise, measurement_noise);
+    let east = kalman_axis(&east, &dts, process_noise, measurement_noise);
+    north
+        .iter()
+        .zip(&east)
+        .map(|(y, x)| {
+            (
+                origin.0 + y / METERS_PER_DEGREE,
+                origin.1 + x / meters_per_degree_lon,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_smoothing {
+    use super::*;
+    use crate::gem_finder;
+
+    // straight track heading east with 3 m/s and alternating gps errors of 4 m to the north
+    fn jittery_track() -> (Vec<(f64, f64)>, Vec<f64>) {
+        let coordinates = (0..200)
+            .map(|i| {
+                let jitter = if i % 2 == 0 { 4.0 } else { -4.0 };
+                (
+                    48.0 + jitter / METERS_PER_DEGREE,
+                    8.0 + i as f64 * 3.0 / (METERS_PER_DEGREE * math::to_rad(48.0).cos()),
+                )
+            })
+            .collect();
+        let times = (0..200).map(|i| i as f64).collect();
+        (coordinates, times)
+    }
+
+    #[test]
+    fn test_moving_average() {
+        assert_eq!(
+            moving_average(&[1., 2., 6., 2., 1.], 3),
+            vec![1.5, 3., 10. / 3., 3., 1.5]
+        );
+        assert_eq!(moving_average(&[1., 2.], 1), vec![1., 2.]);
+    }
+
+    #[test]
+    fn test_savitzky_golay() {
+        // polynomials up to the order of the filter are not changed
+        let quadratic: Vec<f64> = (0..20).map(|i| (i * i) as f64 - 3. * i as f64).collect();
+        let smoothed = savitzky_golay(&quadratic, 7, 2);
+        for (a, b) in smoothed.iter().zip(&quadratic) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        // well known weights of the 5 point quadratic filter: (-3, 12, 17, 12, -3) / 35
+        let weights = savitzky_golay_weights(5, 2, 2);
+        for (weight, expected) in weights.iter().zip([-3., 12., 17., 12., -3.]) {
+            assert!((weight - expected / 35.).abs() < 1e-12);
+        }
+        // too short to be filtered
+        assert_eq!(savitzky_golay(&[1., 5.], 7, 2), vec![1., 5.]);
+    }
+
+    #[test]
+    fn test_smoothing_reduces_distance_of_jittery_track() {
+        let (coordinates, times) = jittery_track();
+        let raw = *gem_finder::cumulative_distances(&coordinates, Default::default())
+            .last()
+            .unwrap();
+        // the true length of the track is 199 * 3 m
+        assert!(raw > 1_000.);
+        for smoothing in ["moving-average", "savitzky-golay:9:2", "kalman"] {
+            let smoothing: Smoothing = smoothing.parse().unwrap();
+            let smoothed = smooth_coordinates(&coordinates, &times, smoothing);
+            assert_eq!(smoothed.len(), coordinates.len());
+            let distance = *gem_finder::cumulative_distances(&smoothed, Default::default())
+                .last()
+                .unwrap();
+            assert!(distance < 0.8 * raw, "{:?}: {}", smoothing, distance);
+            assert!(distance > 0.9 * 597., "{:?}: {}", smoothing, distance);
+        }
+        assert!(matches!(
+            smooth_coordinates(&coordinates, &times, Smoothing::None),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_kalman_follows_straight_track() {
+        let coordinates: Vec<(f64, f64)> =
+            (0..100).map(|i| (48.0, 8.0 + i as f64 * 1e-4)).collect();
+        let times: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let smoothed = kalman(&coordinates, &times, 1.0, 5.0);
+        let (lat, lon) = smoothed[99];
+        assert!((lat - 48.0).abs() < 1e-9);
+        assert!((lon - coordinates[99].1).abs() * METERS_PER_DEGREE < 1.0);
+    }
+
+    #[test]
+    fn test_smooth_values() {
+        let values = [100., 104., 100., 104., 100., 104., 100., 104., 100.];
+        let times: Vec<f64> = (0..9).map(|i| i as f64).collect();
+        assert_eq!(
+            smooth_values(&values, &times, Smoothing::None).as_ref(),
+            values
+        );
+        for smoothing in ["moving-average:3", "savitzky-golay:5:2", "kalman"] {
+            let smoothed = smooth_values(&values, &times, smoothing.parse().unwrap());
+            assert_eq!(smoothed.len(), values.len());
+            let spread = smoothed[2..7]
+                .iter()
+                .fold(0_f64, |spread, value| spread.max((value - 102.).abs()));
+            assert!(spread < 2., "{}: {:?}", smoothing, smoothed);
+        }
+        assert!(smooth_values(&[], &[], Smoothing::default()).is_empty());
+        // missing values are kept, the values around them are smoothed on their own
+        let values = [100., 104., f64::NAN, 100., 104.];
+        let smoothed = smooth_values(&values, &times[..5], "moving-average:3".parse().unwrap());
+        assert_eq!(smoothed[..2], [102., 102.]);
+        assert!(smoothed[2].is_nan());
+        assert_eq!(smoothed[3..], [102., 102.]);
+    }
+
+    #[test]
+    fn test_smoothing_from_str() {
+        assert_eq!("none".parse(), Ok(Smoothing::None));
+        assert_eq!(
+            "moving-average".parse(),
+            Ok(Smoothing::MovingAverage { window: 5 })
+        );
+        assert_eq!(
+            "savitzky-golay:9".parse(),
+            Ok(Smoothing::SavitzkyGolay {
+                window: 9,
+                order: 2
+            })
+        );
+        assert_eq!(
+            "kalman:0.5:3".parse(),
+            Ok(Smoothing::Kalman {
+                process_noise: 0.5,
+                measurement_noise: 3.0
+            })
+        );
+        for spec in [
+            "none",
+            "moving-average:9",
+            "savitzky-golay:7:2",
+            "kalman:0.5:3",
+        ] {
+            assert_eq!(spec.parse::<Smoothing>().unwrap().to_string(), spec);
+        }
+        assert!("savitzky-golay:8:2".parse::<Smoothing>().is_err());
+        assert!("savitzky-golay:5:5".parse::<Smoothing>().is_err());
+        assert!("moving-average:x".parse::<Smoothing>().is_err());
+        assert!("moving-average:4.5".parse::<Smoothing>().is_err());
+        assert!("savitzky-golay:7:2.5".parse::<Smoothing>().is_err());
+        assert!("kalman:1:2:3".parse::<Smoothing>().is_err());
+        assert_eq!(
+            "lowess".parse::<Smoothing>(),
+            Err(
+                "Unknown smoothing 'lowess', expected one of: none, moving-average, \
+                 savitzky-golay, kalman."
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use crate::fit_reader::FitData;
+use crate::math;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// unix timestamp of 2020-09-13, used as start time of all synthetic activities
+pub const START_TIME: f64 = 1_600_000_000.0;
+pub const START_COORDINATE: (f64, f64) = (49.4, 8.7);
+pub const START_ALTITUDE: f64 = 250.0;
+
+// seconds between the unix epoch and the fit epoch (1989-12-31 00:00:00 UTC)
+const FIT_EPOCH_OFFSET: f64 = 631_065_600.0;
+const METERS_PER_DEGREE: f64 = 111_320.0;
+const CRC_TABLE: [u16; 16] = [
+    0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800, 0xB401,
+    0x5000, 0x9C01, 0x8801, 0x4400,
+];
+
+// speed in m/s at second t, oscillating between 2 and 4 m/s with a period of 10 minutes
+pub fn speed_at(t: f64) -> f64 {
+    3.0 + (2.0 * math::PI * t / 600.0).sin()
+}
+
+// altitude in meter at second t, oscillating by +-50 m with a period of 30 minutes
+pub fn altitude_at(t: f64) -> f64 {
+    START_ALTITUDE + 50.0 * (2.0 * math::PI * t / 1800.0).sin()
+}
+
+// generate a synthetic 1 Hz activity of the given duration in seconds. The track runs in circles
+// (one full turn per hour) so even very long activities stay in a small area.
+pub fn generate_activity(duration: u32) -> FitData {
+    let mut fit_data = FitData {
+        calories: (duration / 6) as u16,
+        times: vec![],
+        coordinates: vec![],
+        altitudes: vec![],
+        distances: vec![],
+        timer_pauses: vec![],
+        record_messages: vec![],
+    };
+    let (mut lat, mut lon) = START_COORDINATE;
+    let mut distance = 0.0;
+    for i in 0..duration {
+        let t = i as f64;
+        fit_data.times.push(START_TIME + t);
+        fit_data.coordinates.push((lat, lon));
+        fit_data.altitudes.push(altitude_at(t));
+        fit_data.distances.push(distance);
+        let heading = 2.0 * math::PI * t / 3600.0;
+        let step = speed_at(t);
+        distance += step;
+        lat += step * heading.cos() / METERS_PER_DEGREE;
+        lon += step * heading.sin() / (METERS_PER_DEGREE * math::to_rad(lat).cos());
+    }
+    fit_data
+}
+
+fn crc(mut crc: u16, byte: u8) -> u16 {
+    let mut tmp = CRC_TABLE[(crc & 0xF) as usize];
+    crc = (crc >> 4) & 0x0FFF;
+    crc = crc ^ tmp ^ CRC_TABLE[(byte & 0xF) as usize];
+    tmp = CRC_TABLE[(crc & 0xF) as usize];
+    crc = (crc >> 4) & 0x0FFF;
+    crc ^ tmp ^ CRC_TABLE[((byte >> 4) & 0xF) as usize]
+}
+
+fn checksum(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |sum, byte| crc(sum, *byte))
+}
+
+fn to_semicircles(degree: f64) -> i32 {
+    if degree.is_nan() {
+        i32::MAX // invalid value
+    } else {
+        (degree * 2f64.powi(31) / 180.0).round() as i32
+    }
+}
+
+// definition message, fields are given as (field number, size, base type)
+fn push_definition(data: &mut Vec<u8>, local_num: u8, global_num: u16, fields: &[(u8, u8, u8)]) {
+    data.push(0x40 | local_num);
+    data.push(0); // reserved
+    data.push(0); // little endian
+    data.extend_from_slice(&global_num.to_le_bytes());
+    data.push(fields.len() as u8);
+    for (field_num, size, base_type) in fields {
+        data.extend_from_slice(&[*field_num, *size, *base_type]);
+    }
+}
+
+fn to_fit_timestamp(time: f64) -> u32 {
+    if time.is_nan() {
+        u32::MAX
+    } else {
+        (time - FIT_EPOCH_OFFSET) as u32
+    }
+}
+
+// timer event message, event type 4 stops and 0 starts the timer
+fn push_timer_event(data: &mut Vec<u8>, time: f64, event_type: u8) {
+    data.push(0x03);
+    data.extend_from_slice(&to_fit_timestamp(time).to_le_bytes());
+    data.extend_from_slice(&[0, event_type]);
+}
+
+// encode the given data as fit file, containing a file_id, one record per data point and a session
+// message. Timer pauses are written as timer events in between the records.
+pub fn to_fit_bytes(fit_data: &FitData) -> Vec<u8> {
+    let mut data: Vec<u8> = vec![];
+    // file_id with type = activity
+    push_definition(&mut data, 0, 0, &[(0, 1, 0x00)]);
+    data.extend_from_slice(&[0x00, 4]);
+    // records with timestamp, latitude, longitude, altitude and distance
+    push_definition(
+        &mut data,
+        1,
+        20,
+        &[
+            (253, 4, 0x86),
+            (0, 4, 0x85),
+            (1, 4, 0x85),
+            (2, 2, 0x84),
+            (5, 4, 0x86),
+        ],
+    );
+    // timer events with timestamp, event and event type
+    push_definition(
+        &mut data,
+        3,
+        21,
+        &[(253, 4, 0x86), (0, 1, 0x00), (1, 1, 0x00)],
+    );
+    let mut events = fit_data
+        .timer_pauses
+        .iter()
+        .flat_map(|(stop, start)| [(*stop, 4), (*start, 0)])
+        .peekable();
+    for i in 0..fit_data.times.len() {
+        while let Some((time, event_type)) = events.next_if(|(time, _)| *time <= fit_data.times[i])
+        {
+            push_timer_event(&mut data, time, event_type);
+        }
+        let timestamp = to_fit_timestamp(fit_data.times[i]);
+        let altitude = if fit_data.altitudes[i].is_nan() {
+            u16::MAX
+        } else {
+            (fit_data.altitudes[i] * 10.0).round() as u16 // turn meter into cm
+        };
+        let distance = match fit_data.distances.get(i) {
+            Some(distance) if !distance.is_nan() => (distance * 100.0).round() as u32, // turn meter into cm
+            _ => u32::MAX,
+        };
+        data.push(0x01);
+        data.extend_from_slice(&timestamp.to_le_bytes());
+        data.extend_from_slice(&to_semicircles(fit_data.coordinates[i].0).to_le_bytes());
+        data.extend_from_slice(&to_semicircles(fit_data.coordinates[i].1).to_le_bytes());
+        data.extend_from_slice(&altitude.to_le_bytes());
+        data.extend_from_slice(&distance.to_le_bytes());
+    }
+    for (time, event_type) in events {
+        push_timer_event(&mut data, time, event_type);
+    }
+    // session with total calories
+    push_definition(&mut data, 2, 18, &[(11, 2, 0x84)]);
+    data.push(0x02);
+    data.extend_from_slice(&fit_data.calories.to_le_bytes());
+
+    let mut header: Vec<u8> = vec![14, 0x10];
+    header.extend_from_slice(&2093u16.to_le_bytes()); // profile version
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(b".FIT");
+    header.extend_from_slice(&checksum(&header).to_le_bytes());
+
+    let mut bytes = header;
+    bytes.extend_from_slice(&data);
+    let file_crc = checksum(&bytes);
+    bytes.extend_from_slice(&file_crc.to_le_bytes());
+    bytes
+}
+
+pub fn write_fit(fit_data: &FitData, path: &Path) -> io::Result<()> {
+    fs::write(path, to_fit_bytes(fit_data))
+}
+
+#[cfg(test)]
+mod test_synthetic {
+    use super::*;
+    use crate::fit_reader;
+
+    #[test]
+    fn test_generate_activity() {
+        let activity = generate_activity(3600);
+        assert_eq!(activity.times.len(), 3600);
+        assert_eq!(activity.coordinates.len(), 3600);
+        assert_eq!(activity.altitudes.len(), 3600);
+        assert_eq!(activity.distances.len(), 3600);
+        assert_eq!(activity.times[0], START_TIME);
+        assert_eq!(activity.times[3599], START_TIME + 3599.);
+        assert_eq!(activity.coordinates[0], START_COORDINATE);
+        assert_eq!(activity.altitudes[0], START_ALTITUDE);
+        assert_eq!(activity.distances[0], 0.);
+        assert_eq!(activity.distances[1], speed_at(0.));
+        assert_eq!(activity.calories, 600);
+    }
+
+    #[test]
+    fn test_checksum() {
+        // checksum of a valid fit header including its own crc must be zero
+        let bytes = to_fit_bytes(&generate_activity(10));
+        assert_eq!(checksum(&bytes[..14]), 0);
+        assert_eq!(checksum(&bytes), 0);
+    }
+
+    #[test]
+    fn test_write_and_parse_fit() {
+        let activity = generate_activity(600);
+        let path = std::env::temp_dir().join("sportgems_test_write_and_parse_fit.fit");
+        write_fit(&activity, &path).unwrap();
+        let parsed = fit_reader::parse_fit(path.to_str().unwrap()).unwrap();
+        // first message is the file_id, which holds no time series values
+        assert!(parsed.times[0].is_nan());
+        for i in 0..activity.times.len() {
+            assert_eq!(parsed.times[i + 1], activity.times[i]);
+            assert!((parsed.coordinates[i + 1].0 - activity.coordinates[i].0).abs() < 1e-5);
+            assert!((parsed.coordinates[i + 1].1 - activity.coordinates[i].1).abs() < 1e-5);
+            assert!((parsed.altitudes[i + 1] - activity.altitudes[i]).abs() < 0.1);
+            assert!((parsed.distances[i + 1] - activity.distances[i]).abs() < 0.01);
+        }
+        assert_eq!(parsed.calories, activity.calories);
+        assert!(parsed.timer_pauses.is_empty());
+    }
+
+    #[test]
+    fn test_write_and_parse_timer_pauses() {
+        let mut activity = generate_activity(60);
+        activity.timer_pauses = vec![
+            (START_TIME + 10., START_TIME + 20.5),
+            (START_TIME + 30., START_TIME + 40.),
+        ];
+        let parsed = fit_reader::parse_fit_bytes(&to_fit_bytes(&activity)).unwrap();
+        assert_eq!(
+            parsed.timer_pauses,
+            vec![
+                (START_TIME + 10., START_TIME + 20.),
+                (START_TIME + 30., START_TIME + 40.)
+            ]
+        );
+        // each event is a row of its own like the file_id, the session and the crc at the end of
+        // the file, the records are unchanged
+        assert_eq!(parsed.times.len(), activity.times.len() + 7);
+        let records: Vec<f64> = parsed
+            .times
+            .iter()
+            .zip(&parsed.coordinates)
+            .filter(|(_, coordinate)| !coordinate.0.is_nan())
+            .map(|(time, _)| *time)
+            .collect();
+        assert_eq!(records, activity.times);
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+// gap in seconds between two records, which splits an activity into two segments
+pub const DEFAULT_SEGMENT_GAP: f64 = 300.0;
+
+// indexes of the records with irregular times, each compared with the last regular record before
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeIssues {
+    // same time as the record before
+    pub duplicates: Vec<usize>,
+    // single records earlier than the record before, while the following records continue after it
+    pub out_of_order: Vec<usize>,
+    // the times start over earlier from here on, e.g. after a clock reset or in merged files
+    pub clock_resets: Vec<usize>,
+    // more than the maximum gap after the record before
+    pub gaps: Vec<usize>,
+}
+
+impl TimeIssues {
+    pub fn is_empty(&self) -> bool {
+        self.duplicates.is_empty()
+            && self.out_of_order.is_empty()
+            && self.clock_resets.is_empty()
+            && self.gaps.is_empty()
+    }
+
+    // records the segments are split at, sorted
+    pub fn segment_breaks(&self) -> Vec<usize> {
+        let mut breaks = [self.clock_resets.as_slice(), self.gaps.as_slice()].concat();
+        breaks.sort_unstable();
+        breaks
+    }
+}
+
+// missing times (NaN) are skipped
+pub fn check_times(times: &[f64], max_gap: f64) -> TimeIssues {
+    let mut issues = TimeIssues::default();
+    let next_valid = |i: usize| times[i + 1..].iter().find(|time| !time.is_nan());
+    let mut last: Option<f64> = None;
+    for (i, time) in times.iter().enumerate() {
+        if time.is_nan() {
+            continue;
+        }
+        if let Some(last) = last {
+            if *time == last {
+                issues.duplicates.push(i);
+                continue;
+            }
+            if *time < last {
+                match next_valid(i) {
+                    Some(next) if *next <= last => issues.clock_resets.push(i),
+                    _ => {
+                        issues.out_of_order.push(i);
+                        continue;
+                    }
+                }
+            } else if time - last > max_gap {
+                issues.gaps.push(i);
+            }
+        }
+        last = Some(*time);
+    }
+    issues
+}
+
+// times with the duplicated and out of order records set to missing (NaN), which are skipped by
+// the search. Clock resets and gaps are kept, the search does not span them.
+pub fn repair_times(times: &[f64]) -> Vec<f64> {
+    let issues = check_times(times, f64::INFINITY);
+    let mut repaired = times.to_vec();
+    for i in issues.duplicates.into_iter().chain(issues.out_of_order) {
+        repaired[i] = f64::NAN;
+    }
+    repaired
+}
+
+// ranges of the records between clock resets and gaps longer than `max_gap` seconds, covering
+// all records
+pub fn split_segments(times: &[f64], max_gap: f64) -> Vec<Range<usize>> {
+    let mut start = 0;
+    let mut segments = vec![];
+    for end in check_times(times, max_gap).segment_breaks() {
+        segments.push(start..end);
+        start = end;
+    }
+    segments.push(start..times.len());
+    segments
+}
+
+#[cfg(test)]
+mod test_timestamps {
+    use super::*;
+
+    #[test]
+    fn test_check_times() {
+        let nan = f64::NAN;
+        let times = vec![
+            nan, 10., 11., 11., 12., 9., 13., 14., 500., 501., 2., 3., nan, 4.,
+        ];
+        let issues = check_times(&times, DEFAULT_SEGMENT_GAP);
+        assert_eq!(issues.duplicates, vec![3]);
+        assert_eq!(issues.out_of_order, vec![5]);
+        assert_eq!(issues.gaps, vec![8]);
+        assert_eq!(issues.clock_resets, vec![10]);
+        assert_eq!(issues.segment_breaks(), vec![8, 10]);
+        assert!(!issues.is_empty());
+        assert!(check_times(&[nan, 1., 2., nan, 3.], 1.).is_empty());
+        assert_eq!(check_times(&times, f64::INFINITY).gaps, Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_check_times_last_record() {
+        // a single earlier record at the end cannot be told apart from a clock reset
+        let issues = check_times(&[1., 2., 3., 0.], DEFAULT_SEGMENT_GAP);
+        assert_eq!(issues.out_of_order, vec![3]);
+        assert!(issues.clock_resets.is_empty());
+    }
+
+    #[test]
+    fn test_repair_times() {
+        let repaired = repair_times(&[10., 11., 11., 12., 9., 13., 2., 3.]);
+        assert!(repaired[2].is_nan());
+        assert!(repaired[4].is_nan());
+        assert_eq!(repaired[..2], [10., 11.]);
+        assert_eq!(repaired[5..], [13., 2., 3.]);
+    }
+
+    #[test]
+    fn test_split_segments() {
+        let times = vec![0., 1., 2., 600., 601., 5., 6.];
+        assert_eq!(
+            split_segments(&times, DEFAULT_SEGMENT_GAP),
+            vec![0..3, 3..5, 5..7]
+        );
+        assert_eq!(split_segments(&times, f64::INFINITY), vec![0..5, 5..7]);
+        assert_eq!(split_segments(&[], DEFAULT_SEGMENT_GAP), vec![0..0]);
+    }
+}
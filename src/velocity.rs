@@ -1,8 +1,9 @@
+use crate::climb;
+use crate::dtypes;
 use crate::errors;
 use crate::fit_reader;
 use crate::gem_finder;
 use crate::math;
-use crate::dtypes;
 
 fn get_velocity(
     section: &dtypes::WindowSection,
@@ -25,7 +26,11 @@ pub fn update_sections_max_velocity(
         window_sec.start as usize,
         window_sec.end as usize,
     );
-    window_sec.velocity = get_velocity(&window_sec, &window_sec.distance, &input_data.times);
+    window_sec.velocity = get_velocity(
+        window_sec,
+        &window_sec.distance,
+        input_data.velocity_times(),
+    );
     // update fastest_sec only in case the current distance is equal to the desired distance +- 1% and velocity is larger
     if gem_finder::distance_in_bounds(
         window_sec.distance,
@@ -45,39 +50,135 @@ pub fn find_fastest_section(
     times: Vec<f64>,
     tolerance: Option<f64>,
 ) -> Result<dtypes::TargetSection, errors::InputDataError> {
-    match gem_finder::InputData::new(desired_distance, coordinates, times, None, tolerance) {
-        Err(e) => Err(e),
-        Ok(mut finder) => {
-            math::fill_nans(&mut finder.coordinates);
-            finder.compute_vector_of_distances();
-            match finder.check_if_total_distance_suffice() {
-                Ok(_) => match finder.search_section(update_sections_max_velocity) {
-                    Ok(result) => return Ok(result),
-                    Err(e) => return Err(e),
-                },
-                Err(e) => return Err(e),
-            }
-        }
+    find_fastest_section_with_options(
+        desired_distance,
+        coordinates,
+        times,
+        None,
+        &gem_finder::FinderOptions::with_tolerance(tolerance),
+    )
+}
+
+// altitudes are optional, they are used for the slope distance and to describe the found section
+pub fn find_fastest_section_with_options(
+    desired_distance: f64,
+    coordinates: Vec<(f64, f64)>,
+    times: Vec<f64>,
+    altitudes: Option<Vec<f64>>,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let finder = gem_finder::InputData::with_options(
+        desired_distance,
+        coordinates,
+        times,
+        altitudes,
+        options,
+    )?;
+    search_fastest_section(finder)
+}
+
+pub fn find_fastest_section_with_distances(
+    desired_distance: f64,
+    distances: Vec<f64>,
+    times: Vec<f64>,
+    altitudes: Option<Vec<f64>>,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let finder = gem_finder::InputData::with_distances(
+        desired_distance,
+        distances,
+        times,
+        altitudes,
+        options,
+    )?;
+    search_fastest_section(finder)
+}
+
+// finders on device distances come with their distances, all others compute them from the
+// coordinates
+fn search_fastest_section(
+    mut finder: gem_finder::InputData,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    if finder.distances.values.is_empty() {
+        finder.fill_coordinates();
+        finder.fill_altitudes();
+        finder.compute_vector_of_distances();
+    } else {
+        finder.fill_altitudes();
     }
+    finder.smooth_altitudes();
+    finder.check_if_total_distance_suffice()?;
+    finder.split_segments();
+    finder.compute_moving_times();
+    finder.search_section(update_sections_max_velocity)
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 pub fn find_fastest_section_in_fit(
     desired_distance: f64,
     path_to_fit: &str,
     tolerance: Option<f64>,
 ) -> Result<dtypes::TargetSection, errors::InputDataError> {
-    let fit_data: fit_reader::FitData = fit_reader::parse_fit(path_to_fit);
-    find_fastest_section(
+    find_fastest_section_in_fit_with_options(
+        desired_distance,
+        path_to_fit,
+        &gem_finder::FinderOptions::with_tolerance(tolerance),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn find_fastest_section_in_fit_with_options(
+    desired_distance: f64,
+    path_to_fit: &str,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let fit_data: fit_reader::FitData = fit_reader::parse_fit(path_to_fit)?;
+    find_fastest_section_in_fit_data_with_options(desired_distance, &fit_data, options)
+}
+
+pub fn find_fastest_section_in_fit_data(
+    desired_distance: f64,
+    fit_data: &fit_reader::FitData,
+    tolerance: Option<f64>,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    find_fastest_section_in_fit_data_with_options(
         desired_distance,
-        fit_data.coordinates,
-        fit_data.times,
-        tolerance,
+        fit_data,
+        &gem_finder::FinderOptions::with_tolerance(tolerance),
     )
 }
 
+pub fn find_fastest_section_in_fit_data_with_options(
+    desired_distance: f64,
+    fit_data: &fit_reader::FitData,
+    options: &gem_finder::FinderOptions,
+) -> Result<dtypes::TargetSection, errors::InputDataError> {
+    let altitudes = climb::FIT_ALTITUDE_OUTLIER_FILTER.apply(&fit_data.altitudes, &fit_data.times);
+    let mut finder = if fit_data.use_device_distances() {
+        gem_finder::InputData::with_distances(
+            desired_distance,
+            fit_data.distances.clone(),
+            fit_data.times.clone(),
+            Some(altitudes),
+            options,
+        )?
+    } else {
+        gem_finder::InputData::with_options(
+            desired_distance,
+            fit_data.coordinates.clone(),
+            fit_data.times.clone(),
+            Some(altitudes),
+            options,
+        )?
+    };
+    finder.timer_pauses = fit_data.timer_pauses.clone();
+    search_fastest_section(finder)
+}
+
 #[cfg(test)]
 mod test_find_fastest_section {
     use super::*;
+    use crate::timestamps;
     #[test]
     fn test_find_fastest_section_edge_case_no_change_in_time() {
         // test case where coordinates are changing but time does not, this would lead to infinite velocity
@@ -179,6 +280,265 @@ mod test_find_fastest_section {
         assert_eq!(result.target_value.round(), 3.0);
     }
 
+    #[test]
+    fn test_find_fastest_section_in_fit_details() {
+        let result = find_fastest_section_in_fit(1_000., FIT_FILE, Some(0.01)).unwrap();
+        assert!((result.distance - 1_000.).abs() <= 10.);
+        assert_eq!(result.duration, result.end_time - result.start_time);
+        assert!((result.distance / result.duration - result.target_value).abs() < 1e-9);
+        assert!((result.pace() - 1_000. / 60. / result.target_value).abs() < 0.1);
+        let fit_data = fit_reader::parse_fit(FIT_FILE).unwrap();
+        assert_eq!(result.start_time, fit_data.times[628]);
+        assert_eq!(result.end_time, fit_data.times[719]);
+        assert_eq!(result.end_coordinate, fit_data.coordinates[719]);
+        assert!(result.elevation_gain.unwrap() > 0.);
+    }
+
+    #[test]
+    fn test_find_fastest_section_without_altitudes() {
+        let coordinates = vec![
+            (48.123, 9.35),
+            (48.123, 9.36),
+            (48.123, 9.37),
+            (48.123, 9.38),
+        ];
+        let times = vec![1608228953.8, 1608228954.8, 1608228955.8, 1608228956.8];
+        let result = find_fastest_section(1_000., coordinates, times, Some(0.5)).unwrap();
+        assert_eq!(result.start_coordinate, (48.123, 9.35));
+        assert_eq!(result.end_coordinate, (48.123, 9.38));
+        assert_eq!(result.start_time, 1608228953.8);
+        assert_eq!(result.end_time, 1608228956.8);
+        assert_eq!(result.elevation_gain, None);
+    }
+
+    #[test]
+    fn test_find_fastest_section_slope_distance() {
+        // steep climb with about 74 m on the map and the same altitude gain between the records
+        let coordinates: Vec<(f64, f64)> =
+            (0..30).map(|i| (48.0, 8.0 + i as f64 * 0.001)).collect();
+        let times: Vec<f64> = (0..30).map(|i| i as f64 * 10.).collect();
+        let altitudes: Vec<f64> = (0..30).map(|i| i as f64 * 74.5).collect();
+        let options = gem_finder::FinderOptions {
+            tolerance: 0.1,
+            slope_distance: true,
+            ..Default::default()
+        };
+        let flat =
+            find_fastest_section(1_000., coordinates.clone(), times.clone(), Some(0.1)).unwrap();
+        let slope = find_fastest_section_with_options(
+            1_000.,
+            coordinates,
+            times,
+            Some(altitudes),
+            &options,
+        )
+        .unwrap();
+        assert!((slope.distance - 1_000.).abs() <= 100.);
+        assert!(slope.end - slope.start < flat.end - flat.start);
+        assert!(slope.target_value > flat.target_value);
+    }
+
+    #[test]
+    fn test_find_fastest_section_linear_fill() {
+        // about 3 m/s with a gps dropout of 20 seconds, which the forward fill turns into a
+        // standstill followed by a jump of 60 m
+        let mut coordinates: Vec<(f64, f64)> =
+            (0..300).map(|i| (48.0, 8.0 + i as f64 * 4e-5)).collect();
+        for coordinate in &mut coordinates[140..160] {
+            *coordinate = (f64::NAN, f64::NAN);
+        }
+        let times: Vec<f64> = (0..300).map(|i| i as f64).collect();
+        let find = |fill: &str| {
+            let options = gem_finder::FinderOptions {
+                tolerance: 0.1,
+                fill: fill.parse().unwrap(),
+                ..Default::default()
+            };
+            find_fastest_section_with_options(
+                60.,
+                coordinates.clone(),
+                times.clone(),
+                None,
+                &options,
+            )
+            .unwrap()
+        };
+        assert!(find("forward").target_value > 20.);
+        assert!(find("linear").target_value < 3.);
+        // the gap is longer than the maximum gap of 10 seconds and stays missing, it adds no
+        // distance instead of a jump
+        assert!(find("linear:10").target_value < 3.);
+    }
+
+    #[test]
+    fn test_find_fastest_section_moving_time() {
+        // 3.3 m/s with a stop of one minute at a red light, then 2.8 m/s
+        let speed = |t: usize| match t {
+            100..=159 => 0.,
+            0..=259 => 3.3,
+            _ => 2.8,
+        };
+        let mut coordinates = vec![(48.0, 8.0)];
+        for t in 0..559 {
+            let (lat, lon) = coordinates[t];
+            coordinates.push((lat, lon + speed(t) / 74_488.));
+        }
+        let times: Vec<f64> = (0..560).map(|t| t as f64).collect();
+        let options = |moving_time: &str| gem_finder::FinderOptions {
+            tolerance: 0.05,
+            moving_time: moving_time.parse().unwrap(),
+            ..Default::default()
+        };
+        let find = |moving_time: &str| {
+            find_fastest_section_with_options(
+                500.,
+                coordinates.clone(),
+                times.clone(),
+                None,
+                &options(moving_time),
+            )
+            .unwrap()
+        };
+        // on the elapsed time the fastest section starts after the stop
+        let elapsed = find("elapsed");
+        assert!(elapsed.start >= 159);
+        // without timer pauses, the timer falls back to the elapsed time
+        assert_eq!(find("timer"), elapsed);
+        let stationary = find("stationary");
+        assert!(stationary.start < 100 && stationary.end > 160);
+        assert!((stationary.target_value - 3.3).abs() < 0.1);
+        assert!(stationary.target_value > elapsed.target_value);
+        // the duration of the section is still the elapsed time
+        assert!(stationary.duration > 200.);
+
+        // fit files of devices with auto pause hold no records while the timer is stopped
+        let records: Vec<usize> = (0..560).filter(|t| !(101..160).contains(t)).collect();
+        let fit_data = fit_reader::FitData {
+            calories: 0,
+            times: records.iter().map(|t| times[*t]).collect(),
+            coordinates: records.iter().map(|t| coordinates[*t]).collect(),
+            altitudes: vec![f64::NAN; records.len()],
+            distances: vec![],
+            timer_pauses: vec![(100., 160.)],
+            record_messages: vec![],
+        };
+        let find_in_fit = |moving_time: &str| {
+            find_fastest_section_in_fit_data_with_options(500., &fit_data, &options(moving_time))
+                .unwrap()
+        };
+        assert!(find_in_fit("elapsed").target_value < 3.1);
+        let timer = find_in_fit("timer");
+        assert!((timer.target_value - 3.3).abs() < 0.1);
+        assert_eq!(timer.target_value, find_in_fit("stationary").target_value);
+    }
+
+    #[test]
+    fn test_find_fastest_section_duplicate_time_at_boundary() {
+        // 3 m/s with 50 seconds at 6 m/s in between, which start with a duplicated time
+        let mut distance = 0.;
+        let coordinates: Vec<(f64, f64)> = (0..200)
+            .map(|i| {
+                distance += if (100..150).contains(&i) { 6. } else { 3. };
+                (48.0, 8.0 + distance / 74_488.)
+            })
+            .collect();
+        let mut times: Vec<f64> = (0..200).map(|i| i as f64).collect();
+        times[100] = times[99];
+        let options = gem_finder::FinderOptions {
+            tolerance: 0.05,
+            ..Default::default()
+        };
+        for moving_time in ["elapsed", "timer", "stationary"] {
+            let options = gem_finder::FinderOptions {
+                moving_time: moving_time.parse().unwrap(),
+                ..options.clone()
+            };
+            let section = find_fastest_section_with_options(
+                300.,
+                coordinates.clone(),
+                times.clone(),
+                None,
+                &options,
+            )
+            .unwrap();
+            assert!((98..=101).contains(&section.start), "{:?}", section);
+            assert!(!section.start_time.is_nan() && !section.end_time.is_nan());
+            assert_eq!(section.duration, section.end_time - section.start_time);
+            assert!(section.pace().is_finite());
+        }
+    }
+
+    #[test]
+    fn test_find_fastest_section_segments() {
+        // 3 m/s for 1000 seconds, a bus ride of 2 km in 400 seconds without records and another
+        // 1000 seconds at 3 m/s
+        let track = |bus_ride: f64| -> Vec<(f64, f64)> {
+            (0..2000)
+                .map(|i| {
+                    let distance = i as f64 * 3. + if i < 1000 { 0. } else { bus_ride };
+                    (48.0, 8.0 + distance / 74_488.)
+                })
+                .collect()
+        };
+        let mut times: Vec<f64> = (0..2000).map(|i| i as f64).collect();
+        for time in &mut times[1000..] {
+            *time += 400.;
+        }
+        let find = |desired_distance: f64, bus_ride: f64, times: &[f64], segment_gap: f64| {
+            let options = gem_finder::FinderOptions {
+                tolerance: 0.05,
+                segment_gap,
+                ..Default::default()
+            };
+            find_fastest_section_with_options(
+                desired_distance,
+                track(bus_ride),
+                times.to_vec(),
+                None,
+                &options,
+            )
+        };
+        assert_eq!(
+            find(5_000., 2_000., &times, timestamps::DEFAULT_SEGMENT_GAP),
+            Err(errors::InputDataError::NoSectionFound)
+        );
+        let section = find(5_000., 2_000., &times, f64::INFINITY).unwrap();
+        assert!(section.start < 1000 && section.end >= 1000);
+        assert!(section.target_value > 3.5);
+
+        // merged files, the times of the second part start over earlier, so sections spanning
+        // both parts would be way too fast
+        for (i, time) in times[1000..].iter_mut().enumerate() {
+            *time = 900. + i as f64;
+        }
+        // a single record written out of order
+        times[500] = 200.;
+        let section = find(1_000., 0., &times, f64::INFINITY).unwrap();
+        assert!((section.target_value - 3.).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_find_fastest_section_break_at_last_record() {
+        // the gap or clock reset before the last record leaves an empty window at the end, the
+        // last record is ignored
+        let coordinates: Vec<(f64, f64)> = (0..5).map(|i| (48.0, 8.0 + i as f64 * 4e-3)).collect();
+        let find = |coordinates: &[(f64, f64)], times: &[f64]| {
+            let options = gem_finder::FinderOptions::with_tolerance(Some(0.5));
+            find_fastest_section_with_options(
+                500.,
+                coordinates.to_vec(),
+                times.to_vec(),
+                None,
+                &options,
+            )
+        };
+        let expected = find(&coordinates[..4], &[0., 1., 2., 3.]);
+        assert!(expected.is_ok());
+        for last_time in [1000., 1.] {
+            assert_eq!(find(&coordinates, &[0., 1., 2., 3., last_time]), expected);
+        }
+    }
+
     #[test]
     fn test_find_fastest_section_in_fit_two_km() {
         let result = find_fastest_section_in_fit(2_000., FIT_FILE, Some(0.01)).unwrap();
@@ -203,3 +563,65 @@ mod test_find_fastest_section {
         assert_eq!(result.target_value.round(), 2.0);
     }
 }
+
+#[cfg(test)]
+mod test_find_fastest_section_synthetic {
+    use super::*;
+    use crate::synthetic;
+
+    #[test]
+    fn test_find_fastest_section_synthetic_activity() {
+        let activity = synthetic::generate_activity(3_600);
+        let result =
+            find_fastest_section(1_000., activity.coordinates, activity.times, Some(0.01)).unwrap();
+        // synthetic speed peaks at 4 m/s every 10 minutes (t = 150s, 750s, ...), the fastest
+        // section must be centered around one of these peaks
+        let center = (result.start + result.end) / 2;
+        assert!(((center % 600) as i32 - 150).abs() <= 10);
+        assert!(result.target_value > 3.5 && result.target_value < 4.0);
+    }
+
+    #[test]
+    fn test_find_fastest_section_indoor_activity() {
+        // indoor activities have no coordinates, only the distance recorded by e.g. a footpod
+        let mut activity = synthetic::generate_activity(3_600);
+        activity.coordinates = vec![(f64::NAN, f64::NAN); activity.times.len()];
+        let bytes = synthetic::to_fit_bytes(&activity);
+        let fit_data = fit_reader::parse_fit_bytes(&bytes).unwrap();
+        assert!(fit_data.use_device_distances());
+        let result = find_fastest_section_in_fit_data(1_000., &fit_data, Some(0.01)).unwrap();
+        let center = (result.start + result.end) / 2;
+        // the parsed fit data starts with the file_id message
+        assert!(((center as i32 - 1) % 600 - 150).abs() <= 10);
+        assert!((result.distance - 1_000.).abs() <= 10.);
+        assert!(result.start_coordinate.0.is_nan());
+
+        let with_distances = find_fastest_section_with_distances(
+            1_000.,
+            activity.distances,
+            activity.times,
+            None,
+            &gem_finder::FinderOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(with_distances.start + 1, result.start);
+        // the distances of the fit file are rounded to centimeters
+        assert!((with_distances.target_value - result.target_value).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_find_fastest_section_with_distances_of_fit() {
+        let fit_data = fit_reader::parse_fit("tests/data/2019-09-14-17-22-05.fit").unwrap();
+        let result = find_fastest_section_with_distances(
+            1_000.,
+            fit_data.distances,
+            fit_data.times,
+            None,
+            &gem_finder::FinderOptions::default(),
+        )
+        .unwrap();
+        // the recorded distance is close to the one computed from the coordinates
+        assert!((result.start as i32 - 628).abs() <= 10);
+        assert!((result.target_value - 2.89).abs() < 0.1);
+    }
+}
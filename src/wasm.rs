@@ -0,0 +1,245 @@
+use crate::{climb, dtypes, errors, fit_reader, velocity};
+use wasm_bindgen::prelude::*;
+
+// all names are converted to camel case to feel native in javascript, coordinates are passed as
+// separate latitude and longitude arrays, which map to Float64Array without any conversion
+
+// errors are thrown as javascript `Error` holding the message of the InputDataError, the
+// conversion comes with the std::error::Error implementation of InputDataError
+fn to_coordinates(latitudes: &[f64], longitudes: &[f64]) -> Result<Vec<(f64, f64)>, JsError> {
+    if latitudes.len() != longitudes.len() {
+        return Err(errors::InputDataError::InconsistentLength.into());
+    }
+    Ok(latitudes
+        .iter()
+        .copied()
+        .zip(longitudes.iter().copied())
+        .collect())
+}
+
+#[wasm_bindgen]
+pub struct FastestSection {
+    pub start: u32,
+    pub end: u32,
+    pub velocity: f64,
+}
+
+impl From<dtypes::TargetSection> for FastestSection {
+    fn from(section: dtypes::TargetSection) -> FastestSection {
+        FastestSection {
+            start: section.start,
+            end: section.end,
+            velocity: section.target_value,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct ClimbSection {
+    pub start: u32,
+    pub end: u32,
+    pub climb: f64,
+}
+
+impl From<dtypes::TargetSection> for ClimbSection {
+    fn from(section: dtypes::TargetSection) -> ClimbSection {
+        ClimbSection {
+            start: section.start,
+            end: section.end,
+            climb: section.target_value,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct FitData {
+    fit_data: fit_reader::FitData,
+}
+
+// fit data is either read with `parseFit` or passed as arrays
+#[wasm_bindgen]
+impl FitData {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        times: Vec<f64>,
+        latitudes: &[f64],
+        longitudes: &[f64],
+        altitudes: Vec<f64>,
+    ) -> Result<FitData, JsError> {
+        let coordinates = to_coordinates(latitudes, longitudes)?;
+        if times.len() != coordinates.len() || times.len() != altitudes.len() {
+            return Err(errors::InputDataError::InconsistentLength.into());
+        }
+        Ok(FitData {
+            fit_data: fit_reader::FitData {
+                calories: 0,
+                times,
+                coordinates,
+                altitudes,
+                distances: Vec::new(),
+                timer_pauses: Vec::new(),
+                record_messages: Vec::new(),
+            },
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn calories(&self) -> u16 {
+        self.fit_data.calories
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn times(&self) -> Vec<f64> {
+        self.fit_data.times.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn latitudes(&self) -> Vec<f64> {
+        self.fit_data.coordinates.iter().map(|c| c.0).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn longitudes(&self) -> Vec<f64> {
+        self.fit_data.coordinates.iter().map(|c| c.1).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn altitudes(&self) -> Vec<f64> {
+        self.fit_data.altitudes.clone()
+    }
+
+    #[wasm_bindgen(js_name = findFastestSection)]
+    pub fn find_fastest_section(
+        &self,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> Result<FastestSection, JsError> {
+        Ok(
+            velocity::find_fastest_section_in_fit_data(
+                desired_distance,
+                &self.fit_data,
+                tolerance,
+            )?
+            .into(),
+        )
+    }
+
+    #[wasm_bindgen(js_name = findBestClimbSection)]
+    pub fn find_best_climb_section(
+        &self,
+        desired_distance: f64,
+        tolerance: Option<f64>,
+    ) -> Result<ClimbSection, JsError> {
+        Ok(
+            climb::find_best_climb_section_in_fit_data(
+                desired_distance,
+                &self.fit_data,
+                tolerance,
+            )?
+            .into(),
+        )
+    }
+}
+
+// the fit crate is not available on wasm32, fit files are read with the pure rust decoder
+#[wasm_bindgen(js_name = parseFit)]
+pub fn parse_fit(bytes: &[u8]) -> Result<FitData, JsError> {
+    Ok(FitData {
+        fit_data: fit_reader::decode_fit_bytes(bytes)?,
+    })
+}
+
+#[wasm_bindgen(js_name = findFastestSection)]
+pub fn find_fastest_section(
+    desired_distance: f64,
+    times: Vec<f64>,
+    latitudes: &[f64],
+    longitudes: &[f64],
+    tolerance: Option<f64>,
+) -> Result<FastestSection, JsError> {
+    let coordinates = to_coordinates(latitudes, longitudes)?;
+    Ok(velocity::find_fastest_section(desired_distance, coordinates, times, tolerance)?.into())
+}
+
+#[wasm_bindgen(js_name = findBestClimbSection)]
+pub fn find_best_climb_section(
+    desired_distance: f64,
+    times: Vec<f64>,
+    latitudes: &[f64],
+    longitudes: &[f64],
+    altitudes: Vec<f64>,
+    tolerance: Option<f64>,
+) -> Result<ClimbSection, JsError> {
+    let coordinates = to_coordinates(latitudes, longitudes)?;
+    Ok(
+        climb::find_best_climb_section(desired_distance, coordinates, times, altitudes, tolerance)?
+            .into(),
+    )
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod test_wasm {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    const FIT_FILE: &[u8] = include_bytes!("../tests/data/2019-09-14-17-22-05.fit");
+
+    #[wasm_bindgen_test]
+    fn test_parse_fit() {
+        let fit_data = parse_fit(FIT_FILE).unwrap();
+        assert_eq!(fit_data.calories(), 432);
+        assert_eq!(fit_data.times().len(), 1230);
+        assert_eq!(fit_data.latitudes()[100], 49.40629959106445);
+        assert_eq!(fit_data.longitudes()[100], 8.695788383483887);
+        assert_eq!(fit_data.altitudes()[100], 254.9);
+        assert!(parse_fit(&FIT_FILE[..100]).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_find_sections_in_fit_data() {
+        let fit_data = parse_fit(FIT_FILE).unwrap();
+        let fastest = fit_data.find_fastest_section(1_000., Some(0.01)).unwrap();
+        assert_eq!((fastest.start, fastest.end), (628, 719));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_fit_data() {
+        let latitudes = [48.123, 48.123, 48.123, 48.123];
+        let longitudes = [9.35, 9.36, 9.37, 9.38];
+        let times = vec![1608228953.8, 1608228954.8, 1608228955.8, 1608228956.8];
+        let altitudes = vec![100., 110., 120., 130.];
+        let fit_data =
+            FitData::new(times.clone(), &latitudes, &longitudes, altitudes.clone()).unwrap();
+        assert_eq!(fit_data.latitudes(), latitudes);
+        assert_eq!(fit_data.altitudes(), altitudes);
+        let fastest = fit_data.find_fastest_section(1_000., Some(0.5)).unwrap();
+        assert_eq!((fastest.start, fastest.end), (0, 3));
+        let climb = fit_data.find_best_climb_section(1_000., Some(0.5)).unwrap();
+        assert_eq!((climb.start, climb.end), (0, 3));
+        assert!(FitData::new(times, &latitudes, &longitudes, altitudes[..2].to_vec()).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_find_sections() {
+        let latitudes = [48.123, 48.123, 48.123, 48.123];
+        let longitudes = [9.35, 9.36, 9.37, 9.38];
+        let times = vec![1608228953.8, 1608228954.8, 1608228955.8, 1608228956.8];
+        let fastest =
+            find_fastest_section(1_000., times.clone(), &latitudes, &longitudes, Some(0.5))
+                .unwrap();
+        assert_eq!((fastest.start, fastest.end), (0, 3));
+        assert_eq!(fastest.velocity.round(), 495.0);
+        let altitudes = vec![100., 110., 120., 130.];
+        let climb = find_best_climb_section(
+            1_000.,
+            times.clone(),
+            &latitudes,
+            &longitudes,
+            altitudes,
+            Some(0.5),
+        )
+        .unwrap();
+        assert_eq!((climb.start, climb.end), (0, 3));
+        assert!(find_fastest_section(1_000., times, &latitudes, &longitudes[..2], None).is_err());
+    }
+}
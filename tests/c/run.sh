@@ -0,0 +1,7 @@
+#!/usr/bin/env sh
+# builds the c api and runs the c test harness against it, run from the repository root
+set -e
+
+cargo build --no-default-features --features capi
+cc -std=c99 -Wall -Wextra -Werror -Iinclude tests/c/test_capi.c -Ltarget/debug -lsportgems -o target/debug/test_capi
+LD_LIBRARY_PATH=target/debug DYLD_LIBRARY_PATH=target/debug target/debug/test_capi
@@ -0,0 +1,82 @@
+// small test harness for the c api, build and run it with tests/c/run.sh
+#include <assert.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "sportgems.h"
+
+#define FIT_FILE "tests/data/2019-09-14-17-22-05.fit"
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    assert(file != NULL);
+    fseek(file, 0, SEEK_END);
+    *len = (size_t)ftell(file);
+    rewind(file);
+    uint8_t *data = malloc(*len);
+    assert(fread(data, 1, *len, file) == *len);
+    fclose(file);
+    return data;
+}
+
+static void test_fit_file(void) {
+    size_t len;
+    uint8_t *data = read_file(FIT_FILE, &len);
+    SportgemsActivity *activity = NULL;
+    SportgemsSection section;
+
+    assert(sportgems_activity_from_fit(data, len, &activity) == SPORTGEMS_ERROR_OK);
+    free(data);
+    assert(sportgems_activity_len(activity) == 1231);
+
+    assert(sportgems_find_fastest_section(activity, 1000, SPORTGEMS_DEFAULT_TOLERANCE, &section) ==
+           SPORTGEMS_ERROR_OK);
+    assert(section.start == 628 && section.end == 719);
+
+    assert(sportgems_find_best_climb_section(activity, 1000, SPORTGEMS_DEFAULT_TOLERANCE,
+                                             &section) == SPORTGEMS_ERROR_OK);
+    assert(section.start == 346 && section.end == 586);
+
+    assert(sportgems_find_fastest_section(activity, 100000, SPORTGEMS_DEFAULT_TOLERANCE,
+                                          &section) == SPORTGEMS_ERROR_DISTANCE_TOO_SMALL);
+    sportgems_activity_free(activity);
+}
+
+static void test_arrays(void) {
+    double times[] = {1608228953.8, 1608228954.8, 1608228955.8, 1608228956.8};
+    double latitudes[] = {48.123, 48.123, 48.123, 48.123};
+    double longitudes[] = {9.35, 9.36, 9.37, 9.38};
+    SportgemsActivity *activity = NULL;
+    SportgemsSection section;
+
+    assert(sportgems_activity_from_arrays(times, latitudes, longitudes, NULL, 4, &activity) ==
+           SPORTGEMS_ERROR_OK);
+    assert(sportgems_find_fastest_section(activity, 1000, 0.5, &section) == SPORTGEMS_ERROR_OK);
+    assert(section.start == 0 && section.end == 3);
+    sportgems_activity_free(activity);
+}
+
+static void test_errors(void) {
+    const uint8_t garbage[] = "no fit file";
+    SportgemsActivity *activity = NULL;
+
+    assert(sportgems_activity_from_fit(garbage, sizeof(garbage), &activity) ==
+           SPORTGEMS_ERROR_INVALID_FIT_FILE);
+    assert(activity == NULL);
+    assert(sportgems_activity_from_fit(NULL, 0, &activity) == SPORTGEMS_ERROR_NULL_POINTER);
+    assert(strcmp(sportgems_error_message(SPORTGEMS_ERROR_INVALID_FIT_FILE),
+                  "Could not read fit file, check that the given path points to a valid fit "
+                  "file.") == 0);
+    assert(strcmp(sportgems_error_message(42), "Unknown error.") == 0);
+    assert(strcmp(sportgems_error_message(-1), "Unknown error.") == 0);
+    sportgems_activity_free(NULL);
+}
+
+int main(void) {
+    test_fit_file();
+    test_arrays();
+    test_errors();
+    printf("all c api tests passed\n");
+    return 0;
+}
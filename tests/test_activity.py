@@ -0,0 +1,168 @@
+import math
+from pathlib import Path
+
+import pytest
+
+from sportgems import (
+    find_best_climb_section,
+    find_best_climb_section_in_fit,
+    find_fastest_section,
+    find_fastest_section_in_fit,
+    parse_fit_data,
+    Activity,
+    DistanceTooSmallException,
+    InconsistentLengthException,
+    InvalidFitFileException,
+)
+
+
+def test_activity_from_fit(fit_file):
+    activity = Activity.from_fit(fit_file)
+    assert len(activity) == 1231
+    assert repr(activity) == "Activity(records=1231)"
+    assert activity.calories == 432
+    # results are equal to the ones of the single functions
+    assert activity.fastest_section(1_000) == find_fastest_section_in_fit(1_000, fit_file)
+    assert activity.fastest_section(2_000, 0.01) == find_fastest_section_in_fit(2_000, fit_file)
+    assert activity.best_climb_section(1_000) == find_best_climb_section_in_fit(1_000, fit_file)
+    with pytest.raises(DistanceTooSmallException):
+        activity.fastest_section(100_000)
+
+
+def test_activity_from_bytes(fit_file):
+    activity = Activity.from_bytes(Path(fit_file).read_bytes())
+    assert activity.fastest_section(1_000).start == 628
+    with pytest.raises(InvalidFitFileException):
+        Activity.from_bytes(b"no fit file")
+
+
+def test_activity_from_arrays(track):
+    activity = Activity(track.times, track.coordinates, track.altitudes)
+    # the activity knows the altitudes, so the elevation gain of the fastest section is given
+    fastest = activity.fastest_section(1_000)
+    expected = find_fastest_section(1_000, track.times, track.coordinates)
+    assert (fastest.start, fastest.end, fastest.velocity) == (expected.start, expected.end, expected.velocity)
+    assert expected.elevation_gain is None
+    assert fastest.elevation_gain > 0
+    assert activity.best_climb_section(1_000) == find_best_climb_section(1_000, track.times, track.coordinates, track.altitudes)
+    assert activity.calories is None
+
+    # altitudes are optional, but needed for climb sections
+    activity = Activity(track.times, track.coordinates)
+    assert activity.altitudes is None
+    assert activity.elevation_gain is None
+    with pytest.raises(InconsistentLengthException):
+        activity.best_climb_section(1_000)
+    with pytest.raises(InconsistentLengthException):
+        Activity(track.times[:-1], track.coordinates)
+
+
+def test_activity_derived_data(fit_file):
+    activity = Activity.from_fit(fit_file)
+    distances = activity.distances
+    assert len(distances) == len(activity)
+    assert distances[0] == 0
+    assert distances == sorted(distances)
+    assert not any(math.isnan(lat) or math.isnan(lon) for lat, lon in activity.coordinates)
+    assert activity.elevation_gain > 0
+
+
+def test_activity_best_efforts(fit_file):
+    activity = Activity.from_fit(fit_file)
+    efforts, errors = activity.best_efforts([1_000, 2_000, 100_000])
+    assert efforts[0] == activity.fastest_section(1_000)
+    assert efforts[1] == activity.fastest_section(2_000)
+    assert efforts[2] is None
+    assert errors[:2] == [None, None]
+    assert isinstance(errors[2], DistanceTooSmallException)
+
+
+def test_activity_summary(fit_file):
+    summary = Activity.from_fit(fit_file).summary()
+    assert summary["records"] == 1231
+    assert summary["calories"] == 432
+    assert summary["duration"] == summary["end_time"] - summary["start_time"]
+    assert 5_000 < summary["distance"] < 6_000
+    assert summary["average_velocity"] == summary["distance"] / summary["duration"]
+    assert summary["elevation_gain"] > 0
+
+
+def test_activity_distance_model(fit_file):
+    activity = Activity.from_fit(fit_file, distance_model="geodesic")
+    assert activity.distance_model == "geodesic"
+    assert Activity.from_fit(fit_file).distance_model == "spherical"
+    assert activity.fastest_section(1_000) == find_fastest_section_in_fit(1_000, fit_file, distance_model="geodesic")
+    with pytest.raises(ValueError):
+        Activity.from_fit(fit_file, distance_model="flat")
+
+
+def test_activity_slope_distance(fit_file):
+    activity = Activity.from_fit(fit_file, slope_distance=True)
+    assert activity.slope_distance
+    assert activity.distances[-1] > Activity.from_fit(fit_file).distances[-1]
+    assert activity.best_climb_section(1_000) == find_best_climb_section_in_fit(1_000, fit_file, slope_distance=True)
+
+
+def test_activity_smoothing(fit_file):
+    activity = Activity.from_fit(fit_file, smoothing="kalman")
+    assert activity.smoothing == "kalman:1:5"
+    assert Activity.from_fit(fit_file).smoothing == "none"
+    assert activity.distances[-1] < Activity.from_fit(fit_file).distances[-1]
+    assert activity.fastest_section(1_000) == find_fastest_section_in_fit(1_000, fit_file, smoothing="kalman")
+    with pytest.raises(ValueError):
+        Activity.from_fit(fit_file, smoothing="kalman:0")
+
+
+def test_activity_fill(fit_file):
+    activity = Activity.from_fit(fit_file, fill="linear:30")
+    assert activity.fill == "linear:30"
+    assert Activity.from_fit(fit_file).fill == "forward"
+    assert activity.fastest_section(1_000) == find_fastest_section_in_fit(1_000, fit_file, fill="linear:30")
+    assert activity.best_climb_section(1_000) == find_best_climb_section_in_fit(1_000, fit_file, fill="linear:30")
+    with pytest.raises(ValueError):
+        Activity.from_fit(fit_file, fill="linear:-1")
+
+
+def test_activity_moving_time(fit_file):
+    activity = Activity.from_fit(fit_file, moving_time="stationary:1")
+    assert activity.moving_time == "stationary:1:10"
+    assert activity.timer_pauses == []
+    assert Activity.from_fit(fit_file).moving_time == "elapsed"
+    assert activity.fastest_section(1_000) == find_fastest_section_in_fit(1_000, fit_file, moving_time="stationary:1")
+    with pytest.raises(ValueError):
+        Activity.from_fit(fit_file, moving_time="stationary:0")
+
+
+def test_activity_segments():
+    times = [float(t if t < 500 else t + 600) for t in range(1_000)]
+    activity = Activity.from_distances(times, [3. * t for t in range(1_000)])
+    assert activity.segment_gap == 300.
+    assert activity.segments() == [(0, 500), (500, 1_000)]
+    assert activity.time_issues()["gaps"] == [500]
+    assert Activity.from_distances(times, [3. * t for t in range(1_000)], segment_gap=math.inf).segments() == [(0, 1_000)]
+
+
+def test_activity_gain_method(fit_file):
+    raw = Activity.from_fit(fit_file)
+    ascent, descent = raw.ascent_descent()
+    assert raw.summary()["elevation_loss"] == descent
+    assert raw.gain_method == "sum"
+    activity = Activity.from_fit(fit_file, elevation_smoothing="moving-average", gain_method="hysteresis:2")
+    assert activity.elevation_smoothing == "moving-average:5"
+    assert activity.gain_method == "hysteresis:2"
+    assert activity.elevation_gain < ascent
+    assert activity.best_climb_section(1_000) == find_best_climb_section_in_fit(
+        1_000, fit_file, elevation_smoothing="moving-average", gain_method="hysteresis:2"
+    )
+    with pytest.raises(ValueError):
+        Activity.from_fit(fit_file, gain_method="max")
+
+
+def test_activity_from_distances(fit_file):
+    fit_data = parse_fit_data(fit_file)
+    activity = Activity.from_distances(fit_data.times, fit_data.distances, fit_data.altitudes)
+    assert activity.distances[-1] == 5839.77
+    assert activity.fastest_section(1_000).velocity > 2.8
+    assert activity.summary()["distance"] > 5_800
+    with pytest.raises(InconsistentLengthException):
+        Activity.from_distances([1.0, 2.0], [0.0])
@@ -0,0 +1,69 @@
+import asyncio
+
+from sportgems import (
+    aio,
+    find_fastest_section,
+    find_fastest_section_in_fit,
+    find_best_climb_section_in_fit,
+    parse_fit_data,
+    DistanceTooSmallException,
+    FileNotReadableException,
+)
+
+import pytest
+
+
+def test_aio_find_fastest_section_in_fit(fit_file):
+    expected = find_fastest_section_in_fit(1_000, fit_file)
+    result = asyncio.run(aio.find_fastest_section_in_fit(1_000, fit_file))
+    assert (result.start, result.end, result.velocity) == (expected.start, expected.end, expected.velocity)
+
+
+def test_aio_find_fastest_section(track):
+    expected = find_fastest_section(1_000, track.times, track.coordinates)
+    result = asyncio.run(aio.find_fastest_section(1_000, track.times, track.coordinates))
+    assert (result.start, result.end, result.velocity) == (expected.start, expected.end, expected.velocity)
+
+
+def test_aio_concurrent_calls(fit_file):
+    async def search_all():
+        return await asyncio.gather(
+            aio.find_fastest_section_in_fit(1_000, fit_file),
+            aio.find_best_climb_section_in_fit(1_000, fit_file),
+            aio.parse_fit_data(fit_file),
+            aio.analyze_files([fit_file], [1_000]),
+        )
+
+    fastest, climb, fit_data, analyses = asyncio.run(search_all())
+    assert fastest.start == find_fastest_section_in_fit(1_000, fit_file).start
+    assert climb.start == find_best_climb_section_in_fit(1_000, fit_file).start
+    assert len(fit_data.times) == len(parse_fit_data(fit_file).times)
+    assert analyses[0].fastest_sections[0].start == fastest.start
+
+
+def test_aio_exceptions(fit_file, data_dir):
+    with pytest.raises(DistanceTooSmallException):
+        asyncio.run(aio.find_fastest_section_in_fit(10_000, fit_file))
+
+    with pytest.raises(FileNotReadableException):
+        asyncio.run(aio.parse_fit_data(str(data_dir / "does_not_exist.fit")))
+
+
+def test_aio_does_not_block_event_loop(fit_file):
+    # the event loop keeps processing other tasks while sportgems is busy
+    ticks = 0
+
+    async def tick():
+        nonlocal ticks
+        while True:
+            ticks += 1
+            await asyncio.sleep(0)
+
+    async def search():
+        ticker = asyncio.create_task(tick())
+        for _ in range(5):
+            await aio.find_best_climb_section_in_fit(1_000, fit_file)
+        ticker.cancel()
+
+    asyncio.run(search())
+    assert ticks > 5
@@ -0,0 +1,35 @@
+from sportgems import parse_fit_data
+
+import pytest
+
+pa = pytest.importorskip("pyarrow")
+
+
+def test_fit_data_to_arrow(fit_file):
+    fit_data = parse_fit_data(fit_file)
+    batch = fit_data.to_arrow()
+    assert isinstance(batch, pa.RecordBatch)
+    assert batch.num_rows == 1231
+    assert batch.schema.names == ["timestamp", "lat", "lon", "altitude", "distance"]
+    assert batch.schema.field("timestamp").type == pa.timestamp("ms", tz="+00:00")
+    assert batch.column("timestamp")[100].value == 1568474841000
+    assert batch.column("lat")[100].as_py() == fit_data.coordinates[100][0]
+    assert batch.column("lon")[100].as_py() == fit_data.coordinates[100][1]
+    assert batch.column("altitude")[100].as_py() == fit_data.altitudes[100]
+    assert batch.column("distance")[100].as_py() == fit_data.distances[100]
+
+
+def test_fit_data_to_arrow__nans_become_nulls(fit_file):
+    fit_data = parse_fit_data(fit_file)
+    batch = fit_data.to_arrow()
+    # the fit file contains non-record messages, which result in nan values
+    missing_altitudes = sum(1 for altitude in fit_data.altitudes if altitude != altitude)
+    assert missing_altitudes > 0
+    assert batch.column("altitude").null_count == missing_altitudes
+
+
+def test_fit_data_to_polars(fit_file):
+    pl = pytest.importorskip("polars")
+    df = pl.from_arrow(parse_fit_data(fit_file).to_arrow())
+    assert df.shape == (1231, 5)
+    assert df.columns == ["timestamp", "lat", "lon", "altitude", "distance"]
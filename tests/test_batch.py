@@ -0,0 +1,44 @@
+from sportgems import (
+    analyze_files,
+    find_fastest_section_in_fit,
+    find_best_climb_section_in_fit,
+    FileNotReadableException,
+    DistanceTooSmallException,
+)
+
+
+def test_analyze_files(fit_file, data_dir):
+    missing_file = str(data_dir / "does_not_exist.fit")
+    results = analyze_files([fit_file, missing_file, fit_file], [1_000, 2_000, 10_000])
+    assert len(results) == 3
+
+    # results of the batch are equal to the results of the single file functions
+    result = results[0]
+    assert result.path == fit_file
+    assert result.error is None
+    fastest = find_fastest_section_in_fit(1_000, fit_file)
+    assert result.fastest_sections[0].start == fastest.start
+    assert result.fastest_sections[0].end == fastest.end
+    assert result.fastest_sections[0].velocity == fastest.velocity
+    climb = find_best_climb_section_in_fit(2_000, fit_file)
+    assert result.climb_sections[1].start == climb.start
+    assert result.climb_sections[1].end == climb.end
+    assert result.climb_sections[1].climb == climb.climb
+
+    # activity is too short for 10km sections
+    assert result.fastest_sections[2] is None
+    assert result.climb_sections[2] is None
+    assert result.fastest_section_errors[:2] == [None, None]
+    assert isinstance(result.fastest_section_errors[2], DistanceTooSmallException)
+    assert isinstance(result.climb_section_errors[2], DistanceTooSmallException)
+
+    # missing file does not abort the batch
+    result = results[1]
+    assert result.path == missing_file
+    assert isinstance(result.error, FileNotReadableException)
+    assert result.fastest_sections == []
+    assert result.climb_sections == []
+    assert result.fastest_section_errors == []
+    assert result.climb_section_errors == []
+
+    assert results[2].fastest_sections[0].start == results[0].fastest_sections[0].start
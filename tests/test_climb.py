@@ -1,4 +1,12 @@
-from sportgems import find_best_climb_section, find_best_climb_section_in_fit, DistanceTooSmallException
+from sportgems import (
+    find_best_climb_section,
+    find_best_climb_section_from_distances,
+    find_best_climb_section_in_fit,
+    parse_fit_data,
+    total_ascent_descent,
+    DistanceTooSmallException,
+    InconsistentLengthException,
+)
 
 import pytest
 
@@ -42,3 +50,52 @@ def test_find_best_climb_section_in_fit(fit_file):
     with pytest.raises(DistanceTooSmallException, match="Distance of provided input data is too small for requested desired distance."):
         result = find_best_climb_section_in_fit(10_000, fit_file)
 
+
+
+def test_find_best_climb_section__details(track):
+    result = find_best_climb_section(1000, track.times, track.coordinates, track.altitudes)
+    assert 990 <= result.distance <= 1_010
+    assert result.start_time == track.times[result.start]
+    assert result.end_time == track.times[result.end]
+    assert round(result.elevation_gain / (result.duration / 60), 3) == round(result.climb, 3)
+    assert result.start_coordinate == tuple(track.coordinates[result.start])
+
+
+def test_find_best_climb_section__slope_distance(fit_file):
+    flat = find_best_climb_section_in_fit(1_000, fit_file)
+    slope = find_best_climb_section_in_fit(1_000, fit_file, slope_distance=True)
+    assert 990 <= slope.distance <= 1_010
+    assert slope.end - slope.start <= flat.end - flat.start
+
+
+def test_find_best_climb_section__gain_method(fit_file):
+    sum_ = find_best_climb_section_in_fit(1_000, fit_file)
+    hysteresis = find_best_climb_section_in_fit(1_000, fit_file, gain_method="hysteresis")
+    assert 0 < hysteresis.climb <= sum_.climb
+    smoothed = find_best_climb_section_in_fit(
+        1_000, fit_file, elevation_smoothing="moving-average", gain_method="hysteresis:2"
+    )
+    assert smoothed.climb > 0
+    with pytest.raises(ValueError, match="Unknown gain method 'barometric'"):
+        find_best_climb_section_in_fit(1_000, fit_file, gain_method="barometric")
+
+
+def test_total_ascent_descent():
+    # climb of 10 m with 1 m of noise on top
+    altitudes = [0., 1., 0., 1., 3., 2., 5., 4., 8., 7., 10., 9., 10., 9.]
+    assert total_ascent_descent(altitudes) == (15., 6.)
+    assert total_ascent_descent(altitudes, gain_method="hysteresis") == (10., 0.)
+    ascent, descent = total_ascent_descent(altitudes, elevation_smoothing="moving-average:3")
+    assert ascent < 15 and descent < 6
+    assert total_ascent_descent([float("nan"), 1., 3.]) == (2., 0.)
+    with pytest.raises(InconsistentLengthException):
+        total_ascent_descent(altitudes, times=[0., 1.])
+    with pytest.raises(ValueError, match="Invalid threshold"):
+        total_ascent_descent(altitudes, gain_method="hysteresis:x")
+
+
+def test_find_best_climb_section_from_distances(fit_file):
+    fit_data = parse_fit_data(fit_file)
+    result = find_best_climb_section_from_distances(1_000, fit_data.times, fit_data.distances, fit_data.altitudes)
+    assert 990 <= result.distance <= 1_010
+    assert result.climb > 0
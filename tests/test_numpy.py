@@ -0,0 +1,69 @@
+from sportgems import (
+    find_best_climb_section,
+    find_fastest_section,
+    find_fastest_section_in_fit,
+    parse_fit_data,
+)
+
+import numpy as np
+import pytest
+
+
+def test_find_fastest_section__numpy_input(track):
+    expected = find_fastest_section(1_000, track.times, track.coordinates)
+    result = find_fastest_section(1_000, np.array(track.times, dtype=float), np.array(track.coordinates))
+    assert (result.start, result.end, result.velocity) == (expected.start, expected.end, expected.velocity)
+
+
+def test_find_best_climb_section__numpy_input(track):
+    expected = find_best_climb_section(1_000, track.times, track.coordinates, track.altitudes)
+    result = find_best_climb_section(
+        1_000, np.array(track.times, dtype=float), np.array(track.coordinates), np.array(track.altitudes)
+    )
+    assert (result.start, result.end, result.climb) == (expected.start, expected.end, expected.climb)
+
+
+def test_find_fastest_section__non_contiguous_and_int_arrays(track):
+    expected = find_fastest_section(1_000, track.times, track.coordinates)
+    # columns of a larger array are no contiguous views
+    table = np.column_stack([track.times, np.array(track.coordinates)])
+    result = find_fastest_section(1_000, table[:, 0], table[:, 1:])
+    assert (result.start, result.end) == (expected.start, expected.end)
+    # arrays of other dtypes are accepted as well
+    result = find_fastest_section(1_000, np.array(track.times, dtype=np.int64), np.array(track.coordinates))
+    assert (result.start, result.end) == (expected.start, expected.end)
+
+
+def test_find_fastest_section__invalid_coordinates_shape(track):
+    coordinates = np.zeros((len(track.times), 3))
+    with pytest.raises(ValueError, match=r"Coordinates array needs to be of shape \(n, 2\)."):
+        find_fastest_section(1_000, np.array(track.times, dtype=float), coordinates)
+
+
+def test_parse_fit_data__numpy_output(fit_file):
+    fit_data = parse_fit_data(fit_file)
+    assert fit_data.times_array.dtype == np.float64
+    assert fit_data.times_array.shape == (1231,)
+    assert fit_data.coordinates_array.shape == (1231, 2)
+    assert fit_data.altitudes_array.shape == (1231,)
+    assert tuple(fit_data.coordinates_array[100]) == fit_data.coordinates[100]
+    assert fit_data.times_array[100] == fit_data.times[100]
+    assert fit_data.altitudes_array[100] == fit_data.altitudes[100]
+    # arrays are created once and cannot be modified
+    assert fit_data.times_array is fit_data.times_array
+    assert not fit_data.coordinates_array.flags.writeable
+    with pytest.raises(ValueError):
+        fit_data.altitudes_array[0] = 0.0
+
+
+def test_parse_fit_data__numpy_round_trip(fit_file):
+    # arrays returned by parse_fit_data can be passed on to the search functions directly
+    fit_data = parse_fit_data(fit_file)
+    result = find_fastest_section(1_000, fit_data.times_array, fit_data.coordinates_array)
+    expected = find_fastest_section_in_fit(1_000, fit_file)
+    assert (result.start, result.end) == (expected.start, expected.end)
+    result = find_best_climb_section(
+        1_000, fit_data.times_array, fit_data.coordinates_array, fit_data.altitudes_array
+    )
+    expected = find_best_climb_section(1_000, fit_data.times, fit_data.coordinates, fit_data.altitudes)
+    assert (result.start, result.end, result.climb) == (expected.start, expected.end, expected.climb)
@@ -0,0 +1,41 @@
+import math
+
+from sportgems import (
+    detect_outliers,
+    remove_outliers,
+    remove_coordinate_outliers,
+    InconsistentLengthException,
+)
+
+import pytest
+
+
+def test_detect_outliers_below_sea_level():
+    altitudes = [-430., -431., -430., -429., -250., -430., -431., -430., -432., -431.]
+    expected = [i == 4 for i in range(10)]
+    assert detect_outliers(altitudes) == expected
+    assert detect_outliers(altitudes, method="median") == expected
+    assert detect_outliers(altitudes, method="rate") == expected
+    assert not any(detect_outliers(altitudes, method="none"))
+
+
+def test_remove_outliers():
+    speeds = [3., 3.1, float("nan"), 3.2, 25., 3.1, 3.]
+    times = [0., 2., 4., 6., 8., 10., 12.]
+    result = remove_outliers(speeds, times, method="rate:5")
+    assert math.isnan(result[2]) and math.isnan(result[4])
+    assert result[3] == 3.2
+    with pytest.raises(InconsistentLengthException):
+        remove_outliers(speeds, times[:3])
+    with pytest.raises(ValueError):
+        remove_outliers(speeds, method="hampel:4")
+    with pytest.raises(ValueError):
+        remove_outliers(speeds, method="hampel:9.7")
+
+
+def test_remove_coordinate_outliers():
+    coordinates = [(48.0, 8.0 + i * 4e-5) for i in range(10)]
+    coordinates[6] = (48.01, coordinates[6][1])
+    result = remove_coordinate_outliers(coordinates, method="rate:50")
+    assert all(math.isnan(c) for c in result[6])
+    assert result[:6] == coordinates[:6]
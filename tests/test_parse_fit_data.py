@@ -1,4 +1,10 @@
-from sportgems import parse_fit_data
+from sportgems import parse_fit_data, FileNotReadableException, InvalidFitFileException
+
+import pytest
+
+
+INVALID_FIT_FILE_MSG = "Could not read fit file, check that the given path points to a valid fit file."
+FILE_NOT_READABLE_MSG = "Could not open file, check that the given path exists and is readable."
 
 
 def test_parse_fit_data(fit_file):
@@ -9,3 +15,16 @@ def test_parse_fit_data(fit_file):
     assert fit_data.coordinates[100] == (49.40629959106445, 8.695788383483887)
     assert fit_data.times[100] == 1568474841.0
     assert fit_data.altitudes[100] == 254.9
+    assert fit_data.distances[100] == 766.55
+
+
+def test_parse_fit_data__invalid_file(data_dir):
+    with pytest.raises(FileNotReadableException, match=FILE_NOT_READABLE_MSG):
+        parse_fit_data(str(data_dir / "does_not_exist.fit"))
+    # missing files are reported as os errors
+    with pytest.raises(OSError):
+        parse_fit_data(str(data_dir / "does_not_exist.fit"))
+
+    # python files are no fit files
+    with pytest.raises(InvalidFitFileException, match=INVALID_FIT_FILE_MSG):
+        parse_fit_data(str(data_dir.parent / "conftest.py"))
@@ -0,0 +1,44 @@
+from sportgems import (
+    find_fastest_section_from_distances,
+    parse_fit_data,
+    quality_report,
+    Activity,
+    InvalidDesiredDistanceException,
+    NoSectionFoundException,
+)
+
+import pytest
+
+
+def test_quality_report(fit_file):
+    activity = Activity.from_fit(fit_file)
+    report = quality_report(activity)
+    # the rows of e.g. events and laps are no records
+    assert report["records"] == sum(parse_fit_data(fit_file).record_messages) < len(activity)
+    assert report["missing"] == {"times": 0, "coordinates": 45, "altitudes": 0, "distances": None}
+    assert report["altitude_spikes"] == []
+    assert report["time_issues"]["clock_resets"] == []
+    assert report["sampling_intervals"]["min"] <= report["sampling_intervals"]["median"]
+    assert report["suggested_tolerance"] >= 0.01
+    assert set(report) == {
+        "records", "missing", "sampling_intervals", "time_issues", "speed_spikes",
+        "altitude_spikes", "step_distance", "suggested_tolerance",
+    }
+
+
+def test_quality_report_suggested_tolerance():
+    # a record every 30 seconds, which is about 95 m apart
+    times = [30. * i for i in range(200)]
+    distances = [95. * i for i in range(200)]
+    report = quality_report(Activity.from_distances(times, distances))
+    assert report["missing"] == {"times": 0, "coordinates": None, "altitudes": None, "distances": 0}
+    assert report["sampling_intervals"]["mean"] == 30.
+    assert report["step_distance"] == 95.
+    assert report["suggested_tolerance"] == 0.048
+    with pytest.raises(NoSectionFoundException):
+        find_fastest_section_from_distances(1_000, times, distances)
+    find_fastest_section_from_distances(1_000, times, distances, tolerance=report["suggested_tolerance"])
+    activity = Activity.from_distances(times, distances)
+    assert quality_report(activity, desired_distance=10_000)["suggested_tolerance"] == 0.01
+    with pytest.raises(InvalidDesiredDistanceException):
+        quality_report(activity, desired_distance=0)
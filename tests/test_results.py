@@ -0,0 +1,104 @@
+import math
+import pickle
+
+from sportgems import (
+    analyze_files,
+    find_best_climb_section_in_fit,
+    find_fastest_section,
+    find_fastest_section_in_fit,
+    parse_fit_data,
+    ClimbSection,
+    FastestSection,
+    FitData,
+)
+
+
+def test_fastest_section_dunder_methods(fit_file):
+    result = find_fastest_section_in_fit(1_000, fit_file)
+    assert repr(result).startswith("FastestSection(start=628, end=719, velocity=2.888")
+    assert result == find_fastest_section_in_fit(1_000, fit_file)
+    assert result != find_fastest_section_in_fit(2_000, fit_file)
+    assert hash(result) == hash(find_fastest_section_in_fit(1_000, fit_file))
+    assert len({result, find_fastest_section_in_fit(1_000, fit_file)}) == 1
+
+    # the repr can be evaluated to recreate the section
+    assert eval(repr(result)) == result
+
+    unpickled = pickle.loads(pickle.dumps(result))
+    assert isinstance(unpickled, FastestSection)
+    assert unpickled == result
+    assert unpickled.velocity == result.velocity
+
+
+def test_climb_section_dunder_methods(fit_file):
+    result = find_best_climb_section_in_fit(1_000, fit_file)
+    assert repr(result).startswith("ClimbSection(start=346, end=586, climb=5.786")
+    assert pickle.loads(pickle.dumps(result)) == result
+    assert hash(pickle.loads(pickle.dumps(result))) == hash(result)
+    # fastest and climb sections are never equal
+    assert result != find_fastest_section_in_fit(1_000, fit_file)
+
+
+def test_section_to_dict(track):
+    result = find_fastest_section(1_000, track.times, track.coordinates)
+    result_dict = result.to_dict()
+    assert list(result_dict) == [
+        "start",
+        "end",
+        "velocity",
+        "distance",
+        "duration",
+        "start_time",
+        "end_time",
+        "pace",
+        "elevation_gain",
+        "start_coordinate",
+        "end_coordinate",
+    ]
+    assert result_dict["start"] == result.start
+    assert result_dict["velocity"] == result.velocity
+    assert result_dict["elevation_gain"] is None
+    assert FastestSection(**result_dict) == result
+
+
+def test_fit_data_dunder_methods(fit_file):
+    fit_data = parse_fit_data(fit_file)
+    assert repr(fit_data) == "FitData(calories=432, records=1231)"
+    # fit data holds NaN values for missing data, which are still equal to themselves
+    assert any(math.isnan(time) for time in fit_data.times)
+    assert fit_data == parse_fit_data(fit_file)
+    assert hash(fit_data) == hash(parse_fit_data(fit_file))
+
+    unpickled = pickle.loads(pickle.dumps(fit_data))
+    assert isinstance(unpickled, FitData)
+    assert unpickled == fit_data
+    assert unpickled.calories == 432
+    assert len(unpickled.times) == 1231
+
+    fit_data_dict = fit_data.to_dict()
+    assert list(fit_data_dict) == ["calories", "times", "coordinates", "altitudes", "distances", "timer_pauses",
+                                  "record_messages"]
+    assert FitData(**fit_data_dict) == fit_data
+    assert FitData(1, [1.0], [(1.0, 2.0)], [3.0]) != fit_data
+    assert FitData(1, [1.0], [(1.0, 2.0)], [3.0], timer_pauses=[(1.0, 2.0)]) != FitData(1, [1.0], [(1.0, 2.0)], [3.0])
+
+
+def test_file_analysis_dunder_methods(fit_file, data_dir):
+    missing_file = str(data_dir / "does_not_exist.fit")
+    results = analyze_files([fit_file, missing_file], [1_000, 10_000])
+    assert repr(results[0]).startswith(f"FileAnalysis(path='{fit_file}', fastest_sections=[FastestSection(")
+    assert repr(results[1]).endswith("error=FileNotReadableException('Could not open file, check that the given path exists and is readable.'))")
+
+    unpickled = pickle.loads(pickle.dumps(results))
+    assert unpickled == results
+    assert hash(unpickled[0]) == hash(results[0])
+    assert unpickled[0] != unpickled[1]
+
+    result_dict = results[0].to_dict()
+    assert result_dict["path"] == fit_file
+    assert result_dict["fastest_sections"][0]["start"] == 628
+    assert result_dict["fastest_sections"][1] is None
+    assert result_dict["fastest_section_errors"][0] is None
+    assert result_dict["fastest_section_errors"][1].startswith("Distance of provided input data is too small")
+    assert result_dict["error"] is None
+    assert results[1].to_dict()["error"].startswith("Could not open file")
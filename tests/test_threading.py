@@ -0,0 +1,50 @@
+import threading
+import time
+from concurrent.futures import ThreadPoolExecutor
+
+from sportgems import Activity, find_fastest_section_in_fit, find_best_climb_section_in_fit
+
+
+def test_concurrent_calls_from_threads(fit_file):
+    # run the same searches from multiple threads at once and expect consistent results
+    expected = find_fastest_section_in_fit(1_000, fit_file)
+    with ThreadPoolExecutor(max_workers=4) as executor:
+        results = list(executor.map(lambda _: find_fastest_section_in_fit(1_000, fit_file), range(8)))
+    for result in results:
+        assert (result.start, result.end, result.velocity) == (expected.start, expected.end, expected.velocity)
+
+    expected = find_best_climb_section_in_fit(1_000, fit_file)
+    with ThreadPoolExecutor(max_workers=4) as executor:
+        results = list(executor.map(lambda _: find_best_climb_section_in_fit(1_000, fit_file), range(8)))
+    for result in results:
+        assert (result.start, result.end, result.climb) == (expected.start, expected.end, expected.climb)
+
+
+
+def test_other_threads_progress_during_call():
+    # the gil is released during the search, therefore another python thread keeps running
+    times = [float(i) for i in range(200_000)]
+    coordinates = [(48.0, 8.0 + i * 0.0002 + (i % 7) * 0.00001) for i in range(200_000)]
+    activity = Activity(times, coordinates)
+    # compute the cached distances before the measured call
+    activity.fastest_section(1_000)
+    ticks = []
+    done = threading.Event()
+
+    def tick():
+        while not done.is_set():
+            ticks.append(time.perf_counter())
+            time.sleep(0.001)
+
+    thread = threading.Thread(target=tick)
+    thread.start()
+    try:
+        start = time.perf_counter()
+        activity.best_efforts([float(d) for d in range(1_000, 31_000, 1_000)])
+        end = time.perf_counter()
+    finally:
+        done.set()
+        thread.join()
+    # a thread switch right before or after the call adds ticks at the borders of the call only
+    quarter = (end - start) / 4
+    assert any(start + quarter < t < end - quarter for t in ticks)
@@ -0,0 +1,48 @@
+import math
+
+from sportgems import (
+    check_times,
+    repair_times,
+    split_segments,
+    find_fastest_section_from_distances,
+    NoSectionFoundException,
+)
+
+import pytest
+
+
+def test_check_times():
+    times = [10., 11., 11., 12., 9., 13., 500., 501., 2., 3.]
+    assert check_times(times) == {
+        "duplicates": [2],
+        "out_of_order": [4],
+        "clock_resets": [8],
+        "gaps": [6],
+    }
+    assert check_times(times, math.inf)["gaps"] == []
+    with pytest.raises(ValueError):
+        check_times(times, 0.)
+
+
+def test_repair_times():
+    repaired = repair_times([10., 11., 11., 12., 9., 13.])
+    assert math.isnan(repaired[2]) and math.isnan(repaired[4])
+    assert repaired[5] == 13.
+
+
+def test_split_segments():
+    times = [0., 1., 2., 600., 601., 5., 6.]
+    assert split_segments(times) == [(0, 3), (3, 5), (5, 7)]
+    assert split_segments(times, max_gap=math.inf) == [(0, 5), (5, 7)]
+
+
+def test_fastest_section_segment_gap():
+    # 3 m/s with the device switched off for 10 minutes after 500 seconds
+    times = [float(t if t < 500 else t + 600) for t in range(1_000)]
+    distances = [3. * t for t in range(1_000)]
+    with pytest.raises(NoSectionFoundException):
+        find_fastest_section_from_distances(2_000, times, distances)
+    result = find_fastest_section_from_distances(2_000, times, distances, segment_gap=math.inf)
+    assert result.start < 500 < result.end
+    with pytest.raises(ValueError):
+        find_fastest_section_from_distances(1_000, times, distances, segment_gap=-1.)
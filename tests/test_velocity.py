@@ -1,4 +1,13 @@
-from sportgems import find_fastest_section, find_fastest_section_in_fit, DistanceTooSmallException
+from sportgems import (
+    find_fastest_section,
+    find_fastest_section_from_distances,
+    find_fastest_section_in_fit,
+    parse_fit_data,
+    DistanceTooSmallException,
+    InconsistentLengthException,
+)
+
+import math
 
 import pytest
 
@@ -40,3 +49,116 @@ def test_find_fastest_section_in_fit(fit_file):
     # test fastest 10km
     with pytest.raises(DistanceTooSmallException, match="Distance of provided input data is too small for requested desired distance."):
         result = find_fastest_section_in_fit(10_000, fit_file)
+
+
+def test_find_fastest_section__details(fit_file, track):
+    result = find_fastest_section_in_fit(1_000, fit_file)
+    assert 990 <= result.distance <= 1_010
+    assert result.duration == result.end_time - result.start_time
+    assert round(result.distance / result.duration, 3) == round(result.velocity, 3)
+    assert round(result.pace, 2) == round(1_000 / 60 / result.velocity, 2)
+    assert result.elevation_gain > 0
+    assert len(result.start_coordinate) == 2
+    assert len(result.end_coordinate) == 2
+
+    # elevation gain is unknown when searching without altitudes
+    result = find_fastest_section(1_000, track.times, track.coordinates)
+    assert result.elevation_gain is None
+    assert result.start_time == track.times[result.start]
+    assert result.end_coordinate == tuple(track.coordinates[result.end])
+
+
+def test_find_fastest_section__distance_model(fit_file, track):
+    spherical = find_fastest_section_in_fit(1_000, fit_file)
+    assert find_fastest_section_in_fit(1_000, fit_file, distance_model="spherical") == spherical
+    for distance_model in ["haversine", "equirectangular", "geodesic"]:
+        result = find_fastest_section_in_fit(1_000, fit_file, distance_model=distance_model)
+        assert abs(result.velocity - spherical.velocity) / spherical.velocity < 0.01
+
+    result = find_fastest_section(1_000, track.times, track.coordinates, distance_model="geodesic")
+    assert 990 <= result.distance <= 1_010
+
+    with pytest.raises(ValueError, match="Unknown distance model 'vincenty'"):
+        find_fastest_section_in_fit(1_000, fit_file, distance_model="vincenty")
+
+
+def test_find_fastest_section__slope_distance():
+    # steep climb with about 74 m on the map and the same altitude gain between the records
+    coordinates = [(48.0, 8.0 + i * 0.001) for i in range(30)]
+    times = [i * 10.0 for i in range(30)]
+    altitudes = [i * 74.5 for i in range(30)]
+    flat = find_fastest_section(1_000, times, coordinates, 0.1)
+    slope = find_fastest_section(1_000, times, coordinates, 0.1, slope_distance=True, altitudes=altitudes)
+    assert slope.end - slope.start < flat.end - flat.start
+    assert slope.elevation_gain > 0
+    # without altitudes the distance on the map is used
+    assert find_fastest_section(1_000, times, coordinates, 0.1, slope_distance=True) == flat
+
+
+def test_find_fastest_section__smoothing(fit_file):
+    # straight track with 3 m/s and a gps error of 4 m alternating to both sides
+    coordinates = [(48.0 + (1 if i % 2 else -1) * 4 / 111_195, 8.0 + i * 3 / 74_403) for i in range(600)]
+    times = [float(i) for i in range(600)]
+    raw = find_fastest_section(1_000, times, coordinates, 0.1)
+    for smoothing in ["moving-average", "savitzky-golay:9:2", "kalman"]:
+        smoothed = find_fastest_section(1_000, times, coordinates, 0.1, smoothing=smoothing)
+        assert smoothed.velocity < raw.velocity
+        assert smoothed.start_coordinate == coordinates[smoothed.start]
+    fit_smoothed = find_fastest_section_in_fit(1_000, fit_file, smoothing="kalman")
+    assert fit_smoothed.start > 0
+
+    with pytest.raises(ValueError, match="Unknown smoothing 'lowess'"):
+        find_fastest_section_in_fit(1_000, fit_file, smoothing="lowess")
+    with pytest.raises(ValueError, match="Invalid parameters"):
+        find_fastest_section(1_000, times, coordinates, smoothing="savitzky-golay:8")
+
+
+def test_find_fastest_section__fill(fit_file):
+    # about 3 m/s with a gps dropout of 20 seconds, forward filled it turns into a jump of 60 m
+    coordinates = [(48.0, 8.0 + i * 4e-5) for i in range(300)]
+    coordinates[140:160] = [(math.nan, math.nan)] * 20
+    times = [float(i) for i in range(300)]
+    assert find_fastest_section(60, times, coordinates, 0.1).velocity > 20
+    assert find_fastest_section(60, times, coordinates, 0.1, fill="linear").velocity < 3
+    # the dropout is longer than the maximum gap and stays missing, it adds no distance
+    assert find_fastest_section(60, times, coordinates, 0.1, fill="linear:10").velocity < 3
+    assert find_fastest_section_in_fit(1_000, fit_file, fill="linear").start > 0
+
+    with pytest.raises(ValueError, match="Unknown fill strategy 'spline'"):
+        find_fastest_section_in_fit(1_000, fit_file, fill="spline")
+
+
+def test_find_fastest_section__moving_time(fit_file):
+    # 3.3 m/s with a stop of one minute at a red light, then 2.8 m/s
+    speeds = [3.3] * 100 + [0.0] * 60 + [3.3] * 100 + [2.8] * 300
+    coordinates = [(48.0, 8.0)]
+    for speed in speeds[:-1]:
+        coordinates.append((48.0, coordinates[-1][1] + speed / 74_488))
+    times = [float(i) for i in range(len(speeds))]
+    elapsed = find_fastest_section(500, times, coordinates, 0.05)
+    assert elapsed.start >= 159
+    stationary = find_fastest_section(500, times, coordinates, 0.05, moving_time="stationary")
+    assert stationary.start < 100 < 160 < stationary.end
+    assert stationary.velocity > elapsed.velocity
+    assert stationary.duration > 200
+    # the timer of the activity is never stopped
+    assert find_fastest_section_in_fit(1_000, fit_file, moving_time="timer") == find_fastest_section_in_fit(1_000, fit_file)
+
+    with pytest.raises(ValueError, match="Unknown moving time 'moving'"):
+        find_fastest_section_in_fit(1_000, fit_file, moving_time="moving")
+
+
+def test_find_fastest_section_from_distances(fit_file):
+    # e.g. an indoor run, where only the distance recorded by a footpod is known
+    fit_data = parse_fit_data(fit_file)
+    result = find_fastest_section_from_distances(1_000, fit_data.times, fit_data.distances)
+    assert abs(result.start - 628) <= 10
+    assert round(result.velocity, 1) == 2.9
+    assert math.isnan(result.start_coordinate[0])
+    assert result.elevation_gain is None
+
+    result = find_fastest_section_from_distances(1_000, fit_data.times, fit_data.distances, altitudes=fit_data.altitudes)
+    assert result.elevation_gain is not None
+
+    with pytest.raises(InconsistentLengthException):
+        find_fastest_section_from_distances(1_000, [1.0, 2.0], [0.0])